@@ -0,0 +1,1505 @@
+use crate::cache::Cache;
+use crate::cfg::{CfgSet, Features, Target};
+use crate::checks;
+use crate::collect::Collector;
+use crate::expand;
+use crate::finding::{self, CrateKind, Finding, ParseError, Severity, Upstream};
+use crate::ignore::Ignores;
+use crate::modules::{ModuleTree, Placement, Visibility};
+use crate::prefilter::{self, Prefilter};
+use crate::rules::{ItemCtx, Rule, RuleRegistry};
+use crate::rustdoc::{self, DocType};
+use crate::scene;
+use crate::types::{Collected, TypeInfo};
+use crate::versions;
+use cargo_metadata::semver::Version;
+use cargo_metadata::{Metadata, Package, TargetKind};
+use clap::ValueEnum;
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use rayon::prelude::*;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+use syn::parse_file;
+use walkdir::{DirEntry, WalkDir};
+
+/// What to scan and which checks to run. The defaults match those of the command line tool.
+#[derive(Debug, Clone)]
+pub struct AnalysisConfig {
+    /// Source directory to scan instead of the `src` directories of all workspace members.
+    pub root: Option<String>,
+    /// Source trees to scan as crates of their own instead of the workspace, e.g. a vendored
+    /// checkout. A directory with a `Cargo.toml` stands for its `src` directory.
+    pub paths: Vec<String>,
+    /// Crate name for the sources in `paths` instead of the package name from their
+    /// `Cargo.toml` or the name of their directory.
+    pub crate_name: Option<String>,
+    /// Crate root file for the sources in `paths` instead of their `lib.rs` or `main.rs`.
+    pub crate_root: Option<PathBuf>,
+    /// Only dependencies whose package name matches are scanned.
+    pub deps: GlobSet,
+    /// Don't scan any dependencies, only the current project.
+    pub no_deps: bool,
+    /// Scan only the package of this name from the dependency graph instead of the workspace
+    /// and its dependencies, e.g. to audit an upstream crate.
+    pub package: Option<String>,
+    /// Opt-in checks to enable.
+    pub rules: Vec<String>,
+    /// Checks to disable.
+    pub disabled_rules: Vec<String>,
+    /// Severities to report checks with instead of their default one.
+    pub severities: HashMap<String, Severity>,
+    /// Version of `bevy_reflect` to adjust the checks to instead of the one cargo resolved, e.g.
+    /// for source trees without metadata.
+    pub bevy_version: Option<Version>,
+    /// Fully qualified type paths that are meant to have `#[reflect(from_reflect = false)]`, which
+    /// `from-reflect-disabled` leaves alone.
+    pub allow_from_reflect_false: GlobSet,
+    /// Fully qualified type paths whose findings are errors whatever the severity of their
+    /// check, e.g. the types of saved games.
+    pub persistence_critical: GlobSet,
+    /// Source files to skip, relative to the workspace root.
+    pub ignore_paths: GlobSet,
+    /// Directories to skip wherever they are, by name.
+    pub exclude_dirs: GlobSet,
+    /// Scan the examples of workspace members, each as a crate of its own.
+    pub include_examples: bool,
+    /// Scan the integration tests of workspace members, each as a crate of its own.
+    pub include_tests: bool,
+    /// Scan the benchmarks of workspace members, each as a crate of its own.
+    pub include_benches: bool,
+    /// Scan the libraries of workspace members with their macros expanded by rustc, to see the
+    /// types that macros generate. Findings then point into the expanded code.
+    pub expand: bool,
+    /// Where the visibility, re-exports and trait impls of the workspace members' types come
+    /// from.
+    pub backend: Backend,
+    /// Fully qualified type paths to leave out of the results.
+    pub ignore_types: GlobSet,
+    /// Derive macros that stand for a trait of another name, by name or full path, e.g.
+    /// `"my_macros::ReflectComponent"` to `"Component"`.
+    pub derive_aliases: BTreeMap<String, String>,
+    /// Only types that are visible at least this far are checked.
+    pub visibility: Visibility,
+    /// Features to evaluate `#[cfg(...)]` with in addition to the default features, with
+    /// `package/feature` for dependencies.
+    pub features: Vec<String>,
+    /// Treat all features of all crates as enabled.
+    pub all_features: bool,
+    /// Don't enable the default features of the current project.
+    pub no_default_features: bool,
+    /// Target to evaluate `#[cfg(...)]` for.
+    pub target: Target,
+    /// Read and write the analysis cache in `target/reflect-check`.
+    pub use_cache: bool,
+    /// Keep the cargo commands that `expand` and the rustdoc backend run off the network.
+    pub offline: bool,
+}
+
+impl Default for AnalysisConfig {
+    fn default() -> Self {
+        Self {
+            root: None,
+            paths: Vec::new(),
+            crate_name: None,
+            crate_root: None,
+            deps: GlobSetBuilder::new()
+                .add(Glob::new("bevy_*").expect("valid glob"))
+                .build()
+                .expect("valid glob set"),
+            no_deps: false,
+            package: None,
+            rules: Vec::new(),
+            disabled_rules: Vec::new(),
+            severities: HashMap::new(),
+            bevy_version: None,
+            allow_from_reflect_false: GlobSet::empty(),
+            persistence_critical: GlobSet::empty(),
+            ignore_paths: GlobSet::empty(),
+            exclude_dirs: GlobSet::empty(),
+            include_examples: false,
+            include_tests: false,
+            include_benches: false,
+            expand: false,
+            backend: Backend::default(),
+            ignore_types: GlobSet::empty(),
+            derive_aliases: BTreeMap::new(),
+            visibility: Visibility::Public,
+            features: Vec::new(),
+            all_features: false,
+            no_default_features: false,
+            target: Target::host(),
+            use_cache: true,
+            offline: false,
+        }
+    }
+}
+
+/// Source of the type information of workspace members.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Backend {
+    /// Parse the sources and resolve modules, `use`s and impls by name.
+    #[default]
+    Syn,
+    /// Additionally document the libraries with rustdoc's JSON output, for the exact visibility,
+    /// public paths and trait impls of their types. Needs the code to compile.
+    RustdocJson,
+}
+
+/// The result of an analysis.
+#[derive(Debug, Default)]
+pub struct Analysis {
+    /// All structs and enums that passed the visibility and `ignore_types` filters.
+    pub types: Vec<TypeInfo>,
+    /// Problems found by the enabled checks, minus suppressed ones.
+    pub findings: Vec<Finding>,
+    /// Problems that didn't stop the analysis, like a cache that couldn't be written.
+    pub warnings: Vec<String>,
+    /// Files that were skipped because they couldn't be parsed, which can hide findings.
+    pub parse_errors: Vec<ParseError>,
+    /// How long the stages of the analysis and each rule took.
+    pub timings: Timings,
+}
+
+/// Where the time of an analysis went.
+#[derive(Debug, Default, Clone)]
+pub struct Timings {
+    /// The stages in the order they ran, like `parse` or `checks`, with how long they took.
+    pub stages: Vec<(&'static str, Duration)>,
+    /// How long each enabled rule took over all types, by id.
+    pub rules: Vec<(&'static str, Duration)>,
+    /// Source files that were scanned.
+    pub files: usize,
+    /// Crates the source files belong to.
+    pub crates: usize,
+}
+
+impl Timings {
+    // Record the time since `started` as the stage `name`, and start the next one
+    fn lap(&mut self, name: &'static str, started: &mut Instant) {
+        self.stages.push((name, started.elapsed()));
+        *started = Instant::now();
+    }
+}
+
+/// A step of the analysis, for showing progress while it runs.
+#[derive(Debug, Clone, Copy)]
+pub enum Progress<'a> {
+    /// The source files to parse were collected.
+    Started { files: usize, crates: usize },
+    /// A file was parsed or taken from the cache.
+    FileParsed { path: &'a str },
+    /// All files of a crate were parsed.
+    CrateParsed { name: &'a str, files: usize },
+    /// The checks ran.
+    Finished { findings: usize },
+}
+
+/// Scans a cargo project and its dependencies for reflection problems.
+pub struct Analyzer {
+    config: AnalysisConfig,
+    metadata: Option<Metadata>,
+    rules: RuleRegistry,
+    on_progress: Option<ProgressCallback>,
+}
+
+type ProgressCallback = Box<dyn Fn(Progress<'_>) + Send + Sync>;
+
+impl fmt::Debug for Analyzer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Analyzer")
+            .field("config", &self.config)
+            .field("metadata", &self.metadata)
+            .field("rules", &self.rules)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Analyzer {
+    /// `metadata` describes the project to scan, usually from `cargo_metadata::MetadataCommand`.
+    pub fn new(config: AnalysisConfig, metadata: Metadata) -> Self {
+        Self {
+            config,
+            metadata: Some(metadata),
+            rules: RuleRegistry::default(),
+            on_progress: None,
+        }
+    }
+
+    /// Scan only the source trees in `config.paths`, without a cargo project around them.
+    pub fn without_metadata(config: AnalysisConfig) -> Self {
+        Self {
+            config,
+            metadata: None,
+            rules: RuleRegistry::default(),
+            on_progress: None,
+        }
+    }
+
+    /// Run `rule` along with the built-in checks, or instead of the built-in check with the same
+    /// id. Like those, it can be disabled, requested or given a severity by its id.
+    pub fn register_rule(mut self, rule: impl Rule + 'static) -> Self {
+        self.rules.register(rule);
+        self
+    }
+
+    /// Call `callback` as the analysis progresses. It's called from multiple threads.
+    pub fn on_progress(mut self, callback: impl Fn(Progress<'_>) + Send + Sync + 'static) -> Self {
+        self.on_progress = Some(Box::new(callback));
+        self
+    }
+
+    /// Parse all source files, put their types into their modules and run the enabled checks.
+    pub fn analyze(&self) -> Analysis {
+        let config = &self.config;
+        let metadata = self.metadata.as_ref();
+        let packages = metadata.map_or(&[][..], |metadata| &metadata.packages);
+        let mut warnings = Vec::new();
+        let mut timings = Timings::default();
+        let mut stage = Instant::now();
+        let trees = source_trees(config);
+        let targets = metadata.map_or_else(Vec::new, |metadata| extra_targets(metadata, config));
+
+        // Collect all source files from the current project and dependencies
+        let mut source_files = Vec::new();
+        if let Some(name) = &config.package {
+            match packages
+                .iter()
+                .find(|package| package.name == *name)
+                .and_then(|package| package.manifest_path.parent())
+            {
+                Some(dir) => {
+                    collect_package_files(dir.as_str(), &config.exclude_dirs, &mut source_files)
+                }
+                None => warnings.push(format!(
+                    "There's no package `{name}` in the dependency graph"
+                )),
+            }
+        } else if !trees.is_empty() {
+            for tree in &trees {
+                if !Path::new(&tree.path).is_dir() {
+                    warnings.push(format!("Source directory {} doesn't exist", tree.path));
+                }
+                collect_source_files(&tree.path, &config.exclude_dirs, &mut source_files);
+            }
+        } else if let Some(root) = &config.root {
+            collect_source_files(root, &config.exclude_dirs, &mut source_files);
+        } else if let Some(metadata) = metadata {
+            collect_workspace_files(metadata, &config.exclude_dirs, &mut source_files);
+        }
+        for target in targets
+            .iter()
+            .filter(|target| !target.is_bin && config.package.is_none())
+        {
+            match &target.dir {
+                Some(dir) => collect_source_files(
+                    &relative_to_current_dir(dir).to_string_lossy(),
+                    &config.exclude_dirs,
+                    &mut source_files,
+                ),
+                None => source_files.push(
+                    relative_to_current_dir(&target.root_file)
+                        .to_string_lossy()
+                        .into_owned(),
+                ),
+            }
+        }
+        if !config.no_deps
+            && config.package.is_none()
+            && let Some(metadata) = metadata
+        {
+            collect_dependency_files(metadata, config, &mut source_files, &mut warnings); // Dependencies
+        }
+        source_files.retain(|path| !self.is_path_ignored(path));
+        // A file can be reached more than once, e.g. through a path dependency that's also a
+        // workspace member
+        let mut seen = HashSet::new();
+        source_files.retain(|path| seen.insert(canonical_path(Path::new(path))));
+        timings.lap("collect files", &mut stage);
+
+        // Libraries whose macros were expanded are scanned from the expansion instead of their
+        // sources
+        let expanded = match metadata {
+            Some(metadata) if config.expand && config.package.is_none() => {
+                let expanded = self.expand_workspace(metadata, &mut warnings);
+                timings.lap("expand macros", &mut stage);
+                expanded
+            }
+            _ => Vec::new(),
+        };
+        source_files.extend(
+            expanded
+                .iter()
+                .map(|(_, file)| file.to_string_lossy().into_owned()),
+        );
+
+        let cache = config.use_cache.then(|| {
+            let target_directory = metadata.map_or(Path::new("target"), |metadata| {
+                metadata.target_directory.as_std_path()
+            });
+            Cache::load(&target_directory.join("reflect-check"))
+        });
+
+        // What `#[cfg(...)]`s evaluate to for each crate, `None` is the current project. Source
+        // trees count as the current project.
+        let root_package = metadata.and_then(Metadata::root_package);
+        let mut cfgs: HashMap<Option<String>, CfgSet> = packages
+            .iter()
+            .map(|package| {
+                let is_root = root_package.is_some_and(|root| root.id == package.id);
+                (
+                    Some(crate_name(package)),
+                    self.cfg_for(Some(package), is_root),
+                )
+            })
+            .collect();
+        cfgs.insert(None, self.cfg_for(root_package, true));
+        for tree in &trees {
+            cfgs.insert(Some(tree.crate_name.clone()), self.cfg_for(None, true));
+        }
+        for target in &targets {
+            let is_root = root_package.is_some_and(|root| root.id == target.package.id);
+            cfgs.insert(
+                Some(target.name.clone()),
+                self.cfg_for(Some(target.package), is_root),
+            );
+        }
+
+        // The directories files are attributed to crates by
+        let crate_dirs: Vec<CrateDir> = expanded
+            .iter()
+            .filter_map(|(name, file)| {
+                let dir = canonical_path(file)?;
+                Some(CrateDir {
+                    module_root: dir.clone(),
+                    dir,
+                    name: name.clone(),
+                })
+            })
+            .chain(trees.iter().filter_map(|tree| {
+                let dir = canonical_path(Path::new(&tree.path))?;
+                Some(CrateDir {
+                    module_root: dir.clone(),
+                    dir,
+                    name: tree.crate_name.clone(),
+                })
+            }))
+            .chain(targets.iter().filter_map(|target| {
+                let dir = canonical_path(target.dir.as_ref().unwrap_or(&target.root_file))?;
+                Some(CrateDir {
+                    module_root: dir.clone(),
+                    dir,
+                    name: target.name.clone(),
+                })
+            }))
+            .chain(packages.iter().filter_map(package_crate_dir))
+            .collect();
+
+        let mut crate_roots = crate_roots(config.root.as_deref(), metadata, &trees, &targets);
+        crate_roots.extend(
+            expanded
+                .iter()
+                .map(|(name, file)| (file.clone(), name.clone())),
+        );
+        let root_files: HashSet<PathBuf> = crate_roots
+            .iter()
+            .filter_map(|(path, _)| fs::canonicalize(path).ok())
+            .collect();
+
+        // Files left to parse per crate, to tell when a crate is done
+        let source_files: Vec<(&str, Option<String>)> = source_files
+            .iter()
+            .map(|path| {
+                (
+                    path.as_str(),
+                    crate_dir_for_file(Path::new(path), &crate_dirs)
+                        .map(|crate_dir| crate_dir.name.clone()),
+                )
+            })
+            .filter(|(path, crate_name)| {
+                // The expansion stands for the library, but a `main.rs` next to it is a crate of
+                // its own with the same name
+                let is_expanded =
+                    |name: &String| expanded.iter().any(|(crate_name, _)| crate_name == name);
+                let path = Path::new(path);
+                !crate_name.as_ref().is_some_and(is_expanded)
+                    || expanded.iter().any(|(_, file)| file == path)
+                    || (path.file_name() == Some("main.rs".as_ref())
+                        && fs::canonicalize(path).is_ok_and(|path| root_files.contains(&path)))
+            })
+            .collect();
+        let mut crate_files: HashMap<Option<&str>, usize> = HashMap::new();
+        for (_, crate_name) in &source_files {
+            *crate_files.entry(crate_name.as_deref()).or_default() += 1;
+        }
+        let remaining: HashMap<Option<&str>, AtomicUsize> = crate_files
+            .iter()
+            .map(|(crate_name, files)| (*crate_name, AtomicUsize::new(*files)))
+            .collect();
+        self.progress(Progress::Started {
+            files: source_files.len(),
+            crates: crate_files.len(),
+        });
+        timings.files = source_files.len();
+        timings.crates = crate_files.len();
+
+        // Files outside of any package come from `root`, which counts as part of the workspace,
+        // and so do the source trees and the targets of workspace members
+        let members: HashSet<String> = metadata
+            .map(Metadata::workspace_packages)
+            .unwrap_or_default()
+            .into_iter()
+            .map(crate_name)
+            .chain(trees.iter().map(|tree| tree.crate_name.clone()))
+            .chain(targets.iter().map(|target| target.name.clone()))
+            .collect();
+        // Only the dependencies' files are reduced to their outline: the workspace's may use
+        // instances of generic types without mentioning anything else that's relevant
+        let prefilter = Prefilter::new(
+            config
+                .derive_aliases
+                .keys()
+                .filter_map(|alias| alias.rsplit("::").next()),
+        );
+        // Parse the files and track collected types with fully qualified paths. Syntax trees
+        // can't be sent between threads, so each file is parsed and analyzed on the same worker,
+        // and dropped right after. Only one tree per worker is alive at a time, whatever the size
+        // of the workspace; what's kept are the much smaller collected types.
+        let mut collected = source_files
+            .par_iter()
+            .map(|(path, crate_name)| {
+                let is_dependency = crate_name
+                    .as_ref()
+                    .is_some_and(|name| !members.contains(name.as_str()));
+                let collected = analyze_file(
+                    path,
+                    crate_name.as_deref(),
+                    &cfgs,
+                    is_dependency.then_some(&prefilter),
+                    &root_files,
+                    &config.derive_aliases,
+                    cache.as_ref(),
+                );
+                self.progress(Progress::FileParsed { path });
+                let crate_name = crate_name.as_deref();
+                if remaining[&crate_name].fetch_sub(1, Ordering::Relaxed) == 1 {
+                    self.progress(Progress::CrateParsed {
+                        name: crate_name.unwrap_or("current project"),
+                        files: crate_files[&crate_name],
+                    });
+                }
+                collected
+            })
+            .reduce(Collected::default, Collected::merge);
+        let expanded_crates = expanded
+            .iter()
+            .map(|(name, _)| Some(name.clone()))
+            .collect();
+        collected.derives_from_impls(&expanded_crates);
+        if let Some(cache) = cache
+            && let Err(err) = cache.save()
+        {
+            warnings.push(format!("Failed to write cache: {err}"));
+        }
+        // Stitch the files together into the crates' module trees. Files that aren't reachable
+        // from a crate root (and aren't configured out) get a module path based on their location.
+        let mut parse_errors = std::mem::take(&mut collected.parse_errors);
+        parse_errors.sort_by(|a, b| a.file.cmp(&b.file));
+        timings.lap("parse", &mut stage);
+
+        // Rustdoc decides the visibility of the types it documents, so they're only filtered
+        // afterwards
+        let documented = match metadata {
+            Some(metadata) if config.backend == Backend::RustdocJson => {
+                let documented = self.document_workspace(metadata, &mut warnings);
+                timings.lap("document", &mut stage);
+                documented
+            }
+            _ => HashMap::new(),
+        };
+        let module_tree = ModuleTree::build(&crate_roots, &collected.mods);
+        let mut types = collected.finish(
+            |file| {
+                if let Some(placement) = module_tree.placement(file) {
+                    Some(placement.clone())
+                } else if module_tree.is_excluded(file) {
+                    None
+                } else {
+                    Some(Placement {
+                        module_path: resolve_module_path(
+                            file,
+                            config.root.as_deref(),
+                            &crate_dirs,
+                        )?,
+                        visibility: Visibility::Public,
+                    })
+                }
+            },
+            if documented.is_empty() {
+                config.visibility
+            } else {
+                Visibility::Private
+            },
+        );
+        if !documented.is_empty() {
+            let crates = documented.keys().cloned().collect();
+            let doc_types: Vec<DocType> = documented.into_values().flatten().collect();
+            rustdoc::apply(&mut types, &doc_types, &crates);
+            types.retain(|ty| ty.visibility >= config.visibility);
+        }
+        types.retain(|ty| !config.ignore_types.is_match(&ty.location.type_path));
+        timings.lap("resolve modules", &mut stage);
+
+        let bevy_version = config
+            .bevy_version
+            .clone()
+            .or_else(|| bevy_version(metadata?));
+        let enabled_rules =
+            self.rules
+                .enabled(&config.rules, &config.disabled_rules, bevy_version.as_ref());
+        let mut findings = self.check_types(
+            &types,
+            &enabled_rules,
+            |ty| match &ty.crate_name {
+                Some(name) if !members.contains(name.as_str()) => CrateKind::Dependency,
+                _ => CrateKind::Workspace,
+            },
+            &mut timings,
+        );
+        // Scene files are checked as a whole rather than type by type
+        let scene_check = &checks::UNLOADABLE_SCENE_TYPE;
+        if let Some(metadata) = metadata
+            && enabled_rules.iter().any(|rule| rule.id() == scene_check.id)
+        {
+            let severity = config
+                .severities
+                .get(scene_check.id)
+                .copied()
+                .unwrap_or(scene_check.default_severity);
+            let started = Instant::now();
+            let (scene_findings, scene_errors) =
+                scene::check_scene_dirs(&asset_dirs(metadata), &types, severity);
+            timings.rules.push((scene_check.id, started.elapsed()));
+            findings.extend(scene_findings);
+            parse_errors.extend(scene_errors);
+        }
+        // Version conflicts are a matter of the dependency graph rather than of any type
+        let versions_check = &checks::MIXED_BEVY_VERSIONS;
+        if let Some(metadata) = metadata
+            && enabled_rules
+                .iter()
+                .any(|rule| rule.id() == versions_check.id)
+        {
+            let started = Instant::now();
+            let severity = config
+                .severities
+                .get(versions_check.id)
+                .copied()
+                .unwrap_or(versions_check.default_severity);
+            findings.extend(versions::mixed_versions(metadata, severity));
+            timings.rules.push((versions_check.id, started.elapsed()));
+        }
+        self.escalate(&mut findings);
+        if let Some(metadata) = metadata {
+            annotate_upstream(&mut findings, metadata);
+        }
+        // Files are only scanned once, but a file can still be included by several crates with
+        // `#[path]`, reporting the same type under different paths
+        let mut seen = HashSet::new();
+        findings.retain(|finding| {
+            seen.insert((
+                canonical_path(Path::new(&finding.file)),
+                finding.span,
+                finding.check,
+            ))
+        });
+        finding::sort(&mut findings);
+        timings.lap("checks", &mut stage);
+        self.progress(Progress::Finished {
+            findings: findings.len(),
+        });
+
+        Analysis {
+            types,
+            findings,
+            warnings,
+            parse_errors,
+            timings,
+        }
+    }
+
+    /// Check a single file on its own, e.g. an editor's unsaved buffer: `content` stands for the
+    /// file at `path`, whose location decides its crate and module path. Nothing else is parsed,
+    /// so types and registrations in other files are unknown.
+    pub fn analyze_source(&self, path: &str, content: &str) -> Analysis {
+        let config = &self.config;
+        let metadata = self.metadata.as_ref();
+        if self.is_path_ignored(path) {
+            return Analysis::default();
+        }
+        let file = Path::new(path);
+        let absolute = canonical_path(file);
+        // Packages can lie within each other, so the innermost one wins
+        let package = metadata.and_then(|metadata| {
+            metadata
+                .packages
+                .iter()
+                .filter(|package| {
+                    let dir = package.manifest_path.parent();
+                    dir.and_then(|dir| canonical_path(dir.as_std_path()))
+                        .zip(absolute.as_ref())
+                        .is_some_and(|(dir, file)| file.starts_with(dir))
+                })
+                .max_by_key(|package| package.manifest_path.components().count())
+        });
+        let Some(crate_dir) = package
+            .and_then(package_crate_dir)
+            .or_else(|| manifest_crate_dir(file))
+        else {
+            return Analysis::default();
+        };
+        let is_crate_root = match package {
+            Some(package) => package
+                .targets
+                .iter()
+                .any(|target| canonical_path(target.src_path.as_std_path()) == absolute),
+            None => ["lib.rs", "main.rs"]
+                .iter()
+                .any(|root| absolute == Some(crate_dir.module_root.join(root))),
+        };
+        let module_path = if is_crate_root {
+            crate_dir.name.clone()
+        } else {
+            resolve_module_path(path, None, std::slice::from_ref(&crate_dir))
+                .unwrap_or_else(|| crate_dir.name.clone())
+        };
+        let is_root_package = package.is_some_and(|package| {
+            metadata
+                .and_then(Metadata::root_package)
+                .is_some_and(|root| root.id == package.id)
+        });
+        let cfg = self.cfg_for(package, package.is_none() || is_root_package);
+
+        let mut collected = collect_source(
+            path,
+            content,
+            Some(&crate_dir.name),
+            &cfg,
+            is_crate_root,
+            &config.derive_aliases,
+        );
+        let parse_errors = std::mem::take(&mut collected.parse_errors);
+        let mut types = collected.finish(
+            |_| {
+                Some(Placement {
+                    module_path: module_path.clone(),
+                    visibility: Visibility::Public,
+                })
+            },
+            config.visibility,
+        );
+        types.retain(|ty| !config.ignore_types.is_match(&ty.location.type_path));
+
+        let bevy_version = config
+            .bevy_version
+            .clone()
+            .or_else(|| bevy_version(metadata?));
+        let enabled_rules =
+            self.rules
+                .enabled(&config.rules, &config.disabled_rules, bevy_version.as_ref());
+        let mut timings = Timings {
+            files: 1,
+            crates: 1,
+            ..Timings::default()
+        };
+        let mut findings = self.check_types(
+            &types,
+            &enabled_rules,
+            |_| CrateKind::Workspace,
+            &mut timings,
+        );
+        self.escalate(&mut findings);
+        finding::sort(&mut findings);
+
+        Analysis {
+            types,
+            findings,
+            warnings: Vec::new(),
+            parse_errors,
+            timings,
+        }
+    }
+
+    // Run the enabled rules on every type, with the configured severities and allowances, and
+    // add up how long each rule takes to `timings`
+    fn check_types(
+        &self,
+        types: &[TypeInfo],
+        enabled_rules: &[&dyn Rule],
+        crate_kind: impl Fn(&TypeInfo) -> CrateKind,
+        timings: &mut Timings,
+    ) -> Vec<Finding> {
+        let config = &self.config;
+        let mut durations = vec![Duration::ZERO; enabled_rules.len()];
+        let mut findings = Vec::new();
+        for ty in types {
+            let ctx = ItemCtx {
+                ty,
+                crate_kind: crate_kind(ty),
+                types,
+            };
+            for (rule, duration) in enabled_rules.iter().zip(&mut durations) {
+                if ty.suppressions.covers(rule.id()) {
+                    continue;
+                }
+                let started = Instant::now();
+                let mut rule_findings = rule.check_item(&ctx);
+                *duration += started.elapsed();
+                if let Some(severity) = config.severities.get(rule.id()) {
+                    for finding in &mut rule_findings {
+                        finding.severity = *severity;
+                    }
+                }
+                findings.append(&mut rule_findings);
+            }
+        }
+        timings
+            .rules
+            .extend(enabled_rules.iter().map(|rule| rule.id()).zip(durations));
+        findings.retain(|finding| {
+            finding.check != checks::FROM_REFLECT_DISABLED.id
+                || !config.allow_from_reflect_false.is_match(&finding.type_path)
+        });
+        findings
+    }
+
+    // Breaking the types of saved games breaks loading them, so their findings can't wait
+    fn escalate(&self, findings: &mut [Finding]) {
+        for finding in findings {
+            let critical = std::iter::once(&finding.type_path)
+                .chain(&finding.reexports)
+                .any(|path| self.config.persistence_critical.is_match(path));
+            if critical {
+                finding.severity = Severity::Error;
+            }
+        }
+    }
+
+    fn progress(&self, progress: Progress<'_>) {
+        if let Some(on_progress) = &self.on_progress {
+            on_progress(progress);
+        }
+    }
+
+    // The configuration `#[cfg(...)]`s in `package` are evaluated against. `package` is `None`
+    // for a project outside of cargo's view, `is_root` marks the current project.
+    fn cfg_for(&self, package: Option<&Package>, is_root: bool) -> CfgSet {
+        let features = if self.config.all_features {
+            Features::All
+        } else {
+            // Cargo knows which features end up enabled in the build, across the whole
+            // dependency graph. Without a resolve (e.g. from `--no-deps` metadata), default
+            // features and requested ones are assumed.
+            let resolved = self
+                .metadata
+                .as_ref()
+                .and_then(|metadata| metadata.resolve.as_ref())
+                .zip(package)
+                .and_then(|(resolve, package)| {
+                    resolve.nodes.iter().find(|node| node.id == package.id)
+                });
+            match (package, resolved) {
+                (Some(package), Some(node)) => {
+                    Features::resolved(package, &node.features, &self.config.features)
+                }
+                (Some(package), None) => {
+                    let default_features = !(is_root && self.config.no_default_features);
+                    Features::for_package(package, is_root, &self.config.features, default_features)
+                }
+                (None, _) => Features::Some(self.config.features.iter().cloned().collect()),
+            }
+        };
+        CfgSet {
+            features,
+            target: self.config.target.clone(),
+        }
+    }
+
+    // Expand the libraries of the workspace members into `target/reflect-check/expanded`,
+    // returning their crate names and expanded files. Libraries that fail to build are scanned
+    // from their sources.
+    fn expand_workspace(
+        &self,
+        metadata: &Metadata,
+        warnings: &mut Vec<String>,
+    ) -> Vec<(String, PathBuf)> {
+        let dir = metadata
+            .target_directory
+            .as_std_path()
+            .join("reflect-check");
+        let root_package = metadata.root_package();
+        let mut expanded = Vec::new();
+        for package in metadata.workspace_packages() {
+            if lib_target(package).is_none() {
+                continue;
+            }
+            let is_root = root_package.is_some_and(|root| root.id == package.id);
+            let name = crate_name(package);
+            let file = dir.join("expanded").join(format!("{name}.rs"));
+            let written = expand::expand_lib(package, &self.config, is_root, &dir.join("target"))
+                .and_then(|source| {
+                    fs::create_dir_all(dir.join("expanded"))
+                        .and_then(|()| fs::write(&file, source))
+                        .map_err(|err| err.to_string())
+                });
+            match written {
+                Ok(()) => expanded.push((name, relative_to_current_dir(&file))),
+                Err(err) => warnings.push(format!(
+                    "Failed to expand {}, scanning its sources instead: {err}",
+                    package.name
+                )),
+            }
+        }
+        expanded
+    }
+
+    // Document the libraries of the workspace members as JSON into
+    // `target/reflect-check/target`, returning their types by crate name. Libraries that fail to
+    // build are left to the source scan.
+    fn document_workspace(
+        &self,
+        metadata: &Metadata,
+        warnings: &mut Vec<String>,
+    ) -> HashMap<String, Vec<DocType>> {
+        let target_dir = metadata
+            .target_directory
+            .as_std_path()
+            .join("reflect-check")
+            .join("target");
+        let root_package = metadata.root_package();
+        let mut documented = HashMap::new();
+        for package in metadata.workspace_packages() {
+            if lib_target(package).is_none() {
+                continue;
+            }
+            let is_root = root_package.is_some_and(|root| root.id == package.id);
+            let name = crate_name(package);
+            match rustdoc::document_lib(
+                package,
+                &name,
+                &self.config,
+                is_root,
+                &target_dir,
+                metadata.workspace_root.as_std_path(),
+            ) {
+                Ok(types) => {
+                    documented.insert(name, types);
+                }
+                Err(err) => warnings.push(format!(
+                    "Failed to document {}, falling back to its sources: {err}",
+                    package.name
+                )),
+            }
+        }
+        documented
+    }
+
+    // Whether a source file is excluded via `ignore_paths`
+    fn is_path_ignored(&self, path: &str) -> bool {
+        let path = Path::new(path);
+        let relative = self
+            .metadata
+            .as_ref()
+            .and_then(|metadata| path.strip_prefix(&metadata.workspace_root).ok())
+            .or_else(|| path.strip_prefix(".").ok())
+            .unwrap_or(path);
+        self.config.ignore_paths.is_match(relative)
+    }
+}
+
+// Parse a single file and collect its types, or take them from the cache if it's unchanged.
+// Of files that `prefilter` finds nothing relevant in, only the outline is parsed.
+fn analyze_file(
+    path: &str,
+    crate_name: Option<&str>,
+    cfgs: &HashMap<Option<String>, CfgSet>,
+    prefilter: Option<&Prefilter>,
+    root_files: &HashSet<PathBuf>,
+    derive_aliases: &BTreeMap<String, String>,
+    cache: Option<&Cache>,
+) -> Collected {
+    let Ok(mut content) = fs::read_to_string(path) else {
+        return Collected::default();
+    };
+    let outlined = prefilter.is_some_and(|prefilter| !prefilter.is_relevant(&content));
+    if outlined {
+        content = prefilter::outline(&content);
+    }
+    let crate_name = crate_name.map(str::to_string);
+    let Some(cfg) = cfgs.get(&crate_name) else {
+        return Collected::default();
+    };
+
+    let is_crate_root = fs::canonicalize(path).is_ok_and(|path| root_files.contains(&path));
+
+    let key = cache.map(|_| {
+        let aliases = derive_aliases
+            .iter()
+            .map(|(alias, name)| format!("{alias}={name}"))
+            .collect::<Vec<_>>()
+            .join(",");
+        let context = [
+            path,
+            crate_name.as_deref().unwrap_or_default(),
+            if is_crate_root { "root" } else { "" },
+            &cfg.cache_key(),
+            &aliases,
+        ];
+        Cache::key(&content, &context)
+    });
+    if let (Some(cache), Some(key)) = (cache, &key)
+        && let Some(collected) = cache.get(key)
+    {
+        return collected;
+    }
+
+    let mut collected = collect_source(
+        path,
+        &content,
+        crate_name.as_deref(),
+        cfg,
+        is_crate_root,
+        derive_aliases,
+    );
+    // Positions in the outline don't point anywhere in the file
+    if outlined {
+        collected.parse_errors.clear();
+    }
+    if let (Some(cache), Some(key)) = (cache, key) {
+        cache.insert(key, &collected);
+    }
+    collected
+}
+
+// Collect the types and registrations of one file's content
+fn collect_source(
+    path: &str,
+    content: &str,
+    crate_name: Option<&str>,
+    cfg: &CfgSet,
+    is_crate_root: bool,
+    derive_aliases: &BTreeMap<String, String>,
+) -> Collected {
+    let mut collector = Collector::new(
+        cfg,
+        content,
+        path,
+        crate_name,
+        is_crate_root,
+        derive_aliases,
+    );
+    let parse_error = match parse_file(content) {
+        Ok(syntax) => {
+            collector.collect_types(&syntax, &[], Visibility::Public);
+            collector.collect_registrations(&syntax);
+            None
+        }
+        Err(err) => Some(ParseError {
+            file: path.to_string(),
+            message: err.to_string(),
+            span: err.span().into(),
+        }),
+    };
+    let mut collected = collector.into_collected();
+    collected.parse_errors.extend(parse_error);
+    collected
+}
+
+// Where the sources of a crate are
+struct CrateDir {
+    /// Files below it belong to the crate.
+    dir: PathBuf,
+    /// The directory of the crate root, which module paths start from.
+    module_root: PathBuf,
+    name: String,
+}
+
+// A directory from `paths`, scanned as a crate of its own
+struct SourceTree {
+    /// The directory with the sources, as given or the `src` next to a `Cargo.toml`.
+    path: String,
+    crate_name: String,
+    root_files: Vec<PathBuf>,
+}
+
+// A binary, example, integration test or benchmark of a workspace member, which is a crate of its
+// own
+struct ExtraTarget<'a> {
+    package: &'a Package,
+    /// The target's name, which is its crate name.
+    name: String,
+    root_file: PathBuf,
+    /// The directory of a target made of several files, like `examples/foo/main.rs`.
+    dir: Option<PathBuf>,
+    /// Binaries are in `src/bin`, which is scanned with the package, the other targets' files
+    /// have to be collected.
+    is_bin: bool,
+}
+
+// The binaries of workspace members besides `src/main.rs`, which is the package's crate, and
+// the examples, tests and benchmarks that `config` includes
+fn extra_targets<'a>(metadata: &'a Metadata, config: &AnalysisConfig) -> Vec<ExtraTarget<'a>> {
+    let kinds = [
+        (config.include_examples, TargetKind::Example),
+        (config.include_tests, TargetKind::Test),
+        (config.include_benches, TargetKind::Bench),
+        (true, TargetKind::Bin),
+    ];
+    let mut targets = Vec::new();
+    for package in metadata.workspace_packages() {
+        let main = package
+            .manifest_path
+            .parent()
+            .map(|dir| dir.join("src").join("main.rs"));
+        for target in &package.targets {
+            if main.as_ref() == Some(&target.src_path)
+                || !kinds
+                    .iter()
+                    .any(|(included, kind)| *included && target.is_kind(kind.clone()))
+            {
+                continue;
+            }
+            let root_file = target.src_path.as_std_path().to_path_buf();
+            let dir = (root_file.file_name() == Some("main.rs".as_ref()))
+                .then(|| root_file.parent().map(Path::to_path_buf))
+                .flatten();
+            targets.push(ExtraTarget {
+                package,
+                name: target.name.replace('-', "_"),
+                root_file,
+                dir,
+                is_bin: target.is_kind(TargetKind::Bin),
+            });
+        }
+    }
+    targets
+}
+
+// Find out where the sources in `paths` start and which crates they make up
+fn source_trees(config: &AnalysisConfig) -> Vec<SourceTree> {
+    config
+        .paths
+        .iter()
+        .map(|path| {
+            let mut dir = PathBuf::from(path);
+            let mut manifest = dir.join("Cargo.toml");
+            if manifest.is_file() {
+                dir = dir.join("src");
+            } else if let Some(parent) = dir.parent() {
+                manifest = parent.join("Cargo.toml");
+            }
+            let crate_name = config
+                .crate_name
+                .clone()
+                .or_else(|| package_name(&manifest))
+                .or_else(|| {
+                    // A `src` directory is named after its package
+                    let absolute = std::path::absolute(&dir).ok()?;
+                    let named = if absolute.ends_with("src") {
+                        absolute.parent()?
+                    } else {
+                        &absolute
+                    };
+                    Some(named.file_name()?.to_string_lossy().into_owned())
+                })
+                .map_or_else(|| "crate".to_string(), |name| name.replace('-', "_"));
+            let root_files = match &config.crate_root {
+                Some(crate_root) => vec![crate_root.clone()],
+                None => vec![dir.join("lib.rs"), dir.join("main.rs")],
+            };
+            SourceTree {
+                path: dir.to_string_lossy().into_owned(),
+                crate_name,
+                root_files,
+            }
+        })
+        .collect()
+}
+
+// Where the sources of a package are
+fn package_crate_dir(package: &Package) -> Option<CrateDir> {
+    let dir = canonical_path(package.manifest_path.parent()?.as_std_path())?;
+    let module_root = match lib_target(package) {
+        Some(lib) => canonical_path(lib.src_path.parent()?.as_std_path())?,
+        None => dir.join("src"),
+    };
+    Some(CrateDir {
+        dir,
+        module_root,
+        name: crate_name(package),
+    })
+}
+
+// The name a package's library goes by in paths, which has underscores for the dashes of the
+// package name unless `[lib] name` sets another one
+fn crate_name(package: &Package) -> String {
+    lib_target(package)
+        .map_or(&package.name, |target| &target.name)
+        .replace('-', "_")
+}
+
+// Tell the findings in dependencies which package they're in, for reporting them upstream.
+// Several versions of a crate can be in the graph, so the package is the one with the file.
+fn annotate_upstream(findings: &mut [Finding], metadata: &Metadata) {
+    let mut by_crate: HashMap<String, Vec<&Package>> = HashMap::new();
+    for package in &metadata.packages {
+        if !metadata.workspace_members.contains(&package.id) {
+            by_crate
+                .entry(crate_name(package))
+                .or_default()
+                .push(package);
+        }
+    }
+    for finding in findings
+        .iter_mut()
+        .filter(|finding| finding.crate_kind == CrateKind::Dependency)
+    {
+        let crate_name = finding.type_path.split("::").next().unwrap_or_default();
+        let file = Path::new(&finding.file);
+        finding.upstream = by_crate
+            .get(crate_name)
+            .into_iter()
+            .flatten()
+            .find(|package| {
+                package
+                    .manifest_path
+                    .parent()
+                    .is_some_and(|dir| file.starts_with(dir))
+            })
+            .map(|package| upstream(package));
+    }
+}
+
+fn upstream(package: &Package) -> Upstream {
+    let source = match &package.source {
+        None => "path".to_string(),
+        Some(source) if source.is_crates_io() => "crates.io".to_string(),
+        Some(source) => source.repr.clone(),
+    };
+    Upstream {
+        package: package.name.clone(),
+        version: package.version.to_string(),
+        source,
+        repository: package.repository.clone(),
+    }
+}
+
+// The library of a package, if it has one
+fn lib_target(package: &Package) -> Option<&cargo_metadata::Target> {
+    const LIB_KINDS: &[TargetKind] = &[
+        TargetKind::Lib,
+        TargetKind::RLib,
+        TargetKind::DyLib,
+        TargetKind::CDyLib,
+        TargetKind::StaticLib,
+        TargetKind::ProcMacro,
+    ];
+    package
+        .targets
+        .iter()
+        .find(|target| target.kind.iter().any(|kind| LIB_KINDS.contains(kind)))
+}
+
+// The package around a file without metadata, from the nearest `Cargo.toml` above it. Files
+// outside of any package are a crate of their own.
+fn manifest_crate_dir(path: &Path) -> Option<CrateDir> {
+    let absolute = canonical_path(path)?;
+    let parent = absolute.parent()?;
+    let package = parent.ancestors().find_map(|dir| {
+        let name = package_name(&dir.join("Cargo.toml"))?;
+        Some((dir, name))
+    });
+    Some(match package {
+        Some((dir, name)) => CrateDir {
+            dir: dir.to_path_buf(),
+            module_root: dir.join("src"),
+            name: name.replace('-', "_"),
+        },
+        None => CrateDir {
+            dir: parent.to_path_buf(),
+            module_root: parent.to_path_buf(),
+            name: "crate".to_string(),
+        },
+    })
+}
+
+// The package name from a `Cargo.toml`, if there is one
+fn package_name(manifest: &Path) -> Option<String> {
+    let manifest: toml::Table = toml::from_str(&fs::read_to_string(manifest).ok()?).ok()?;
+    Some(manifest.get("package")?.get("name")?.as_str()?.to_string())
+}
+
+// The root files of the given source directory, source trees, included targets and all packages,
+// along with their crate names
+fn crate_roots(
+    root: Option<&str>,
+    metadata: Option<&Metadata>,
+    trees: &[SourceTree],
+    targets: &[ExtraTarget<'_>],
+) -> Vec<(PathBuf, String)> {
+    let mut roots = Vec::new();
+    if let Some(root) = root {
+        let project_name = metadata
+            .and_then(Metadata::root_package)
+            .map_or_else(|| "crate".to_string(), crate_name);
+        roots.push((Path::new(root).join("lib.rs"), project_name.clone()));
+        roots.push((Path::new(root).join("main.rs"), project_name));
+    }
+    for tree in trees {
+        for file in &tree.root_files {
+            roots.push((file.clone(), tree.crate_name.clone()));
+        }
+    }
+    for target in targets {
+        roots.push((target.root_file.clone(), target.name.clone()));
+    }
+    let packages = metadata.map_or(&[][..], |metadata| &metadata.packages);
+    for package in packages {
+        if let Some(dir) = package.manifest_path.parent() {
+            let src = dir.as_std_path().join("src");
+            let lib = lib_target(package).map(|lib| lib.src_path.as_std_path().to_path_buf());
+            roots.push((
+                lib.unwrap_or_else(|| src.join("lib.rs")),
+                crate_name(package),
+            ));
+            roots.push((src.join("main.rs"), crate_name(package)));
+        }
+    }
+    roots
+}
+
+// Collect the `.rs` files below `dir`, skipping what `.gitignore`, `.ignore` and
+// `.reflectcheckignore` files in and above it exclude as well as `exclude_dirs`
+fn collect_source_files(dir: &str, exclude_dirs: &GlobSet, source_files: &mut Vec<String>) {
+    walk_source_files(dir, exclude_dirs, false, source_files);
+}
+
+// Collect the `.rs` files of the package in `dir`, leaving out the packages below it. A package
+// from a git repository is checked out along with the whole repository, whose other packages
+// are scanned on their own if they're in the dependency graph at all.
+fn collect_package_files(dir: &str, exclude_dirs: &GlobSet, source_files: &mut Vec<String>) {
+    walk_source_files(dir, exclude_dirs, true, source_files);
+}
+
+fn walk_source_files(
+    dir: &str,
+    exclude_dirs: &GlobSet,
+    skip_packages: bool,
+    source_files: &mut Vec<String>,
+) {
+    let mut ignores = Ignores::for_root(Path::new(dir));
+    for entry in WalkDir::new(dir)
+        .into_iter()
+        .filter_entry(|entry| {
+            if entry.depth() == 0 {
+                return true;
+            }
+            ignores.enter(entry.depth());
+            let is_dir = entry.file_type().is_dir();
+            if (is_dir && !should_include_dir(entry, exclude_dirs))
+                || (is_dir && skip_packages && entry.path().join("Cargo.toml").is_file())
+                || ignores.is_ignored(entry.path(), is_dir)
+            {
+                return false;
+            }
+            if is_dir {
+                ignores.add_dir(entry.path(), entry.depth());
+            }
+            true
+        })
+        .filter_map(|e| e.ok())
+    {
+        if entry.path().extension().and_then(|ext| ext.to_str()) == Some("rs") {
+            source_files.push(entry.path().to_string_lossy().into_owned());
+        }
+    }
+}
+
+// Collect the `.rs` files in the `src` directory and the library's directory of every workspace
+// member. Members below the current directory are listed by relative paths, like a source
+// directory given on the command line.
+fn collect_workspace_files(
+    metadata: &Metadata,
+    exclude_dirs: &GlobSet,
+    source_files: &mut Vec<String>,
+) {
+    for package in metadata.workspace_packages() {
+        let Some(dir) = package.manifest_path.parent() else {
+            continue;
+        };
+        let src = relative_to_current_dir(&dir.as_std_path().join("src"));
+        collect_source_files(&src.to_string_lossy(), exclude_dirs, source_files);
+        // A library configured with `[lib] path` can live somewhere else, only its root file is
+        // taken if that's next to the `Cargo.toml`, to stay out of `target`
+        if let Some(lib) = lib_target(package)
+            && let Some(lib_dir) = lib.src_path.parent()
+            && !lib_dir.starts_with(dir.join("src"))
+        {
+            if lib_dir == dir {
+                let lib = relative_to_current_dir(lib.src_path.as_std_path());
+                source_files.push(lib.to_string_lossy().into_owned());
+            } else {
+                let lib_dir = relative_to_current_dir(lib_dir.as_std_path());
+                collect_source_files(&lib_dir.to_string_lossy(), exclude_dirs, source_files);
+            }
+        }
+    }
+}
+
+pub(crate) fn relative_to_current_dir(path: &Path) -> PathBuf {
+    let current_dir = std::env::current_dir().unwrap_or_default();
+    match path.strip_prefix(&current_dir) {
+        Ok(relative) => Path::new(".").join(relative),
+        Err(_) => path.to_path_buf(),
+    }
+}
+
+// Exclude the `examples`, `tests` and `benches` directories of crates, which are compiled as
+// crates of their own, and the directories matching `exclude_dirs`
+fn should_include_dir(entry: &DirEntry, exclude_dirs: &GlobSet) -> bool {
+    let path = entry.path();
+    let name = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or("");
+    let crate_target_dir = matches!(name, "examples" | "tests" | "benches")
+        && path
+            .parent()
+            .is_some_and(|parent| parent.join("Cargo.toml").is_file());
+    !crate_target_dir && !exclude_dirs.is_match(name)
+}
+
+// Collect `.rs` files from dependencies whose name matches `config.deps`. Workspace members are
+// left to `collect_workspace_files`. Of a package that's in the graph with several versions, only
+// the newest is scanned: their types share crate and module paths, and would be reported and
+// matched with registrations twice otherwise.
+fn collect_dependency_files(
+    metadata: &Metadata,
+    config: &AnalysisConfig,
+    source_files: &mut Vec<String>,
+    warnings: &mut Vec<String>,
+) {
+    let mut by_name: BTreeMap<&str, Vec<&Package>> = BTreeMap::new();
+    for package in &metadata.packages {
+        if !metadata.workspace_members.contains(&package.id) && config.deps.is_match(&package.name)
+        {
+            by_name.entry(&package.name).or_default().push(package);
+        }
+    }
+    for (name, mut packages) in by_name {
+        packages.sort_by(|a, b| a.version.cmp(&b.version));
+        let Some(newest) = packages.pop() else {
+            continue;
+        };
+        if !packages.is_empty() {
+            let older: Vec<String> = packages
+                .iter()
+                .map(|package| package.version.to_string())
+                .collect();
+            warnings.push(format!(
+                "Scanning only version {} of `{name}`, skipping {} that the dependency graph \
+                contains as well",
+                newest.version,
+                older.join(", ")
+            ));
+        }
+        if let Some(source) = newest.manifest_path.parent() {
+            collect_package_files(source.as_str(), &config.exclude_dirs, source_files);
+        }
+    }
+}
+
+// The resolved version of `bevy_reflect`, the newest one if there are several
+fn bevy_version(metadata: &Metadata) -> Option<Version> {
+    metadata
+        .packages
+        .iter()
+        .filter(|package| package.name == "bevy_reflect")
+        .map(|package| package.version.clone())
+        .max()
+}
+
+// The `assets` directories of the workspace and its members, where scene files are looked for
+fn asset_dirs(metadata: &Metadata) -> Vec<PathBuf> {
+    let mut dirs = vec![relative_to_current_dir(
+        &metadata.workspace_root.as_std_path().join("assets"),
+    )];
+    for package in metadata.workspace_packages() {
+        if let Some(dir) = package.manifest_path.parent() {
+            let assets = relative_to_current_dir(&dir.as_std_path().join("assets"));
+            if !dirs
+                .iter()
+                .any(|known| canonical_path(known) == canonical_path(&assets))
+            {
+                dirs.push(assets);
+            }
+        }
+    }
+    dirs.retain(|dir| dir.is_dir());
+    dirs
+}
+
+// Resolve the fully qualified module path from a file's path relative to its crate's root
+fn resolve_module_path(path: &str, root: Option<&str>, crate_dirs: &[CrateDir]) -> Option<String> {
+    let path = Path::new(path);
+
+    if let Some(crate_dir) = crate_dir_for_file(path, crate_dirs) {
+        // Files outside of the crate root's directory, like a `build.rs`, still get a path
+        let absolute = canonical_path(path)?;
+        let relative_path = absolute
+            .strip_prefix(&crate_dir.module_root)
+            .or_else(|_| absolute.strip_prefix(&crate_dir.dir))
+            .ok()?;
+        let module_path = relative_path_to_module_path(relative_path);
+        if module_path.is_empty() {
+            Some(crate_dir.name.clone())
+        } else {
+            Some(format!("{}::{}", crate_dir.name, module_path))
+        }
+    } else {
+        let relative_path = path.strip_prefix(root?).ok()?;
+        Some(relative_path_to_module_path(relative_path))
+    }
+}
+
+// Find the crate directory for a given file, which may be relative to the current directory.
+// Crates can lie within each other, e.g. a path dependency inside the workspace's root package,
+// so the innermost directory wins.
+fn crate_dir_for_file<'a>(path: &Path, crate_dirs: &'a [CrateDir]) -> Option<&'a CrateDir> {
+    let path = canonical_path(path)?;
+    crate_dirs
+        .iter()
+        .filter(|crate_dir| path.starts_with(&crate_dir.dir))
+        .min_by_key(|crate_dir| std::cmp::Reverse(crate_dir.dir.components().count()))
+}
+
+// The path with symbolic links resolved, so that a file has the same path however it's reached,
+// or just made absolute if it doesn't exist
+fn canonical_path(path: &Path) -> Option<PathBuf> {
+    fs::canonicalize(path)
+        .or_else(|_| std::path::absolute(path))
+        .ok()
+}
+
+// Convert a relative path to a Rust module path
+fn relative_path_to_module_path(path: &Path) -> String {
+    path.iter()
+        .filter_map(|comp| comp.to_str())
+        .map(|s| s.trim_end_matches(".rs"))
+        .filter(|s| *s != "mod")
+        .collect::<Vec<_>>()
+        .join("::")
+}
@@ -0,0 +1,292 @@
+use crate::cfg::CfgSet;
+use proc_macro2::{TokenStream, TokenTree};
+use quote::ToTokens;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use syn::punctuated::Punctuated;
+use syn::{Attribute, Expr, ExprLit, Lit, Meta, MetaList, Path, Token, parenthesized, token};
+
+/// Type data and options that `#[reflect(...)]` accepts without a `Reflect*` type in the scanned
+/// code: the traits Bevy special-cases, common type data and the derive's own options.
+pub const KNOWN_TYPE_DATA: &[&str] = &[
+    "Debug",
+    "Hash",
+    "PartialEq",
+    "Clone",
+    "Default",
+    "Component",
+    "Resource",
+    "Event",
+    "Bundle",
+    "Asset",
+    "Handle",
+    "Serialize",
+    "Deserialize",
+    "FromWorld",
+    "FromReflect",
+    "MapEntities",
+    "State",
+    "FreelyMutableState",
+    "opaque",
+    "from_reflect",
+    "type_path",
+    "no_field_bounds",
+    "where",
+];
+
+/// What a type's attributes say about its reflection setup.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct ReflectAttrs {
+    /// Names of all derived traits, e.g. `Reflect` or `Component`.
+    pub derives: BTreeSet<String>,
+    /// Type data registered via `#[reflect(...)]`, e.g. `Component` or `Default`. The older
+    /// `#[reflect_value(...)]` counts as well, and adds `opaque`.
+    pub reflect: BTreeSet<String>,
+    /// Entries that are listed more than once across `#[reflect(...)]` and
+    /// `#[reflect_value(...)]`, e.g. in `#[reflect(Component, Component)]`.
+    #[serde(default)]
+    pub duplicate_reflect: BTreeSet<String>,
+    /// Has `#[reflect(from_reflect = false)]`, so the derive doesn't implement `FromReflect`.
+    #[serde(default)]
+    pub from_reflect_disabled: bool,
+    /// Components from `#[require(...)]`, by name, that get inserted along with this one.
+    #[serde(default)]
+    pub required: BTreeSet<String>,
+    /// Options from `#[component(...)]`, e.g. `storage` with `SparseSet`. Options without a
+    /// value map to an empty string.
+    pub component: BTreeMap<String, String>,
+}
+
+impl ReflectAttrs {
+    /// Parse the attributes, taking only `cfg_attr`s into account whose predicate holds.
+    ///
+    /// Derives are recorded by the last segment of their path, so `bevy::prelude::Component`
+    /// counts as `Component`. `aliases` maps derive names or full paths to the trait they stand
+    /// for, e.g. from `use bevy::prelude::Component as BevyComponent;`.
+    pub fn parse(attrs: &[Attribute], cfg: &CfgSet, aliases: &BTreeMap<String, String>) -> Self {
+        let mut result = Self::default();
+
+        for attr in attrs {
+            match &attr.meta {
+                // Check for `#[derive(...)]`
+                Meta::List(meta_list) if meta_list.path.is_ident("derive") => {
+                    meta_list
+                        .parse_nested_meta(|nested_meta| {
+                            if let Some(name) = derive_name(&nested_meta.path, aliases) {
+                                result.derives.insert(name);
+                            }
+                            Ok(())
+                        })
+                        .ok();
+                }
+                Meta::List(meta_list) if meta_list.path.is_ident("cfg_attr") => {
+                    let Ok(nested) =
+                        meta_list.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)
+                    else {
+                        continue;
+                    };
+                    let mut nested = nested.into_iter();
+                    if !nested.next().is_some_and(|predicate| cfg.eval(&predicate)) {
+                        continue;
+                    }
+                    for meta in nested {
+                        if let Meta::List(meta_list) = meta {
+                            if meta_list.path.is_ident("derive") {
+                                meta_list
+                                    .parse_nested_meta(|inner_nested_meta| {
+                                        if let Some(name) =
+                                            derive_name(&inner_nested_meta.path, aliases)
+                                        {
+                                            result.derives.insert(name);
+                                        }
+                                        Ok(())
+                                    })
+                                    .ok();
+                            } else if meta_list.path.is_ident("reflect") {
+                                result.add_reflect(meta_list.tokens);
+                            } else if meta_list.path.is_ident("reflect_value") {
+                                result.add_reflect(meta_list.tokens);
+                                result.reflect.insert("opaque".to_string());
+                            } else if meta_list.path.is_ident("component") {
+                                result.component.extend(component_options(&meta_list));
+                            } else if meta_list.path.is_ident("require") {
+                                result
+                                    .required
+                                    .extend(required_components(meta_list.tokens));
+                            }
+                        }
+                    }
+                }
+                // Check for `#[reflect(...)]`
+                Meta::List(meta_list) if meta_list.path.is_ident("reflect") => {
+                    result.add_reflect(meta_list.tokens.clone());
+                }
+                // `#[reflect_value(...)]` is what `#[reflect(opaque, ...)]` used to be
+                Meta::List(meta_list) if meta_list.path.is_ident("reflect_value") => {
+                    result.add_reflect(meta_list.tokens.clone());
+                    result.reflect.insert("opaque".to_string());
+                }
+                Meta::List(meta_list) if meta_list.path.is_ident("component") => {
+                    result.component.extend(component_options(meta_list));
+                }
+                Meta::List(meta_list) if meta_list.path.is_ident("require") => {
+                    result
+                        .required
+                        .extend(required_components(meta_list.tokens.clone()));
+                }
+                _ => {}
+            }
+        }
+
+        result
+    }
+
+    // Record the entries of a `#[reflect(...)]`, remembering those that were already there
+    fn add_reflect(&mut self, tokens: TokenStream) {
+        self.from_reflect_disabled |= disables_from_reflect(tokens.clone());
+        let entries = reflect_entries(tokens);
+        for entry in entries {
+            if !self.reflect.insert(entry.clone()) {
+                self.duplicate_reflect.insert(entry);
+            }
+        }
+    }
+
+    pub fn derives(&self, name: &str) -> bool {
+        self.derives.contains(name)
+    }
+
+    pub fn reflects(&self, name: &str) -> bool {
+        self.reflect.contains(name)
+    }
+
+    /// How a derived component is stored, `Table` unless `#[component(storage = "...")]` says
+    /// otherwise. `None` if `Component` isn't derived.
+    pub fn component_storage(&self) -> Option<&str> {
+        self.derives("Component").then(|| {
+            self.component
+                .get("storage")
+                .map_or("Table", String::as_str)
+        })
+    }
+}
+
+// The names of the entries in `#[reflect(...)]`, like `Component` in `Component`, `Debug` in
+// `Debug(custom_fn)` or `from_reflect` in `from_reflect = false`. Custom attributes
+// (`@expr`) have no name, and a `where` clause runs to the end and is named `where`.
+pub(crate) fn reflect_entries(tokens: TokenStream) -> Vec<String> {
+    let mut entries = Vec::new();
+    let mut at_start = true;
+    for token in tokens {
+        match token {
+            TokenTree::Ident(ident) if ident == "where" => {
+                entries.push(ident.to_string());
+                break;
+            }
+            TokenTree::Ident(ident) if at_start => entries.push(ident.to_string()),
+            TokenTree::Punct(punct) if punct.as_char() == ',' => {
+                at_start = true;
+                continue;
+            }
+            _ => {}
+        }
+        at_start = false;
+    }
+    entries
+}
+
+// The names of the components in `#[require(...)]`, the last segment of the path that starts
+// each entry, like `Transform` in `Transform`, `Name(|| Name::new("Player"))` or
+// `bevy::prelude::Visibility = Visibility::Hidden`
+fn required_components(tokens: TokenStream) -> Vec<String> {
+    let mut names = Vec::new();
+    let mut name = None;
+    let mut in_path = true;
+    // Commas between generic arguments don't end the entry
+    let mut angle_depth = 0usize;
+    for token in tokens {
+        match token {
+            TokenTree::Punct(punct) if punct.as_char() == ',' && angle_depth == 0 => {
+                names.extend(name.take());
+                in_path = true;
+            }
+            TokenTree::Punct(punct) if punct.as_char() == '<' => {
+                angle_depth += 1;
+                in_path = false;
+            }
+            TokenTree::Punct(punct) if punct.as_char() == '>' => {
+                angle_depth = angle_depth.saturating_sub(1);
+            }
+            TokenTree::Punct(punct) if in_path && punct.as_char() == ':' => {}
+            TokenTree::Ident(ident) if in_path => name = Some(ident.to_string()),
+            _ => in_path = false,
+        }
+    }
+    names.extend(name);
+    names
+}
+
+// Whether the entries of `#[reflect(...)]` include `from_reflect = false`
+fn disables_from_reflect(tokens: TokenStream) -> bool {
+    let tokens: Vec<TokenTree> = tokens.into_iter().collect();
+    tokens.windows(3).any(|window| {
+        matches!(
+            window,
+            [TokenTree::Ident(name), TokenTree::Punct(eq), TokenTree::Ident(value)]
+                if name == "from_reflect" && eq.as_char() == '=' && value == "false"
+        )
+    })
+}
+
+// The options in `#[component(...)]`: `storage = "SparseSet"`, `on_add = path::to::hook` or
+// `require(A, B)`, with string literals unquoted and other values as tokens
+fn component_options(meta_list: &MetaList) -> Vec<(String, String)> {
+    let mut options = Vec::new();
+    meta_list
+        .parse_nested_meta(|meta| {
+            let name = meta
+                .path
+                .segments
+                .last()
+                .map(|segment| segment.ident.to_string())
+                .unwrap_or_default();
+            let value = if meta.input.peek(Token![=]) {
+                match meta.value()?.parse::<Expr>()? {
+                    Expr::Lit(ExprLit {
+                        lit: Lit::Str(value),
+                        ..
+                    }) => value.value(),
+                    value => value.to_token_stream().to_string(),
+                }
+            } else if meta.input.peek(token::Paren) {
+                let content;
+                parenthesized!(content in meta.input);
+                content.parse::<TokenStream>()?.to_string()
+            } else {
+                String::new()
+            };
+            options.push((name, value));
+            Ok(())
+        })
+        .ok();
+    options
+}
+
+// The trait a derive path stands for: an alias for the full path or its last segment, or the
+// last segment itself
+fn derive_name(path: &Path, aliases: &BTreeMap<String, String>) -> Option<String> {
+    let name = path.segments.last()?.ident.to_string();
+    let full_path = path
+        .segments
+        .iter()
+        .map(|segment| segment.ident.to_string())
+        .collect::<Vec<_>>()
+        .join("::");
+    Some(
+        aliases
+            .get(&full_path)
+            .or_else(|| aliases.get(&name))
+            .cloned()
+            .unwrap_or(name),
+    )
+}
@@ -0,0 +1,75 @@
+use crate::finding::Finding;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::path::Path;
+use std::{fs, io};
+
+/// Findings that were already present when the baseline was recorded.
+///
+/// Entries are keyed by check, type path and enum variant rather than by location, so that
+/// unrelated edits moving a type around don't resurface its finding, while a new offending
+/// variant of a baselined enum is still reported.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Baseline {
+    findings: BTreeSet<BaselineEntry>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+struct BaselineEntry {
+    check: String,
+    type_path: String,
+    /// Missing in baselines recorded before findings were reported per variant.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    variant: Option<String>,
+}
+
+impl BaselineEntry {
+    fn new(finding: &Finding) -> Self {
+        Self {
+            check: finding.check.to_string(),
+            type_path: finding.type_path.clone(),
+            variant: finding.variant.clone(),
+        }
+    }
+}
+
+impl Baseline {
+    /// Load a baseline, returns `None` if the file doesn't exist yet.
+    pub fn load(path: &Path) -> io::Result<Option<Self>> {
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err),
+        };
+        Ok(Some(serde_json::from_str(&content)?))
+    }
+
+    pub fn from_findings(findings: &[Finding]) -> Self {
+        Self {
+            findings: findings.iter().map(BaselineEntry::new).collect(),
+        }
+    }
+
+    /// Add `finding`, so that it isn't reported anymore.
+    pub fn insert(&mut self, finding: &Finding) {
+        self.findings.insert(BaselineEntry::new(finding));
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let mut content = serde_json::to_string_pretty(self)?;
+        content.push('\n');
+        fs::write(path, content)
+    }
+
+    pub fn len(&self) -> usize {
+        self.findings.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.findings.is_empty()
+    }
+
+    pub fn contains(&self, finding: &Finding) -> bool {
+        self.findings.contains(&BaselineEntry::new(finding))
+    }
+}
@@ -0,0 +1,84 @@
+use crate::types::Collected;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::{fs, io};
+
+/// On-disk cache of per-file analysis results, keyed by a hash of the file's content and
+/// everything else the result depends on.
+///
+/// Only the entries used during a run are written back, so stale entries disappear on their own.
+/// Loaded entries are handed out rather than copied, and the ones to write back are kept
+/// serialized, so the cache doesn't hold a second copy of every file's results during a run.
+#[derive(Debug)]
+pub struct Cache {
+    path: PathBuf,
+    previous: Mutex<HashMap<String, Collected>>,
+    /// The JSON of each entry used so far.
+    current: Mutex<HashMap<String, String>>,
+}
+
+impl Cache {
+    /// Load the cache from `dir`. A missing or unreadable cache just starts out empty.
+    pub fn load(dir: &Path) -> Self {
+        let path = dir.join("cache.json");
+        let previous = fs::read(&path)
+            .ok()
+            .and_then(|content| serde_json::from_slice(&content).ok())
+            .unwrap_or_default();
+        Self {
+            path,
+            previous: Mutex::new(previous),
+            current: Mutex::default(),
+        }
+    }
+
+    /// Build the key for a file. `context` has to contain everything besides the file content
+    /// that influences the analysis result.
+    pub fn key(content: &str, context: &[&str]) -> String {
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(env!("CARGO_PKG_VERSION").as_bytes());
+        for part in context {
+            hasher.update(&[0]);
+            hasher.update(part.as_bytes());
+        }
+        hasher.update(&[0]);
+        hasher.update(content.as_bytes());
+        hasher.finalize().to_hex().to_string()
+    }
+
+    /// Take the entry for `key` out of the loaded cache. It's kept for the next run.
+    pub fn get(&self, key: &str) -> Option<Collected> {
+        let collected = self.previous.lock().unwrap().remove(key)?;
+        self.insert(key.to_string(), &collected);
+        Some(collected)
+    }
+
+    pub fn insert(&self, key: String, collected: &Collected) {
+        // Serializing can't fail for these types, and a missing entry only costs a parse
+        if let Ok(json) = serde_json::to_string(collected) {
+            self.current.lock().unwrap().insert(key, json);
+        }
+    }
+
+    pub fn save(self) -> io::Result<()> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        // The entries are already JSON, so the map is put together by hand. Keys are hex digits
+        // and need no escaping.
+        let current = self.current.into_inner().unwrap();
+        let mut json = String::from("{");
+        for (index, (key, entry)) in current.iter().enumerate() {
+            if index > 0 {
+                json.push(',');
+            }
+            json.push('"');
+            json.push_str(key);
+            json.push_str("\":");
+            json.push_str(entry);
+        }
+        json.push('}');
+        fs::write(&self.path, json)
+    }
+}
@@ -0,0 +1,360 @@
+use cargo_metadata::Package;
+use std::collections::BTreeSet;
+use std::fmt::Write;
+use syn::punctuated::Punctuated;
+use syn::{Attribute, Expr, ExprLit, Lit, Meta, Token};
+
+/// Which cargo features are enabled for a crate.
+#[derive(Debug, Clone)]
+pub enum Features {
+    All,
+    Some(BTreeSet<String>),
+}
+
+impl Features {
+    /// The features of `package` that are enabled by default (unless `default_features` is
+    /// unset) or requested on the command line. `requested` uses cargo's syntax, so `feature`
+    /// applies to the current project and `package/feature` to the named package only.
+    pub fn for_package(
+        package: &Package,
+        is_root: bool,
+        requested: &[String],
+        default_features: bool,
+    ) -> Self {
+        let mut pending = requested_for(package, is_root, requested);
+        if default_features {
+            pending.push("default".to_string());
+        }
+        Features::Some(implied_features(package, pending))
+    }
+
+    /// The features cargo resolved for `package` in the actual build, plus the ones requested
+    /// for it as `package/feature` on the command line.
+    pub fn resolved(package: &Package, resolved: &[String], requested: &[String]) -> Self {
+        let mut pending = requested_for(package, false, requested);
+        pending.extend(resolved.iter().cloned());
+        Features::Some(implied_features(package, pending))
+    }
+
+    fn contains(&self, feature: &str) -> bool {
+        match self {
+            Features::All => true,
+            Features::Some(features) => features.contains(feature),
+        }
+    }
+}
+
+// The requested features that apply to `package`
+fn requested_for(package: &Package, is_root: bool, requested: &[String]) -> Vec<String> {
+    requested
+        .iter()
+        .filter_map(|feature| match feature.split_once('/') {
+            Some((name, feature)) if name == package.name => Some(feature.to_string()),
+            Some(_) => None,
+            None => is_root.then(|| feature.clone()),
+        })
+        .collect()
+}
+
+// The given features along with the ones they enable, since features can enable other features
+// of the same package
+fn implied_features(package: &Package, mut pending: Vec<String>) -> BTreeSet<String> {
+    let mut enabled = BTreeSet::new();
+    while let Some(feature) = pending.pop() {
+        let Some(implied) = package.features.get(&feature) else {
+            continue;
+        };
+        if enabled.insert(feature) {
+            pending.extend(
+                implied
+                    .iter()
+                    .filter(|entry| !entry.contains('/') && !entry.starts_with("dep:"))
+                    .cloned(),
+            );
+        }
+    }
+    enabled
+}
+
+/// The target platform `#[cfg(...)]` predicates are evaluated for.
+#[derive(Debug, Clone)]
+pub struct Target {
+    pub arch: String,
+    pub os: String,
+    pub family: Option<String>,
+    pub env: String,
+    pub vendor: String,
+    pub pointer_width: String,
+    pub endian: String,
+    /// The `target_has_atomic` values, like `32` or `ptr`, for the widths with compare-and-swap.
+    pub atomic_widths: Vec<String>,
+}
+
+/// Vendors of target triples that have three parts, where the second one could also be the OS.
+const VENDORS: &[&str] = &[
+    "pc",
+    "apple",
+    "unknown",
+    "sun",
+    "nvidia",
+    "fortanix",
+    "uwp",
+    "wrs",
+    "esp",
+    "espressif",
+    "kmc",
+    "nintendo",
+    "sony",
+    "openwrt",
+    "unikraft",
+    "win7",
+];
+
+impl Target {
+    /// The platform this tool was built for.
+    pub fn host() -> Self {
+        Self {
+            arch: std::env::consts::ARCH.to_string(),
+            os: std::env::consts::OS.to_string(),
+            family: Some(std::env::consts::FAMILY.to_string()).filter(|family| !family.is_empty()),
+            env: if cfg!(target_env = "gnu") {
+                "gnu"
+            } else if cfg!(target_env = "msvc") {
+                "msvc"
+            } else if cfg!(target_env = "musl") {
+                "musl"
+            } else {
+                ""
+            }
+            .to_string(),
+            vendor: if cfg!(target_vendor = "apple") {
+                "apple"
+            } else if cfg!(target_vendor = "pc") {
+                "pc"
+            } else {
+                "unknown"
+            }
+            .to_string(),
+            pointer_width: (usize::BITS).to_string(),
+            endian: if cfg!(target_endian = "big") {
+                "big"
+            } else {
+                "little"
+            }
+            .to_string(),
+            atomic_widths: [
+                ("8", cfg!(target_has_atomic = "8")),
+                ("16", cfg!(target_has_atomic = "16")),
+                ("32", cfg!(target_has_atomic = "32")),
+                ("64", cfg!(target_has_atomic = "64")),
+                ("ptr", cfg!(target_has_atomic = "ptr")),
+            ]
+            .into_iter()
+            .filter(|(_, has)| *has)
+            .map(|(width, _)| width.to_string())
+            .collect(),
+        }
+    }
+
+    /// Approximate the cfg values of a target triple like `wasm32-unknown-unknown` or
+    /// `x86_64-pc-windows-msvc`. This covers the common targets, not every corner of rustc's list.
+    pub fn from_triple(triple: &str) -> Self {
+        let parts: Vec<&str> = triple.split('-').collect();
+        let arch = match parts[0] {
+            "i386" | "i586" | "i686" => "x86",
+            arch if arch.starts_with("armv7") || arch.starts_with("thumbv") => "arm",
+            arch if arch.starts_with("riscv64") => "riscv64",
+            arch if arch.starts_with("riscv32") => "riscv32",
+            arch => arch,
+        }
+        .to_string();
+        // Triples like `aarch64-linux-android` or `thumbv7em-none-eabihf` leave the vendor out
+        let vendor = parts
+            .get(1)
+            .copied()
+            .filter(|part| parts.len() >= 4 || VENDORS.contains(part))
+            .unwrap_or("unknown")
+            .to_string();
+        // From the end, so that `android` wins over the `linux` before it
+        let os = parts
+            .iter()
+            .skip(1)
+            .rev()
+            .find_map(|part| match *part {
+                "linux" | "windows" | "android" | "ios" | "freebsd" | "netbsd" | "openbsd"
+                | "emscripten" | "wasi" | "none" => Some(*part),
+                "darwin" => Some("macos"),
+                _ => None,
+            })
+            .or(if arch.starts_with("wasm") {
+                Some("unknown")
+            } else {
+                None
+            })
+            .unwrap_or("none")
+            .to_string();
+        let family = match os.as_str() {
+            "windows" => Some("windows"),
+            "linux" | "android" | "macos" | "ios" | "freebsd" | "netbsd" | "openbsd"
+            | "emscripten" => Some("unix"),
+            _ if arch.starts_with("wasm") => Some("wasm"),
+            _ => None,
+        }
+        .map(str::to_owned);
+        let env = match parts.last().copied().unwrap_or_default() {
+            env if env.starts_with("gnu") => "gnu",
+            env if env.starts_with("musl") => "musl",
+            "msvc" => "msvc",
+            _ => "",
+        }
+        .to_string();
+        let pointer_width = match arch.as_str() {
+            "x86_64" | "aarch64" | "riscv64" | "wasm64" | "powerpc64" | "mips64" | "s390x" => "64",
+            "avr" | "msp430" => "16",
+            _ => "32",
+        }
+        .to_string();
+        let endian = if arch.starts_with("powerpc") || arch == "s390x" {
+            "big"
+        } else {
+            "little"
+        }
+        .to_string();
+        let atomic_widths = atomic_widths(parts[0], &arch, &pointer_width);
+        Self {
+            arch,
+            os,
+            family,
+            env,
+            vendor,
+            pointer_width,
+            endian,
+            atomic_widths,
+        }
+    }
+}
+
+// The widths with atomic compare-and-swap. Cores like `thumbv6m`, `riscv32imc`, AVR or MSP430
+// have none, and 64-bit atomics on 32-bit targets only exist on x86, ARMv7-A and wasm.
+fn atomic_widths(arch_part: &str, arch: &str, pointer_width: &str) -> Vec<String> {
+    let without_atomics = arch_part.starts_with("thumbv6m")
+        || arch_part.starts_with("thumbv8m.base")
+        || (arch_part.starts_with("riscv32") && !arch_part[7..].contains(['a', 'g']))
+        || matches!(arch, "avr" | "msp430");
+    if without_atomics {
+        return Vec::new();
+    }
+    let mut widths = vec!["8", "16", "32"];
+    if pointer_width == "64"
+        || matches!(arch, "x86" | "wasm32")
+        || (arch == "arm" && arch_part.starts_with("armv7"))
+    {
+        widths.push("64");
+    }
+    widths.push("ptr");
+    widths.into_iter().map(str::to_string).collect()
+}
+
+/// The configuration `#[cfg(...)]` predicates of a crate are evaluated against.
+#[derive(Debug, Clone)]
+pub struct CfgSet {
+    pub features: Features,
+    pub target: Target,
+}
+
+impl CfgSet {
+    /// Whether an item with these attributes is compiled in, i.e. all of its `#[cfg]`s hold.
+    pub fn is_enabled(&self, attrs: &[Attribute]) -> bool {
+        attrs.iter().all(|attr| match &attr.meta {
+            Meta::List(list) if list.path.is_ident("cfg") => list
+                .parse_args::<Meta>()
+                .map(|predicate| self.eval(&predicate))
+                .unwrap_or(true),
+            _ => true,
+        })
+    }
+
+    /// Evaluate a cfg predicate like `all(feature = "x", not(target_arch = "wasm32"))`.
+    ///
+    /// Unknown options are treated like rustc does: they're not set.
+    pub fn eval(&self, predicate: &Meta) -> bool {
+        match predicate {
+            Meta::Path(path) => {
+                let Some(name) = path.get_ident().map(ToString::to_string) else {
+                    return false;
+                };
+                match name.as_str() {
+                    "unix" | "windows" => self.target.family.as_deref() == Some(name.as_str()),
+                    "debug_assertions" => true,
+                    _ => false,
+                }
+            }
+            Meta::NameValue(name_value) => {
+                let Some(name) = name_value.path.get_ident() else {
+                    return false;
+                };
+                let Expr::Lit(ExprLit {
+                    lit: Lit::Str(value),
+                    ..
+                }) = &name_value.value
+                else {
+                    return false;
+                };
+                let value = value.value();
+                let target = &self.target;
+                match name.to_string().as_str() {
+                    "feature" => self.features.contains(&value),
+                    "target_arch" => target.arch == value,
+                    "target_os" => target.os == value,
+                    "target_family" => target.family.as_deref() == Some(&value),
+                    "target_env" => target.env == value,
+                    "target_vendor" => target.vendor == value,
+                    "target_pointer_width" => target.pointer_width == value,
+                    "target_endian" => target.endian == value,
+                    "target_has_atomic" => target.atomic_widths.contains(&value),
+                    "panic" => value == "unwind",
+                    _ => false,
+                }
+            }
+            Meta::List(list) => {
+                let Ok(nested) =
+                    list.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)
+                else {
+                    return false;
+                };
+                if list.path.is_ident("all") {
+                    nested.iter().all(|meta| self.eval(meta))
+                } else if list.path.is_ident("any") {
+                    nested.iter().any(|meta| self.eval(meta))
+                } else if list.path.is_ident("not") {
+                    nested.len() == 1 && !self.eval(&nested[0])
+                } else {
+                    false
+                }
+            }
+        }
+    }
+
+    /// A string that changes whenever the evaluation result of some predicate could change.
+    pub fn cache_key(&self) -> String {
+        let mut key = match &self.features {
+            Features::All => "all-features".to_string(),
+            Features::Some(features) => features.iter().cloned().collect::<Vec<_>>().join(","),
+        };
+        let target = &self.target;
+        write!(
+            key,
+            ";{}-{}-{:?}-{}-{}-{}-{}-{}",
+            target.arch,
+            target.os,
+            target.family,
+            target.env,
+            target.vendor,
+            target.pointer_width,
+            target.endian,
+            target.atomic_widths.join(",")
+        )
+        .unwrap();
+        key
+    }
+}
@@ -0,0 +1,901 @@
+use crate::finding::Severity;
+use crate::modules::Visibility;
+use crate::types::TypeInfo;
+
+/// Fields with a note on what's wrong with each, which may be empty.
+pub type FieldNotes<'a> = Vec<(&'a str, String)>;
+
+/// Static description of a check, used for messages and rule metadata in reports.
+#[derive(Debug, Clone, Copy)]
+pub struct Check {
+    /// Stable identifier, used on the command line and in reports.
+    pub id: &'static str,
+    /// Human readable name.
+    pub name: &'static str,
+    /// What's wrong with a type flagged by this check.
+    pub problem: &'static str,
+    /// Why it matters and how to fix it.
+    pub description: &'static str,
+    /// How to fix a flagged type, in a few words.
+    pub hint: &'static str,
+    /// Long-form documentation in Markdown, with examples of flagged and fixed code, for
+    /// `explain`.
+    pub explanation: &'static str,
+    pub default_severity: Severity,
+    /// Opt-in checks have to be requested with `--rule`.
+    pub enabled_by_default: bool,
+    /// Whether the type should be flagged.
+    pub applies: fn(&TypeInfo) -> bool,
+    /// Specifics to append to the message, like the offending names.
+    pub detail: Option<fn(&TypeInfo) -> String>,
+    /// For checks about fields, the offending fields (named `Variant::field` in enums) with a
+    /// note on each. Appended to the message like `detail`, but enums get a finding per variant.
+    pub field_detail: Option<fn(&TypeInfo) -> FieldNotes<'_>>,
+    /// Type data that `--fix` adds to `#[reflect(...)]`, for checks that can be fixed that way.
+    /// Entries the attribute already has are left out.
+    pub fix: Option<&'static [&'static str]>,
+    /// The `bevy_reflect` version (major, minor) that introduced what the check is about. It's
+    /// skipped for projects on older versions.
+    pub since: Option<(u64, u64)>,
+    /// The `bevy_reflect` version (major, minor) that made the check obsolete. It's skipped for
+    /// projects on that version or newer.
+    pub until: Option<(u64, u64)>,
+}
+
+pub const MISSING_REFLECT_COMPONENT: Check = Check {
+    id: "missing-reflect-component",
+    name: "MissingReflectComponent",
+    problem: "derives `Reflect` and implements `Component` but is missing `#[reflect(Component)]`",
+    description: "Components that derive `Reflect` need `#[reflect(Component)]` so that \
+        `ReflectComponent` is registered for them. Without it, scenes and editors can see the \
+        type but can't insert, read or remove it as a component.",
+    hint: "add `Component` to the type's `#[reflect(...)]`, `--fix` does this for you",
+    explanation: include_str!("../docs/rules/missing-reflect-component.md"),
+    default_severity: Severity::Warning,
+    enabled_by_default: true,
+    applies: |ty| ty.missing_reflect_for("Component"),
+    detail: None,
+    field_detail: None,
+    fix: Some(&["Component"]),
+    since: None,
+    until: None,
+};
+
+pub const MISSING_REFLECT_RESOURCE: Check = Check {
+    id: "missing-reflect-resource",
+    name: "MissingReflectResource",
+    problem: "derives `Reflect` and implements `Resource` but is missing `#[reflect(Resource)]`",
+    description: "Resources that derive `Reflect` need `#[reflect(Resource)]` so that \
+        `ReflectResource` is registered for them. Without it, scenes and editors can see the \
+        type but can't insert, read or remove it as a resource.",
+    hint: "add `Resource` to the type's `#[reflect(...)]`, `--fix` does this for you",
+    explanation: include_str!("../docs/rules/missing-reflect-resource.md"),
+    default_severity: Severity::Warning,
+    enabled_by_default: true,
+    applies: |ty| ty.missing_reflect_for("Resource"),
+    detail: None,
+    field_detail: None,
+    fix: Some(&["Resource"]),
+    since: None,
+    until: None,
+};
+
+pub const MISSING_REFLECT_EVENT: Check = Check {
+    id: "missing-reflect-event",
+    name: "MissingReflectEvent",
+    problem: "derives `Reflect` and implements `Event` but is missing `#[reflect(Event)]`",
+    description: "Events that derive `Reflect` should register `ReflectEvent` via \
+        `#[reflect(Event)]`, so that editors and other tooling can send them without knowing \
+        the type at compile time. `ReflectEvent` isn't available in every Bevy version, so this \
+        check is opt-in.",
+    hint: "add `Event` to the type's `#[reflect(...)]`, `--fix` does this for you",
+    explanation: include_str!("../docs/rules/missing-reflect-event.md"),
+    default_severity: Severity::Warning,
+    enabled_by_default: false,
+    applies: |ty| ty.missing_reflect_for("Event"),
+    detail: None,
+    field_detail: None,
+    fix: Some(&["Event"]),
+    since: None,
+    until: None,
+};
+
+pub const MISSING_REFLECT_STATE: Check = Check {
+    id: "missing-reflect-state",
+    name: "MissingReflectState",
+    problem: "derives `Reflect` and implements `States` but is missing \
+        `#[reflect(State, FreelyMutableState)]`",
+    description: "States that derive `Reflect` need `ReflectState`, and `ReflectFreelyMutableState` \
+        to be changeable, so that editors and inspectors can read the current state and queue \
+        transitions. They're registered through `#[reflect(State, FreelyMutableState)]` or \
+        `app.register_type_mutable_state::<S>()`.",
+    hint: "add `State` and `FreelyMutableState` to the type's `#[reflect(...)]` or register it \
+        with `app.register_type_mutable_state::<S>()`, `--fix` adds them for you",
+    explanation: include_str!("../docs/rules/missing-reflect-state.md"),
+    default_severity: Severity::Warning,
+    enabled_by_default: true,
+    applies: |ty| {
+        ty.attrs.derives("Reflect")
+            && ty.is_state()
+            && !(ty.has_type_data("State") && ty.has_type_data("FreelyMutableState"))
+    },
+    detail: None,
+    field_detail: None,
+    fix: Some(&["State", "FreelyMutableState"]),
+    since: Some((0, 14)),
+    until: None,
+};
+
+pub const MISSING_REFLECT_ASSET: Check = Check {
+    id: "missing-reflect-asset",
+    name: "MissingReflectAsset",
+    problem: "derives `Reflect` and implements `Asset` but is missing `#[reflect(Asset)]`",
+    description: "Assets that derive `Reflect` need `ReflectAsset` so that inspectors and asset \
+        tooling can look up, read and change them in `Assets<A>` through a handle. \
+        `app.register_asset_reflect::<A>()` registers it along with `ReflectHandle` for \
+        `Handle<A>`; `#[reflect(Asset)]` registers it on its own.",
+    hint: "register the asset with `app.register_asset_reflect::<A>()`, or add `Asset` to the \
+        type's `#[reflect(...)]`, which `--fix` does for you",
+    explanation: include_str!("../docs/rules/missing-reflect-asset.md"),
+    default_severity: Severity::Warning,
+    enabled_by_default: true,
+    applies: |ty| {
+        ty.attrs.derives("Reflect") && ty.implements("Asset") && !ty.has_type_data("Asset")
+    },
+    detail: None,
+    field_detail: None,
+    fix: Some(&["Asset"]),
+    since: Some((0, 12)),
+    until: None,
+};
+
+pub const MISSING_REFLECT_DEFAULT: Check = Check {
+    id: "missing-reflect-default",
+    name: "MissingReflectDefault",
+    problem: "derives `Reflect` and implements `Default` but is missing `#[reflect(Default)]`",
+    description: "Types that implement `Default` should register `ReflectDefault` via \
+        `#[reflect(Default)]`. Scene deserialization and editors use it to construct values \
+        with missing fields; without it, they silently fall back to requiring every field.",
+    hint: "add `Default` to the type's `#[reflect(...)]`, `--fix` does this for you",
+    explanation: include_str!("../docs/rules/missing-reflect-default.md"),
+    default_severity: Severity::Warning,
+    enabled_by_default: true,
+    applies: |ty| ty.missing_reflect_for("Default"),
+    detail: None,
+    field_detail: None,
+    fix: Some(&["Default"]),
+    since: None,
+    until: None,
+};
+
+pub const MISSING_REFLECT_DEBUG: Check = Check {
+    id: "missing-reflect-debug",
+    name: "MissingReflectDebug",
+    problem: "is a component that derives `Reflect` and implements `Debug` but is missing \
+        `#[reflect(Debug)]`",
+    description: "With `#[reflect(Debug)]`, the `Reflect` derive formats the value with its own \
+        `Debug` impl. Without it, `PartialReflect::debug` falls back to printing it field by \
+        field, so inspectors, logs and remote tooling show the raw structure instead of the \
+        readable output the impl was written for.",
+    hint: "add `Debug` to the type's `#[reflect(...)]`, `--fix` does this for you",
+    explanation: include_str!("../docs/rules/missing-reflect-debug.md"),
+    default_severity: Severity::Info,
+    enabled_by_default: false,
+    applies: |ty| ty.implements("Component") && ty.missing_reflect_for("Debug"),
+    detail: None,
+    field_detail: None,
+    fix: Some(&["Debug"]),
+    since: None,
+    until: None,
+};
+
+pub const MISSING_REFLECT_SERIALIZE: Check = Check {
+    id: "missing-reflect-serialize",
+    name: "MissingReflectSerialize",
+    problem: "derives `Reflect` and implements `Serialize` but is missing `#[reflect(Serialize)]`",
+    description: "Types that derive serde's `Serialize` should register `ReflectSerialize` via \
+        `#[reflect(Serialize)]`. Otherwise scenes serialize them field by field through \
+        reflection, which ignores serde attributes and produces a different format.",
+    hint: "add `Serialize` to the type's `#[reflect(...)]`, `--fix` does this for you",
+    explanation: include_str!("../docs/rules/missing-reflect-serialize.md"),
+    default_severity: Severity::Warning,
+    enabled_by_default: true,
+    applies: |ty| ty.missing_reflect_for("Serialize") && !ty.attrs.reflects("opaque"),
+    detail: None,
+    field_detail: None,
+    fix: Some(&["Serialize"]),
+    since: None,
+    until: None,
+};
+
+pub const MISSING_REFLECT_DESERIALIZE: Check = Check {
+    id: "missing-reflect-deserialize",
+    name: "MissingReflectDeserialize",
+    problem: "derives `Reflect` and implements `Deserialize` but is missing `#[reflect(Deserialize)]`",
+    description: "Types that derive serde's `Deserialize` should register \
+        `ReflectDeserialize` via `#[reflect(Deserialize)]`. Otherwise scenes deserialize them \
+        field by field through reflection, which can't read data written by their serde \
+        implementation and breaks the round trip.",
+    hint: "add `Deserialize` to the type's `#[reflect(...)]`, `--fix` does this for you",
+    explanation: include_str!("../docs/rules/missing-reflect-deserialize.md"),
+    default_severity: Severity::Warning,
+    enabled_by_default: true,
+    applies: |ty| ty.missing_reflect_for("Deserialize") && !ty.attrs.reflects("opaque"),
+    detail: None,
+    field_detail: None,
+    fix: Some(&["Deserialize"]),
+    since: None,
+    until: None,
+};
+
+pub const OPAQUE_MISSING_SERDE: Check = Check {
+    id: "opaque-missing-serde",
+    name: "OpaqueMissingSerde",
+    problem: "is reflected as an opaque value and implements serde's traits, but doesn't \
+        register them",
+    description: "Types with `#[reflect_value]` or `#[reflect(opaque)]` are reflected as a \
+        whole, so scenes can only save and load them through `ReflectSerialize` and \
+        `ReflectDeserialize`. Without `Serialize, Deserialize` in the attribute, saving a scene \
+        with the type fails, even though the type implements both.",
+    hint: "add the missing serde traits to the type's `#[reflect_value(...)]` or \
+        `#[reflect(opaque, ...)]`",
+    explanation: include_str!("../docs/rules/opaque-missing-serde.md"),
+    default_severity: Severity::Error,
+    enabled_by_default: true,
+    applies: |ty| {
+        ty.attrs.derives("Reflect")
+            && ty.attrs.reflects("opaque")
+            && ["Serialize", "Deserialize"]
+                .iter()
+                .any(|name| ty.implements(name) && !ty.attrs.reflects(name))
+    },
+    detail: Some(|ty| {
+        ["Serialize", "Deserialize"]
+            .iter()
+            .filter(|name| ty.implements(name) && !ty.attrs.reflects(name))
+            .map(|name| format!("`{name}`"))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }),
+    field_detail: None,
+    fix: None,
+    since: None,
+    until: None,
+};
+
+pub const MISSING_TYPE_PATH: Check = Check {
+    id: "missing-type-path",
+    name: "MissingTypePath",
+    problem: "implements `Reflect` by hand but doesn't implement `TypePath`",
+    description: "Reflection requires `TypePath`, which the `Reflect` derive \
+        provides but a hand-written impl doesn't. Add `#[derive(TypePath)]`, \
+        `impl_type_path!(...)` or an `impl TypePath` for the type, otherwise the impl doesn't \
+        compile and the type can't be registered.",
+    hint: "add `#[derive(TypePath)]`, implement `TypePath` or use `impl_type_path!`",
+    explanation: include_str!("../docs/rules/missing-type-path.md"),
+    default_severity: Severity::Error,
+    enabled_by_default: true,
+    applies: |ty| {
+        (ty.manual_impls.contains("Reflect") || ty.manual_impls.contains("PartialReflect"))
+            && !ty.attrs.derives("Reflect")
+            && !ty.implements("TypePath")
+    },
+    detail: None,
+    field_detail: None,
+    fix: None,
+    since: Some((0, 11)),
+    until: None,
+};
+
+pub const UNREGISTERED_TYPE: Check = Check {
+    id: "unregistered-type",
+    name: "UnregisteredType",
+    problem: "implements `Reflect` but is never registered with `register_type`",
+    description: "Reflected types have to be registered with `app.register_type::<T>()` to show \
+        up in the type registry. Unregistered types can't be loaded from scenes and are \
+        invisible to editors. Types that are only used as fields of registered types are \
+        registered along with them. Registrations are often left to the application using a \
+        library, so this check is opt-in.",
+    hint: "call `app.register_type::<T>()` in the plugin that sets up the type",
+    explanation: include_str!("../docs/rules/unregistered-type.md"),
+    default_severity: Severity::Warning,
+    enabled_by_default: false,
+    applies: |ty| ty.implements("Reflect") && !ty.registered,
+    detail: None,
+    field_detail: None,
+    fix: None,
+    since: None,
+    until: None,
+};
+
+pub const NOT_REGISTERED_BY_PLUGIN: Check = Check {
+    id: "not-registered-by-plugin",
+    name: "NotRegisteredByPlugin",
+    problem: "is a reflected public component that none of its crate's plugins registers",
+    description: "Crates that register their types in a plugin's `build` usually mean to register \
+        all of them, often behind a feature. A public component that derives `Reflect` but isn't \
+        passed to `register_type` in any `impl Plugin` block, neither directly nor as a field of \
+        a registered type, is missing from the type registry of apps that use the plugin, so \
+        scenes can't contain it. Only crates with at least one such registration are checked.",
+    hint: "register the type in the crate's plugin with `app.register_type::<T>()`",
+    explanation: include_str!("../docs/rules/not-registered-by-plugin.md"),
+    default_severity: Severity::Warning,
+    enabled_by_default: false,
+    applies: |ty| {
+        ty.missing_plugin_registration
+            && ty.visibility == Visibility::Public
+            && ty.implements("Reflect")
+            && ty.implements("Component")
+    },
+    detail: None,
+    field_detail: None,
+    fix: None,
+    since: None,
+    until: None,
+};
+
+pub const UNREGISTERED_INSTANCE: Check = Check {
+    id: "unregistered-instance",
+    name: "UnregisteredInstance",
+    problem: "is registered, but not with all the generic arguments it's used with",
+    description: "Every instance of a generic type is a type of its own in the type registry, \
+        so registering `Foo<u32>` doesn't register `Foo<f32>`. Instances used with concrete \
+        arguments anywhere in the scanned code, e.g. in a query or a field, have to be \
+        registered as well, either directly or as a field of a registered type. Registering \
+        the type with generic arguments, like in a generic plugin, counts for all of its \
+        instances. Like `unregistered-type`, this check is opt-in.",
+    hint: "register each used instance, e.g. `app.register_type::<Foo<f32>>()`, or register the \
+        type in a generic plugin",
+    explanation: include_str!("../docs/rules/unregistered-instance.md"),
+    default_severity: Severity::Warning,
+    enabled_by_default: false,
+    applies: |ty| {
+        ty.implements("Reflect") && ty.registered && !ty.unregistered_instances.is_empty()
+    },
+    detail: Some(|ty| {
+        ty.unregistered_instances
+            .iter()
+            .map(|instance| format!("`{instance}`"))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }),
+    field_detail: None,
+    fix: None,
+    since: None,
+    until: None,
+};
+
+pub const FROM_REFLECT_DISABLED: Check = Check {
+    id: "from-reflect-disabled",
+    name: "FromReflectDisabled",
+    problem: "is a component or resource with `#[reflect(from_reflect = false)]`, so scenes \
+        can't construct it",
+    description: "Scenes insert components and resources by building them with `FromReflect`, \
+        falling back to `ReflectDefault` or `ReflectFromWorld`. A type that opts out of \
+        `FromReflect` without registering either can be saved, but loading it fails. Intentional \
+        cases can be allowed with `allow-from-reflect-false` in the configuration.",
+    hint: "remove `from_reflect = false`, or add `Default` or `FromWorld` to the type's \
+        `#[reflect(...)]`",
+    explanation: include_str!("../docs/rules/from-reflect-disabled.md"),
+    default_severity: Severity::Warning,
+    enabled_by_default: true,
+    applies: |ty| {
+        ty.lacks_scene_constructor() && (ty.implements("Component") || ty.implements("Resource"))
+    },
+    detail: None,
+    field_detail: None,
+    fix: None,
+    since: None,
+    until: None,
+};
+
+pub const COMPONENT_NOT_REFLECTED: Check = Check {
+    id: "component-not-reflected",
+    name: "ComponentNotReflected",
+    problem: "is a `Component` but doesn't implement `Reflect`",
+    description: "Components that don't implement `Reflect` are invisible to scenes, editors \
+        and inspectors. This is often intentional, so this check is opt-in; use it to audit \
+        the reflection coverage of a crate.",
+    hint: "derive `Reflect` and add `#[reflect(Component)]`, or make the type private if it's \
+        internal",
+    explanation: include_str!("../docs/rules/component-not-reflected.md"),
+    default_severity: Severity::Info,
+    enabled_by_default: false,
+    applies: |ty| ty.implements("Component") && !ty.implements("Reflect"),
+    detail: None,
+    field_detail: None,
+    fix: None,
+    since: None,
+    until: None,
+};
+
+pub const OBSERVED_COMPONENT_NOT_REFLECTED: Check = Check {
+    id: "observed-component-not-reflected",
+    name: "ObservedComponentNotReflected",
+    problem: "has component hooks or is watched by observers but doesn't implement `Reflect`",
+    description: "Hooks and observers run logic whenever a component is added, inserted or \
+        removed, which is hard to follow without seeing the component's data. Components that \
+        don't implement `Reflect` are invisible to inspectors and editors, so event-driven code \
+        around them can't be debugged that way. Hooks come from `#[component(on_add = ...)]` \
+        and its siblings or `register_component_hooks::<T>()`, observers from their \
+        `Trigger<E, T>` parameter.",
+    hint: "derive `Reflect` and add `#[reflect(Component)]`",
+    explanation: include_str!("../docs/rules/observed-component-not-reflected.md"),
+    default_severity: Severity::Warning,
+    enabled_by_default: true,
+    applies: |ty| ty.observed && ty.implements("Component") && !ty.implements("Reflect"),
+    detail: None,
+    field_detail: None,
+    fix: None,
+    since: Some((0, 14)),
+    until: None,
+};
+
+pub const STATES_NOT_REFLECTED: Check = Check {
+    id: "states-not-reflected",
+    name: "StatesNotReflected",
+    problem: "is a `States` type but doesn't implement `Reflect`",
+    description: "States that don't implement `Reflect` can't be shown or changed by editors \
+        and inspectors, which makes the current state of the app hard to follow. Like \
+        `component-not-reflected`, this check is opt-in; use it to audit the reflection \
+        coverage of a crate.",
+    hint: "derive `Reflect`, add `#[reflect(State, FreelyMutableState)]` and register it with \
+        `app.register_type_mutable_state::<S>()`",
+    explanation: include_str!("../docs/rules/states-not-reflected.md"),
+    default_severity: Severity::Info,
+    enabled_by_default: false,
+    applies: |ty| ty.is_state() && !ty.implements("Reflect"),
+    detail: None,
+    field_detail: None,
+    fix: None,
+    since: Some((0, 14)),
+    until: None,
+};
+
+pub const ASSET_NOT_REFLECTED: Check = Check {
+    id: "asset-not-reflected",
+    name: "AssetNotReflected",
+    problem: "is an `Asset` but doesn't implement `Reflect`",
+    description: "Assets that only derive `TypePath`, as `Asset` requires, can't be shown or \
+        edited by inspectors, and asset tooling can't look into them. This is often \
+        intentional for large or opaque data, so this check is opt-in; use it to audit the \
+        reflection coverage of a crate.",
+    hint: "derive `Reflect` instead of `TypePath` and register it with \
+        `app.register_asset_reflect::<A>()`",
+    explanation: include_str!("../docs/rules/asset-not-reflected.md"),
+    default_severity: Severity::Info,
+    enabled_by_default: false,
+    applies: |ty| ty.implements("Asset") && !ty.implements("Reflect"),
+    detail: None,
+    field_detail: None,
+    fix: None,
+    since: Some((0, 12)),
+    until: None,
+};
+
+pub const REFLECT_COMPONENT_WITHOUT_COMPONENT: Check = Check {
+    id: "reflect-component-without-component",
+    name: "ReflectComponentWithoutComponent",
+    problem: "has `#[reflect(Component)]` but doesn't implement `Component`",
+    description: "`#[reflect(Component)]` registers `ReflectComponent`, which only exists for \
+        components. Without a `Component` derive or impl the type doesn't compile, and if the \
+        impl is hidden behind a macro, readers are misled. This is usually a copy-paste mistake, \
+        e.g. on a resource.",
+    hint: "derive `Component`, or remove `Component` from the type's `#[reflect(...)]`",
+    explanation: include_str!("../docs/rules/reflect-component-without-component.md"),
+    default_severity: Severity::Error,
+    enabled_by_default: true,
+    applies: |ty| ty.attrs.reflects("Component") && !ty.implements("Component"),
+    detail: None,
+    field_detail: None,
+    fix: None,
+    since: None,
+    until: None,
+};
+
+pub const MISSING_REFLECT_BOUNDS: Check = Check {
+    id: "missing-reflect-bounds",
+    name: "MissingReflectBounds",
+    problem: "derives `Reflect` but has type parameters without reflection bounds",
+    description: "The `Reflect` derive only implements reflection for type arguments that \
+        implement `TypePath`, and `Reflect` if they're used in fields. Without these bounds on \
+        the type, instances with other arguments silently aren't reflected, and generic code \
+        like a plugin can't register the type. Add the bounds (e.g. `T: Reflect + TypePath`) or \
+        state them with `#[reflect(where ...)]`. Bounds are matched by name; `Typed` and \
+        `Reflectable` cover both.",
+    hint: "add the missing bounds to the type parameters, or opt out with `#[reflect(where ...)]`",
+    explanation: include_str!("../docs/rules/missing-reflect-bounds.md"),
+    default_severity: Severity::Warning,
+    enabled_by_default: false,
+    applies: |ty| {
+        ty.attrs.derives("Reflect") && !ty.attrs.reflects("where") && !ty.missing_bounds.is_empty()
+    },
+    detail: Some(|ty| {
+        ty.missing_bounds
+            .iter()
+            .map(|(param, missing)| format!("`{param}` (`{}`)", missing.join(" + ")))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }),
+    field_detail: None,
+    fix: None,
+    since: None,
+    until: None,
+};
+
+pub const UNKNOWN_TYPE_DATA: Check = Check {
+    id: "unknown-type-data",
+    name: "UnknownTypeData",
+    problem: "has unknown type data in `#[reflect(...)]`",
+    description: "Every entry in `#[reflect(...)]` has to name type data, like `Component` for \
+        `ReflectComponent`, or one of the derive's options. The compiler reports a misspelled \
+        entry as a missing `Reflect*` type, and not at all behind a disabled `cfg_attr`. Entries \
+        are checked against the `Reflect*` types and `#[reflect_trait]`s in the scanned code \
+        plus a list of common ones, so type data from crates that aren't scanned shows up here \
+        as well.",
+    hint: "fix the name, or import the `Reflect*` type data it refers to",
+    explanation: include_str!("../docs/rules/unknown-type-data.md"),
+    default_severity: Severity::Warning,
+    enabled_by_default: true,
+    applies: |ty| !ty.unknown_type_data.is_empty(),
+    detail: Some(|ty| {
+        ty.unknown_type_data
+            .iter()
+            .map(|(name, suggestion)| match suggestion {
+                Some(suggestion) => format!("`{name}` (did you mean `{suggestion}`?)"),
+                None => format!("`{name}`"),
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    }),
+    field_detail: None,
+    fix: None,
+    since: None,
+    until: None,
+};
+
+pub const DUPLICATE_REFLECT_ENTRY: Check = Check {
+    id: "duplicate-reflect-entry",
+    name: "DuplicateReflectEntry",
+    problem: "lists type data more than once in `#[reflect(...)]`",
+    description: "Each entry in `#[reflect(...)]` registers its type data once, so repeating \
+        one, within an attribute, across several or between `#[reflect(...)]` and \
+        `#[reflect_value(...)]`, adds nothing. Newer `bevy_reflect` versions reject the \
+        duplicate with a derive error, and it often hints at a mix-up, like a second entry that \
+        was meant to be something else.",
+    hint: "remove the repeated entries, keeping one of each",
+    explanation: include_str!("../docs/rules/duplicate-reflect-entry.md"),
+    default_severity: Severity::Warning,
+    enabled_by_default: true,
+    applies: |ty| !ty.attrs.duplicate_reflect.is_empty(),
+    detail: Some(|ty| {
+        ty.attrs
+            .duplicate_reflect
+            .iter()
+            .map(|name| format!("`{name}`"))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }),
+    field_detail: None,
+    fix: None,
+    since: None,
+    until: None,
+};
+
+pub const MISSING_REFLECT_MAP_ENTITIES: Check = Check {
+    id: "missing-reflect-map-entities",
+    name: "MissingReflectMapEntities",
+    problem: "is a reflected component that implements `MapEntities` but is missing \
+        `#[reflect(MapEntities)]`",
+    description: "Entities get new ids when a scene is spawned, so components that store an \
+        `Entity` have to be remapped to point at the spawned entities instead of the ones the \
+        scene was saved from. Scenes only do that for components that register \
+        `ReflectMapEntities`, so without it the `MapEntities` impl is never called and the \
+        references silently point at the wrong entities or at none. Bevy 0.16 moved the mapping \
+        into the `Component` derive, so the check is skipped from there on.",
+    hint: "add `MapEntities` to the type's `#[reflect(...)]`",
+    explanation: include_str!("../docs/rules/missing-reflect-map-entities.md"),
+    default_severity: Severity::Warning,
+    enabled_by_default: true,
+    applies: |ty| ty.missing_reflect_for("MapEntities") && ty.implements("Component"),
+    detail: None,
+    field_detail: None,
+    fix: None,
+    since: None,
+    until: Some((0, 16)),
+};
+
+pub const ENTITY_FIELD_WITHOUT_MAP_ENTITIES: Check = Check {
+    id: "entity-field-without-map-entities",
+    name: "EntityFieldWithoutMapEntities",
+    problem: "is a reflected component with fields that hold an `Entity`, but doesn't implement \
+        `MapEntities`",
+    description: "Entities get new ids when a scene is spawned, and components that refer to \
+        other entities are remapped through their `MapEntities` impl. Without one, fields like \
+        `Entity`, `Option<Entity>` or `Vec<Entity>` keep the ids from when the scene was saved, \
+        which dangle or point at unrelated entities. Fields with `#[reflect(ignore)]` aren't \
+        saved and don't count. Bevy 0.16 moved the mapping into the `Component` derive, so the \
+        check is skipped from there on.",
+    hint: "implement `MapEntities` for the type and add `MapEntities` to its `#[reflect(...)]`, \
+        or mark the fields with `#[reflect(ignore)]` if they needn't be saved",
+    explanation: include_str!("../docs/rules/entity-field-without-map-entities.md"),
+    default_severity: Severity::Warning,
+    enabled_by_default: true,
+    applies: |ty| {
+        ty.attrs.derives("Reflect")
+            && ty.implements("Component")
+            && !ty.implements("MapEntities")
+            && !ty.entity_fields().is_empty()
+    },
+    detail: None,
+    field_detail: Some(|ty| {
+        ty.entity_fields()
+            .into_iter()
+            .map(|field| (field, String::new()))
+            .collect()
+    }),
+    fix: None,
+    since: None,
+    until: Some((0, 16)),
+};
+
+pub const FIELD_NOT_REFLECTED: Check = Check {
+    id: "field-not-reflected",
+    name: "FieldNotReflected",
+    problem: "derives `Reflect` but has fields whose types don't implement `Reflect`",
+    description: "All fields of a type that derives `Reflect` have to implement `Reflect` \
+        themselves, unless they're marked with `#[reflect(ignore)]`. Otherwise the derive fails \
+        to compile, or for generic types, the type silently loses its `Reflect` impl for those \
+        type arguments. Only field types defined in the scanned code are checked, matched by \
+        name.",
+    hint: "derive `Reflect` for the field's type, or mark the field with `#[reflect(ignore)]`",
+    explanation: include_str!("../docs/rules/field-not-reflected.md"),
+    default_severity: Severity::Warning,
+    enabled_by_default: true,
+    applies: |ty| !ty.non_reflect_fields.is_empty(),
+    detail: None,
+    field_detail: Some(|ty| {
+        ty.non_reflect_fields
+            .iter()
+            .map(|(field, type_name)| (field.as_str(), format!("`{type_name}`")))
+            .collect()
+    }),
+    fix: None,
+    since: None,
+    until: None,
+};
+
+pub const NON_STATIC_REFLECT_FIELD: Check = Check {
+    id: "non-static-reflect-field",
+    name: "NonStaticReflectField",
+    problem: "derives `Reflect` but has fields that borrow with a lifetime other than `'static`",
+    description: "`Reflect` requires `'static`, as reflected values are stored and downcast \
+        through `Any`. A type with references or other borrowed data in its fields can't \
+        implement it, and the derive fails with errors about lifetimes that don't point at the \
+        fields. `#[reflect(ignore)]` doesn't help, the type as a whole has to be `'static`. Opaque \
+        types are skipped, as their fields aren't reflected.",
+    hint: "store owned data like `String` or `Vec` instead of references, or an `Entity` or \
+        `Handle` to what the reference pointed at",
+    explanation: include_str!("../docs/rules/non-static-reflect-field.md"),
+    default_severity: Severity::Error,
+    enabled_by_default: true,
+    applies: |ty| {
+        ty.attrs.derives("Reflect")
+            && !ty.attrs.reflects("opaque")
+            && ty.fields.iter().any(|field| !field.lifetimes.is_empty())
+    },
+    detail: None,
+    field_detail: Some(|ty| {
+        ty.fields
+            .iter()
+            .filter(|field| !field.lifetimes.is_empty())
+            .map(|field| {
+                let lifetimes = field
+                    .lifetimes
+                    .iter()
+                    .map(|lifetime| format!("`{lifetime}`"))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                (field.name.as_str(), format!("borrows for {lifetimes}"))
+            })
+            .collect()
+    }),
+    fix: None,
+    since: None,
+    until: None,
+};
+
+pub const BUNDLE_COMPONENT_NOT_REFLECTED: Check = Check {
+    id: "bundle-component-not-reflected",
+    name: "BundleComponentNotReflected",
+    problem: "derives `Bundle` but has components that aren't fully reflected",
+    description: "Every component of a bundle needs to derive `Reflect` with \
+        `#[reflect(Component)]` for entities spawned with it to be saved to and loaded from \
+        scenes. A single member without it is silently missing from the scene. Only field types \
+        defined in the scanned code are checked, matched by name; fields with \
+        `#[bundle(ignore)]` are skipped.",
+    hint: "derive `Reflect` with `#[reflect(Component)]` for the component, or mark the field \
+        with `#[bundle(ignore)]`",
+    explanation: include_str!("../docs/rules/bundle-component-not-reflected.md"),
+    default_severity: Severity::Warning,
+    enabled_by_default: true,
+    applies: |ty| !ty.unreflected_bundle_fields.is_empty(),
+    detail: Some(|ty| {
+        ty.unreflected_bundle_fields
+            .iter()
+            .map(|(field, (type_name, reflect))| {
+                if *reflect {
+                    format!("`{field}` (`{type_name}` lacks `#[reflect(Component)]`)")
+                } else {
+                    format!("`{field}` (`{type_name}` doesn't implement `Reflect`)")
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    }),
+    field_detail: None,
+    fix: None,
+    since: None,
+    until: None,
+};
+
+pub const REQUIRED_COMPONENT_NOT_REFLECTED: Check = Check {
+    id: "required-component-not-reflected",
+    name: "RequiredComponentNotReflected",
+    problem: "is a reflected component that requires components that aren't fully reflected",
+    description: "Components listed in `#[require(...)]` are inserted whenever the requiring \
+        component is, including when it's spawned from a scene. If a required component doesn't \
+        derive `Reflect` with `#[reflect(Component)]`, it's missing when the entity is saved \
+        again and invisible to inspectors. Only required components defined in the scanned code \
+        are checked, matched by name. Whether they're registered is up to `unregistered-type`, \
+        like for any other type.",
+    hint: "derive `Reflect` with `#[reflect(Component)]` for the required component",
+    explanation: include_str!("../docs/rules/required-component-not-reflected.md"),
+    default_severity: Severity::Warning,
+    enabled_by_default: true,
+    applies: |ty| !ty.unreflected_required.is_empty(),
+    detail: Some(|ty| {
+        ty.unreflected_required
+            .iter()
+            .map(|(name, reflect)| {
+                if *reflect {
+                    format!("`{name}` lacks `#[reflect(Component)]`")
+                } else {
+                    format!("`{name}` doesn't implement `Reflect`")
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    }),
+    field_detail: None,
+    fix: None,
+    since: Some((0, 15)),
+    until: None,
+};
+
+pub const IGNORED_FIELD_WITHOUT_DEFAULT: Check = Check {
+    id: "ignored-field-without-default",
+    name: "IgnoredFieldWithoutDefault",
+    problem: "has `#[reflect(ignore)]` fields without `#[reflect(default)]`",
+    description: "When a scene is loaded, components and resources are built through \
+        `FromReflect`, which fills ignored fields with `Default::default()` of their type. \
+        Add `#[reflect(default = \"path::to::fn\")]` to the field, or `#[reflect(Default)]` to \
+        the type, to choose the value explicitly. If the field's type doesn't implement \
+        `Default`, the derive doesn't compile at all, or needs `#[reflect(from_reflect = false)]` \
+        which makes the type impossible to load from scenes.",
+    hint: "add `#[reflect(default)]` to the ignored fields, or `#[reflect(Default)]` to the type",
+    explanation: include_str!("../docs/rules/ignored-field-without-default.md"),
+    default_severity: Severity::Warning,
+    enabled_by_default: true,
+    applies: |ty| !ty.ignored_without_default.is_empty(),
+    detail: None,
+    field_detail: Some(|ty| {
+        ty.ignored_without_default
+            .iter()
+            .map(|(field, lacks_default)| {
+                let note = if *lacks_default {
+                    "its type doesn't implement `Default`"
+                } else {
+                    ""
+                };
+                (field.as_str(), note.to_string())
+            })
+            .collect()
+    }),
+    fix: None,
+    since: Some((0, 12)),
+    until: None,
+};
+
+pub const UNLOADABLE_SCENE_TYPE: Check = Check {
+    id: "unloadable-scene-type",
+    name: "UnloadableSceneType",
+    problem: "is in a scene file but can't be loaded from it",
+    description: "Scene files in `assets` name resources and components by their type path. \
+        Loading the scene fails if a type doesn't exist anymore, isn't registered with \
+        `register_type` or lacks `#[reflect(Component)]` or `#[reflect(Resource)]`. The `.scn.ron` \
+        files below the `assets` directories of the workspace and its members are parsed only \
+        when this opt-in check is enabled.",
+    hint: "register the type and reflect `Component` or `Resource`, or update the scene to the \
+        type's current path",
+    explanation: include_str!("../docs/rules/unloadable-scene-type.md"),
+    default_severity: Severity::Error,
+    enabled_by_default: false,
+    // Reported per scene entry rather than per type, see `scene::check_scene`
+    applies: |_| false,
+    detail: None,
+    field_detail: None,
+    fix: None,
+    since: None,
+    until: None,
+};
+
+pub const MIXED_BEVY_VERSIONS: Check = Check {
+    id: "mixed-bevy-versions",
+    name: "MixedBevyVersions",
+    problem: "is in the dependency graph in more than one version",
+    description: "Each version of `bevy_reflect` and `bevy_ecs` has its own `Reflect` and \
+        `Component` traits and its own type registry. Types from a plugin built against another \
+        version than the app compile on their own, but can't be registered with the app's \
+        registry or inserted into its world, and reflection silently misses them. Reported once \
+        per crate, pointing at its entry in `Cargo.lock`.",
+    hint: "update or pin the dependencies named after each version so that they agree on one \
+        Bevy version, `cargo tree -i <crate>` shows where each comes from",
+    explanation: include_str!("../docs/rules/mixed-bevy-versions.md"),
+    default_severity: Severity::Error,
+    enabled_by_default: true,
+    // Reported per crate from the dependency graph rather than per type, see
+    // `versions::mixed_versions`
+    applies: |_| false,
+    detail: None,
+    field_detail: None,
+    fix: None,
+    since: None,
+    until: None,
+};
+
+/// All checks known to the tool.
+pub const ALL: &[&Check] = &[
+    &MISSING_REFLECT_COMPONENT,
+    &MISSING_REFLECT_RESOURCE,
+    &MISSING_REFLECT_EVENT,
+    &MISSING_REFLECT_STATE,
+    &MISSING_REFLECT_ASSET,
+    &MISSING_REFLECT_DEFAULT,
+    &MISSING_REFLECT_DEBUG,
+    &MISSING_REFLECT_SERIALIZE,
+    &MISSING_REFLECT_DESERIALIZE,
+    &OPAQUE_MISSING_SERDE,
+    &MISSING_TYPE_PATH,
+    &UNREGISTERED_TYPE,
+    &UNREGISTERED_INSTANCE,
+    &NOT_REGISTERED_BY_PLUGIN,
+    &MISSING_REFLECT_BOUNDS,
+    &UNKNOWN_TYPE_DATA,
+    &DUPLICATE_REFLECT_ENTRY,
+    &MISSING_REFLECT_MAP_ENTITIES,
+    &ENTITY_FIELD_WITHOUT_MAP_ENTITIES,
+    &FIELD_NOT_REFLECTED,
+    &NON_STATIC_REFLECT_FIELD,
+    &BUNDLE_COMPONENT_NOT_REFLECTED,
+    &REQUIRED_COMPONENT_NOT_REFLECTED,
+    &IGNORED_FIELD_WITHOUT_DEFAULT,
+    &FROM_REFLECT_DISABLED,
+    &COMPONENT_NOT_REFLECTED,
+    &OBSERVED_COMPONENT_NOT_REFLECTED,
+    &STATES_NOT_REFLECTED,
+    &ASSET_NOT_REFLECTED,
+    &REFLECT_COMPONENT_WITHOUT_COMPONENT,
+    &UNLOADABLE_SCENE_TYPE,
+    &MIXED_BEVY_VERSIONS,
+];
+
+/// Identifiers of all checks, for validating command line arguments.
+pub fn ids() -> impl Iterator<Item = &'static str> {
+    ALL.iter().map(|check| check.id)
+}
+
+/// Look up a check by its identifier.
+pub fn by_id(id: &str) -> Option<&'static Check> {
+    ALL.iter().copied().find(|check| check.id == id)
+}
@@ -0,0 +1,347 @@
+use bevy_reflect_check::output::OutputFormat;
+use bevy_reflect_check::{Backend, Severity, Visibility, checks};
+use cargo_metadata::semver::Version;
+use clap::builder::PossibleValuesParser;
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use serde::Deserialize;
+use std::path::PathBuf;
+
+/// Pseudo rule for `--deny` that turns files that couldn't be parsed into a failure.
+pub const PARSE_ERRORS: &str = "parse-errors";
+
+/// Checks that reflected Bevy components are registered as `ReflectComponent`.
+#[derive(Debug, Parser)]
+#[command(
+    name = "cargo-reflect-check",
+    bin_name = "cargo reflect-check",
+    version,
+    about
+)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    #[command(flatten)]
+    pub scan: ScanArgs,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Report types with incomplete reflection setup, e.g. missing `#[reflect(Component)]` (default).
+    Check,
+    /// List all types that derive `Reflect`.
+    List,
+    /// Report findings grouped by crate, with a count per crate.
+    Report,
+    /// Compare two JSON reports (`--format json`) and show which findings were added or removed.
+    Diff {
+        /// Report of the earlier run, e.g. from the target branch.
+        old: PathBuf,
+        /// Report of the later run, e.g. from the pull request.
+        new: PathBuf,
+    },
+    /// Check a single package from the dependency graph on its own, e.g. an upstream crate
+    /// before filing issues against it.
+    Crate {
+        /// The package name, e.g. `bevy_sprite`.
+        name: String,
+    },
+    /// Keep checking and reprint the findings whenever a source file changes.
+    Watch,
+    /// Run a language server on stdin and stdout that shows the findings in the editor.
+    Lsp,
+    /// Browse the findings interactively, and ignore or fix them one at a time.
+    #[cfg(feature = "tui")]
+    Tui,
+    /// Show the documentation of a check, with examples of flagged and fixed code.
+    Explain {
+        /// The check's identifier, e.g. `missing-reflect-component`.
+        #[arg(value_parser = PossibleValuesParser::new(checks::ids()))]
+        rule: String,
+    },
+    /// Check that every resource and component in a scene file can be loaded.
+    ValidateScene {
+        /// The `.scn.ron` file.
+        scene: PathBuf,
+    },
+}
+
+/// Options shared by all subcommands.
+///
+/// Most of these can also be set in `reflect-check.toml`; the command line takes precedence.
+#[derive(Debug, Args)]
+pub struct ScanArgs {
+    /// Source trees to scan as crates of their own without cargo metadata, e.g. a vendored
+    /// checkout. A directory with a `Cargo.toml` stands for its `src` directory.
+    #[arg(value_name = "PATHS", global = true)]
+    pub paths: Vec<String>,
+
+    /// Crate name for the given paths [default: the package name from their `Cargo.toml`, or
+    /// the directory name].
+    #[arg(long, global = true)]
+    pub crate_name: Option<String>,
+
+    /// Crate root file for the given paths [default: their `lib.rs` or `main.rs`].
+    #[arg(long, global = true)]
+    pub crate_root: Option<PathBuf>,
+
+    /// Configuration file [default: reflect-check.toml in the workspace root, if present].
+    #[arg(long, global = true)]
+    pub config: Option<PathBuf>,
+
+    /// Path to the `Cargo.toml` of the project to scan [default: $CARGO_MANIFEST_DIR/Cargo.toml,
+    /// or the one cargo finds from the current directory].
+    #[arg(long, global = true)]
+    pub manifest_path: Option<PathBuf>,
+
+    /// Source directory to scan instead of the workspace [default: `src` of every workspace member].
+    #[arg(long, global = true)]
+    pub root: Option<String>,
+
+    /// Only scan dependencies whose package name matches this glob (repeatable) [default: bevy_*].
+    #[arg(long, value_name = "GLOB", global = true)]
+    pub deps: Vec<String>,
+
+    /// Scan all dependencies.
+    #[arg(long, conflicts_with = "deps", global = true)]
+    pub all_deps: bool,
+
+    /// Don't scan any dependencies, only the current project.
+    #[arg(long, conflicts_with_all = ["deps", "all_deps"], global = true)]
+    pub no_deps: bool,
+
+    /// Enable a check that is off by default (repeatable). Custom rules of the configuration
+    /// can be named here and in the options below as well.
+    #[arg(long = "rule", value_name = "RULE", global = true)]
+    pub rules: Vec<String>,
+
+    /// Disable a check (repeatable).
+    #[arg(long = "no-rule", value_name = "RULE", global = true)]
+    pub disabled_rules: Vec<String>,
+
+    /// Enable a check and report its findings as errors (repeatable). `parse-errors` fails the
+    /// run if any source file couldn't be parsed.
+    #[arg(long, value_name = "RULE", global = true)]
+    pub deny: Vec<String>,
+
+    /// Enable a check and report its findings as warnings (repeatable).
+    #[arg(long, value_name = "RULE", global = true)]
+    pub warn: Vec<String>,
+
+    /// Disable a check, like `--no-rule` (repeatable).
+    #[arg(long, value_name = "RULE", global = true)]
+    pub allow: Vec<String>,
+
+    /// Features to evaluate `#[cfg(feature = ...)]` with, in addition to the default features.
+    /// Use `package/feature` for dependencies (comma separated or repeatable). Features of the
+    /// current project are passed on to `cargo metadata`.
+    #[arg(short = 'F', long, value_delimiter = ',', global = true)]
+    pub features: Vec<String>,
+
+    /// Treat all features of all crates as enabled.
+    #[arg(long, global = true)]
+    pub all_features: bool,
+
+    /// Don't enable the default features of the current project.
+    #[arg(long, global = true)]
+    pub no_default_features: bool,
+
+    /// Target triple to evaluate `#[cfg(...)]` for [default: the host].
+    #[arg(long, global = true)]
+    pub target: Option<String>,
+
+    /// Number of threads used for parsing [default: number of CPUs].
+    #[arg(short, long, global = true)]
+    pub jobs: Option<usize>,
+
+    /// Run `cargo metadata` without accessing the network. If it fails anyway, only the
+    /// workspace members listed in the manifests are scanned, without dependencies.
+    #[arg(long, global = true)]
+    pub offline: bool,
+
+    /// Don't read or write the analysis cache in `target/reflect-check`.
+    #[arg(long, global = true)]
+    pub no_cache: bool,
+
+    /// Only report findings that aren't in this baseline file. If it doesn't exist yet, it's
+    /// created from the current findings.
+    #[arg(long, global = true)]
+    pub baseline: Option<PathBuf>,
+
+    /// Overwrite the baseline file with the current findings.
+    #[arg(long, requires = "baseline", global = true)]
+    pub update_baseline: bool,
+
+    /// Check the content of a single file read from stdin instead of scanning the workspace, e.g.
+    /// an editor's unsaved buffer. Findings are printed as JSON unless `--format` says otherwise.
+    #[arg(long, requires = "file_path", conflicts_with = "fix", global = true)]
+    pub stdin: bool,
+
+    /// Where the file read with `--stdin` lives, which decides its crate and module path.
+    #[arg(long, value_name = "PATH", requires = "stdin", global = true)]
+    pub file_path: Option<PathBuf>,
+
+    /// Add missing `#[reflect(...)]` type data to the source files of the current workspace.
+    #[arg(long, global = true)]
+    pub fix: bool,
+
+    /// With `--fix`, print a diff of the changes instead of writing them.
+    #[arg(long, requires = "fix", global = true)]
+    pub dry_run: bool,
+
+    /// Only report findings in workspace members.
+    #[arg(long, conflicts_with = "only_deps", global = true)]
+    pub only_workspace: bool,
+
+    /// Only report findings in dependencies.
+    #[arg(long, global = true)]
+    pub only_deps: bool,
+
+    /// Only report findings for types whose fully qualified path matches this glob, e.g.
+    /// `my_game::enemies::*` (repeatable).
+    #[arg(long, value_name = "GLOB", global = true)]
+    pub include: Vec<String>,
+
+    /// Don't report findings for types whose fully qualified path matches this glob, e.g.
+    /// `*Internal` (repeatable).
+    #[arg(long, value_name = "GLOB", global = true)]
+    pub exclude: Vec<String>,
+
+    /// Skip directories with a name matching this glob, e.g. `generated` (repeatable). The
+    /// `examples`, `tests` and `benches` directories next to a `Cargo.toml` are skipped unless
+    /// they're included with the options below.
+    #[arg(long = "exclude-dir", value_name = "GLOB", global = true)]
+    pub exclude_dirs: Vec<String>,
+
+    /// Scan the examples of workspace members, each as a crate of its own.
+    #[arg(long, global = true)]
+    pub include_examples: bool,
+
+    /// Scan the integration tests of workspace members, each as a crate of its own.
+    #[arg(long, global = true)]
+    pub include_tests: bool,
+
+    /// Scan the benchmarks of workspace members, each as a crate of its own.
+    #[arg(long, global = true)]
+    pub include_benches: bool,
+
+    /// Scan the libraries of workspace members with their macros expanded by rustc, to see
+    /// types that macros generate. Slower, as dependencies have to be checked.
+    #[arg(long, global = true)]
+    pub expand: bool,
+
+    /// Where the visibility, re-exports and trait impls of workspace types come from. The
+    /// rustdoc backend documents each library, so the code has to compile.
+    #[arg(
+        long,
+        value_enum,
+        default_value_t,
+        global = true,
+        conflicts_with = "expand"
+    )]
+    pub backend: Backend,
+
+    /// Bevy version to adjust the checks to, like `0.12`, instead of the `bevy_reflect` version
+    /// cargo resolved. Checks for things that didn't exist yet in that version are skipped.
+    #[arg(long, value_name = "VERSION", value_parser = parse_bevy_version, global = true)]
+    pub bevy_version: Option<Version>,
+
+    /// Which types to check, by how far they can be seen [default: public].
+    #[arg(long, value_enum, global = true)]
+    pub visibility: Option<VisibilityFilter>,
+
+    /// Exit with status 1 if there are findings of at least this severity [default: any].
+    #[arg(long, value_enum, global = true)]
+    pub fail_on: Option<FailOn>,
+
+    /// Print reflection coverage per crate and the modules with the most findings instead of the
+    /// findings themselves.
+    #[arg(long, global = true)]
+    pub stats: bool,
+
+    /// Print an estimate of the type registry's memory and startup cost per crate, counting
+    /// every type that derives `Reflect` whatever its visibility, instead of the findings.
+    #[arg(long, conflicts_with = "stats", global = true)]
+    pub registry_size: bool,
+
+    /// Print how long fetching metadata, collecting and parsing the files, and each rule took
+    /// to stderr, along with the number of files and crates.
+    #[arg(long, global = true)]
+    pub timings: bool,
+
+    /// Write an issue draft in Markdown for each dependency package with findings into this
+    /// directory, for reporting them upstream.
+    #[arg(long, value_name = "DIR", global = true)]
+    pub emit_issue_drafts: Option<PathBuf>,
+
+    /// Output format [default: text].
+    #[arg(long, value_enum, global = true)]
+    pub format: Option<OutputFormat>,
+
+    /// Log each parsed crate and a summary of the run to stderr.
+    #[arg(short, long, global = true)]
+    pub verbose: bool,
+
+    /// Don't show progress, warnings or summaries on stderr.
+    #[arg(short, long, conflicts_with = "verbose", global = true)]
+    pub quiet: bool,
+}
+
+// A version with an optional patch level, `0.12` or `0.12.1`
+fn parse_bevy_version(value: &str) -> Result<Version, String> {
+    let full = if value.matches('.').count() == 1 {
+        format!("{value}.0")
+    } else {
+        value.to_string()
+    };
+    Version::parse(&full).map_err(|err| err.to_string())
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum VisibilityFilter {
+    /// Types that other crates can name.
+    #[default]
+    Public,
+    /// Types that can be named anywhere in their crate, including `pub(crate)` ones.
+    Crate,
+    /// All types, including private ones.
+    All,
+}
+
+impl VisibilityFilter {
+    /// The least visibility a type needs to be checked.
+    pub fn min_visibility(self) -> Visibility {
+        match self {
+            VisibilityFilter::Public => Visibility::Public,
+            VisibilityFilter::Crate => Visibility::Crate,
+            VisibilityFilter::All => Visibility::Private,
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FailOn {
+    /// Fail on any finding.
+    #[default]
+    Any,
+    /// Only fail on warnings and errors, ignore informational findings.
+    Warning,
+    /// Only fail on errors.
+    Error,
+    /// Always exit with status 0.
+    Never,
+}
+
+impl FailOn {
+    /// The lowest severity that causes a failure, if any.
+    pub fn threshold(self) -> Option<Severity> {
+        match self {
+            FailOn::Any => Some(Severity::Info),
+            FailOn::Warning => Some(Severity::Warning),
+            FailOn::Error => Some(Severity::Error),
+            FailOn::Never => None,
+        }
+    }
+}
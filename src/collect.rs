@@ -0,0 +1,896 @@
+use crate::attrs::ReflectAttrs;
+use crate::cfg::CfgSet;
+use crate::exports::{Binding, BindingKind};
+use crate::finding::TypeLocation;
+use crate::modules::{ModDecl, Visibility};
+use crate::suppress::Suppressions;
+use crate::types::{Collected, FieldInfo, Instance, TypeInfo, VariantInfo};
+use proc_macro2::TokenTree;
+use quote::ToTokens;
+use std::collections::{BTreeMap, BTreeSet};
+use syn::visit::{self, Visit};
+use syn::{
+    Attribute, BoundLifetimes, Expr, ExprLit, ExprMethodCall, Fields, File, GenericArgument,
+    GenericParam, Generics, ImplItemFn, Item, ItemMacro, Lifetime, Lit, Meta, Path, PathArguments,
+    PathSegment, TraitItemFn, Type, TypeParamBound, UseTree, WherePredicate,
+};
+
+/// Accumulates types, trait impls, module declarations, names in scope and type registrations
+/// from the syntax tree of a file.
+///
+/// Type paths are relative to the file, as its place in the module tree is only known once all
+/// files have been parsed.
+#[derive(Debug)]
+pub struct Collector<'a> {
+    cfg: &'a CfgSet,
+    source: &'a str,
+    file_path: &'a str,
+    crate_name: Option<&'a str>,
+    /// Whether the file is the root of a crate, where private items are visible crate-wide.
+    is_crate_root: bool,
+    /// Derive names that stand for other traits, from the configuration and `use ... as ...`.
+    derive_aliases: BTreeMap<String, String>,
+    collected: Collected,
+}
+
+impl<'a> Collector<'a> {
+    pub fn new(
+        cfg: &'a CfgSet,
+        source: &'a str,
+        file_path: &'a str,
+        crate_name: Option<&'a str>,
+        is_crate_root: bool,
+        derive_aliases: &BTreeMap<String, String>,
+    ) -> Self {
+        Self {
+            cfg,
+            source,
+            file_path,
+            crate_name,
+            is_crate_root,
+            derive_aliases: derive_aliases.clone(),
+            collected: Collected::default(),
+        }
+    }
+
+    /// Recursively collect structs and enums along with their attributes and visibility, as well
+    /// as trait impls and `mod foo;` declarations. Items that are configured out via
+    /// `#[cfg(...)]` are skipped.
+    ///
+    /// `parent_visibility` is how far the module containing `file`'s items can be seen from
+    /// within this file; the module's own place in the crate is accounted for later.
+    pub fn collect_types(
+        &mut self,
+        file: &File,
+        inline_path: &[String],
+        parent_visibility: Visibility,
+    ) {
+        if inline_path.is_empty() {
+            collect_use_aliases(&file.items, &mut self.derive_aliases);
+        }
+        self.collect_items(&file.items, inline_path, parent_visibility);
+    }
+
+    // Collect the items of a module, borrowing those of inline modules rather than copying them
+    fn collect_items(
+        &mut self,
+        items: &[Item],
+        inline_path: &[String],
+        parent_visibility: Visibility,
+    ) {
+        for item in items {
+            let enabled = self.cfg.is_enabled(item_attrs(item));
+            let mut visibility = item_visibility(item);
+            if visibility == Visibility::Private && self.is_crate_root && inline_path.is_empty() {
+                visibility = Visibility::Crate;
+            }
+            let visibility = visibility.within(parent_visibility);
+            if enabled {
+                self.push_bindings(item, inline_path);
+            }
+            match item {
+                // Disabled module declarations are recorded so that their files can be skipped
+                Item::Mod(m) if m.content.is_none() => {
+                    self.collected
+                        .mods
+                        .entry(self.file_path.to_string())
+                        .or_default()
+                        .push(ModDecl {
+                            inline_path: inline_path.to_vec(),
+                            name: m.ident.to_string(),
+                            path_attr: path_attr(&m.attrs),
+                            visibility,
+                            enabled,
+                        });
+                }
+                _ if !enabled => {}
+                Item::Struct(s) => {
+                    let params = param_names(&s.generics);
+                    let fields = self.fields(None, &s.fields, &params);
+                    let missing_bounds = missing_bounds(&s.generics, &fields);
+                    self.push_type(&s.ident, &s.attrs, inline_path, visibility, params, fields)
+                        .missing_bounds = missing_bounds;
+                }
+                Item::Enum(e) => {
+                    let params = param_names(&e.generics);
+                    let variants: Vec<_> = e
+                        .variants
+                        .iter()
+                        .filter(|variant| self.cfg.is_enabled(&variant.attrs))
+                        .collect();
+                    let fields: Vec<_> = variants
+                        .iter()
+                        .flat_map(|variant| {
+                            self.fields(Some(&variant.ident), &variant.fields, &params)
+                        })
+                        .collect();
+                    let missing_bounds = missing_bounds(&e.generics, &fields);
+                    let ty =
+                        self.push_type(&e.ident, &e.attrs, inline_path, visibility, params, fields);
+                    ty.missing_bounds = missing_bounds;
+                    ty.variants = variants
+                        .iter()
+                        .map(|variant| VariantInfo {
+                            name: variant.ident.to_string(),
+                            span: variant.ident.span().into(),
+                        })
+                        .collect();
+                }
+                Item::Impl(i) => self.collected.impls.record(self.crate_name, i),
+                Item::Macro(m) => {
+                    if let Some(name) = macro_reflected_type(m) {
+                        self.collected.macro_reflected.insert(name);
+                    } else if let Some(name) = macro_type_path_type(m) {
+                        self.collected.macro_type_path.insert(name);
+                    }
+                }
+                // `#[reflect_trait]` generates `ReflectTrait`, usable as `#[reflect(Trait)]`
+                Item::Trait(t)
+                    if t.attrs
+                        .iter()
+                        .any(|attr| attr.path().is_ident("reflect_trait")) =>
+                {
+                    self.collected.type_data.insert(t.ident.to_string());
+                }
+                Item::Mod(m) => {
+                    if let Some((_, items)) = &m.content {
+                        let mut nested_path = inline_path.to_vec();
+                        nested_path.push(m.ident.to_string());
+                        self.collect_items(items, &nested_path, visibility);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    // Record the names the item introduces into its module
+    fn push_bindings(&mut self, item: &Item, inline_path: &[String]) {
+        let (vis, kinds) = match item {
+            Item::Struct(s) => (&s.vis, vec![BindingKind::Type(s.ident.to_string())]),
+            Item::Enum(e) => (&e.vis, vec![BindingKind::Type(e.ident.to_string())]),
+            Item::Mod(m) => (&m.vis, vec![BindingKind::Module(m.ident.to_string())]),
+            Item::Use(u) => {
+                let mut kinds = Vec::new();
+                flatten_use_tree(&u.tree, &mut Vec::new(), &mut kinds);
+                (&u.vis, kinds)
+            }
+            _ => return,
+        };
+        let bindings = self
+            .collected
+            .bindings
+            .entry(self.file_path.to_string())
+            .or_default();
+        bindings.extend(kinds.into_iter().map(|kind| Binding {
+            inline_path: inline_path.to_vec(),
+            visibility: Visibility::of(vis),
+            kind,
+        }));
+    }
+
+    fn push_type(
+        &mut self,
+        ident: &syn::Ident,
+        attrs: &[Attribute],
+        inline_path: &[String],
+        visibility: Visibility,
+        generics: Vec<String>,
+        fields: Vec<FieldInfo>,
+    ) -> &mut TypeInfo {
+        let ident_line = ident.span().start().line;
+        let first_line = attrs
+            .first()
+            .map_or(ident_line, |attr| attr.pound_token.span.start().line);
+        let mut type_path = inline_path.to_vec();
+        type_path.push(ident.to_string());
+        if let Some(name) = ident.to_string().strip_prefix("Reflect")
+            && !name.is_empty()
+        {
+            self.collected.type_data.insert(name.to_string());
+        }
+
+        self.collected.types.push(TypeInfo {
+            ident: ident.to_string(),
+            crate_name: self.crate_name.map(str::to_owned),
+            location: TypeLocation {
+                type_path: type_path.join("::"),
+                reexports: Vec::new(),
+                // Set along with the module path
+                definition_path: String::new(),
+                file: self.file_path.to_string(),
+                span: ident.span().into(),
+            },
+            visibility,
+            attrs: ReflectAttrs::parse(attrs, self.cfg, &self.derive_aliases),
+            generics,
+            manual_impls: BTreeSet::new(),
+            registered: false,
+            observed: false,
+            registered_type_data: BTreeSet::new(),
+            missing_plugin_registration: false,
+            unregistered_instances: BTreeSet::new(),
+            fields,
+            variants: Vec::new(),
+            missing_bounds: BTreeMap::new(),
+            non_reflect_fields: BTreeMap::new(),
+            ignored_without_default: BTreeMap::new(),
+            unreflected_bundle_fields: BTreeMap::new(),
+            unreflected_required: BTreeMap::new(),
+            unknown_type_data: BTreeMap::new(),
+            suppressions: Suppressions::parse(attrs, self.source, first_line, ident_line),
+        });
+        self.collected
+            .types
+            .last_mut()
+            .expect("type was just pushed")
+    }
+
+    // The fields that aren't configured out, named `field` or `0` and prefixed with the variant
+    // for enums. `params` are the generic parameters of the type.
+    fn fields(
+        &self,
+        variant: Option<&syn::Ident>,
+        fields: &Fields,
+        params: &[String],
+    ) -> Vec<FieldInfo> {
+        fields
+            .iter()
+            .enumerate()
+            .filter(|(_, field)| self.cfg.is_enabled(&field.attrs))
+            .map(|(index, field)| {
+                let name = field
+                    .ident
+                    .as_ref()
+                    .map_or_else(|| index.to_string(), ToString::to_string);
+                let mut visitor = TypeNameVisitor::default();
+                visitor.visit_type(&field.ty);
+                let mut instances = InstanceVisitor {
+                    params,
+                    instances: BTreeSet::new(),
+                };
+                instances.visit_type(&field.ty);
+                let mut lifetimes = LifetimeVisitor::default();
+                lifetimes.visit_type(&field.ty);
+                let outer_type = match &field.ty {
+                    Type::Path(path) => path.path.segments.last().map(|s| s.ident.to_string()),
+                    _ => None,
+                };
+                // `#[reflect(ignore)]` and `#[reflect(default)]` may be behind a `cfg_attr` too
+                let reflect = ReflectAttrs::parse(&field.attrs, self.cfg, &BTreeMap::new()).reflect;
+                FieldInfo {
+                    name: match variant {
+                        Some(variant) => format!("{variant}::{name}"),
+                        None => name,
+                    },
+                    type_names: visitor.0,
+                    outer_type,
+                    ignored: reflect.contains("ignore"),
+                    reflect_default: reflect.contains("default"),
+                    bundle_ignored: field.attrs.iter().any(|attr| {
+                        attr.path().is_ident("bundle")
+                            && attr
+                                .parse_args::<syn::Ident>()
+                                .is_ok_and(|arg| arg == "ignore")
+                    }),
+                    instances: instances.instances,
+                    lifetimes: lifetimes.into_lifetimes(),
+                }
+            })
+            .collect()
+    }
+
+    /// Record the types registered via `register_type::<T>()`, `register_type_data::<T, D>()` or
+    /// `register_asset_reflect::<T>()` anywhere in the file, outside of code that is configured out,
+    /// and separately the ones registered in `impl Plugin` blocks. Instances of generic types with
+    /// concrete arguments are recorded wherever they're used.
+    pub fn collect_registrations(&mut self, file: &File) {
+        let mut visitor = RegistrationVisitor {
+            cfg: self.cfg,
+            collected: &mut self.collected,
+            params: Vec::new(),
+            in_plugin: false,
+            registers_in_plugin: false,
+        };
+        visitor.visit_file(file);
+        if visitor.registers_in_plugin {
+            self.collected
+                .plugin_crates
+                .insert(self.crate_name.map(str::to_owned));
+        }
+    }
+
+    pub fn into_collected(self) -> Collected {
+        self.collected
+    }
+}
+
+/// Methods whose first generic argument is a type that gets registered for reflection.
+const REGISTRATION_METHODS: &[&str] = &[
+    "register_type",
+    "register_type_data",
+    "register_asset_reflect",
+    "register_type_state",
+    "register_type_mutable_state",
+];
+
+/// Registration methods that add type data along with the type, and the type data they add.
+const TYPE_DATA_REGISTRATIONS: &[(&str, &[&str])] = &[
+    ("register_asset_reflect", &["Asset"]),
+    ("register_type_state", &["State"]),
+    (
+        "register_type_mutable_state",
+        &["State", "FreelyMutableState"],
+    ),
+];
+
+/// Finds `register_type` calls and records the registered type, as well as the instances of
+/// generic types used with concrete arguments.
+struct RegistrationVisitor<'a> {
+    cfg: &'a CfgSet,
+    collected: &'a mut Collected,
+    /// Generic parameters of the items around the current position.
+    params: Vec<String>,
+    /// Whether the current position is inside an `impl Plugin` block.
+    in_plugin: bool,
+    /// Whether any type was registered inside an `impl Plugin` block.
+    registers_in_plugin: bool,
+}
+
+impl<'ast> Visit<'ast> for RegistrationVisitor<'_> {
+    fn visit_item(&mut self, item: &'ast Item) {
+        if !self.cfg.is_enabled(item_attrs(item)) {
+            return;
+        }
+        let scope = self.params.len();
+        let generics = match item {
+            Item::Struct(s) => Some(&s.generics),
+            Item::Enum(e) => Some(&e.generics),
+            Item::Union(u) => Some(&u.generics),
+            Item::Fn(f) => Some(&f.sig.generics),
+            Item::Impl(i) => Some(&i.generics),
+            Item::Trait(t) => Some(&t.generics),
+            Item::Type(t) => Some(&t.generics),
+            _ => None,
+        };
+        self.params
+            .extend(generics.map(param_names).unwrap_or_default());
+        let outside_plugin = !self.in_plugin;
+        if let Item::Impl(item) = item
+            && let Some((_, trait_path, _)) = &item.trait_
+        {
+            self.in_plugin |= trait_path
+                .segments
+                .last()
+                .is_some_and(|segment| segment.ident == "Plugin");
+        }
+        visit::visit_item(self, item);
+        self.params.truncate(scope);
+        if outside_plugin {
+            self.in_plugin = false;
+        }
+    }
+
+    fn visit_impl_item_fn(&mut self, function: &'ast ImplItemFn) {
+        if self.cfg.is_enabled(&function.attrs) {
+            let scope = self.params.len();
+            self.params.extend(param_names(&function.sig.generics));
+            visit::visit_impl_item_fn(self, function);
+            self.params.truncate(scope);
+        }
+    }
+
+    fn visit_trait_item_fn(&mut self, function: &'ast TraitItemFn) {
+        let scope = self.params.len();
+        self.params.extend(param_names(&function.sig.generics));
+        visit::visit_trait_item_fn(self, function);
+        self.params.truncate(scope);
+    }
+
+    fn visit_path(&mut self, path: &'ast Path) {
+        if let Some(instance) = instance_of(path, &self.params)
+            && instance.concrete.is_some()
+        {
+            self.collected.used_instances.insert(instance);
+        }
+        // Observers take a `Trigger<Event, Components>`
+        if let Some(segment) = path.segments.last()
+            && segment.ident == "Trigger"
+            && let PathArguments::AngleBracketed(args) = &segment.arguments
+            && let Some(GenericArgument::Type(components)) = args.args.iter().nth(1)
+        {
+            self.collected
+                .observed
+                .extend(bundle_components(components));
+        }
+        visit::visit_path(self, path);
+    }
+
+    fn visit_expr_method_call(&mut self, call: &'ast ExprMethodCall) {
+        // Attributes on statements like `#[cfg(...)] app.register_type::<T>();` end up here
+        if !self.cfg.is_enabled(&call.attrs) {
+            return;
+        }
+        if call.method == "register_component_hooks"
+            && let Some(turbofish) = &call.turbofish
+            && let Some(GenericArgument::Type(ty)) = turbofish.args.first()
+        {
+            self.collected.observed.extend(bundle_components(ty));
+        }
+        if REGISTRATION_METHODS
+            .iter()
+            .any(|method| call.method == method)
+            && let Some(turbofish) = &call.turbofish
+            && let Some(GenericArgument::Type(Type::Path(ty))) = turbofish.args.first()
+            && let Some(segment) = ty.path.segments.last()
+        {
+            self.collected.registered.insert(segment.ident.to_string());
+            if let Some((_, type_data)) = TYPE_DATA_REGISTRATIONS
+                .iter()
+                .find(|(method, _)| call.method == method)
+            {
+                self.collected
+                    .registered_type_data
+                    .entry(segment.ident.to_string())
+                    .or_default()
+                    .extend(type_data.iter().map(|name| name.to_string()));
+            }
+            if self.in_plugin {
+                self.collected
+                    .plugin_registered
+                    .insert(segment.ident.to_string());
+                self.registers_in_plugin = true;
+            }
+            // Without arguments (or with generic ones), every instance is covered
+            let instance = instance_of(&ty.path, &self.params).unwrap_or_else(|| Instance {
+                name: segment.ident.to_string(),
+                concrete: None,
+            });
+            self.collected.registered_instances.insert(instance);
+        }
+        visit::visit_expr_method_call(self, call);
+    }
+}
+
+/// Generic types whose reflection doesn't involve their type arguments, so `Foo` in
+/// `Handle<Foo>` neither needs to implement `Reflect` nor gets registered along with the field.
+const OPAQUE_WRAPPERS: &[&str] = &["PhantomData", "Arc", "Handle", "AssetId"];
+
+/// Collects the names of all path segments in a type, e.g. `Vec`, `Option` and `Transform` in
+/// `Vec<Option<Transform>>`, without looking into [`OPAQUE_WRAPPERS`].
+#[derive(Default)]
+struct TypeNameVisitor(BTreeSet<String>);
+
+impl<'ast> Visit<'ast> for TypeNameVisitor {
+    fn visit_path_segment(&mut self, segment: &'ast PathSegment) {
+        let name = segment.ident.to_string();
+        let opaque = OPAQUE_WRAPPERS.contains(&name.as_str());
+        self.0.insert(name);
+        if !opaque {
+            visit::visit_path_segment(self, segment);
+        }
+    }
+}
+
+/// Collects the lifetimes in a type other than `'static`, e.g. `'a` in `&'a str` or `Cow<'a, str>`.
+/// Lifetimes introduced by `for<'b>` are left out.
+#[derive(Default)]
+struct LifetimeVisitor {
+    lifetimes: BTreeSet<String>,
+    bound: BTreeSet<String>,
+}
+
+impl<'ast> Visit<'ast> for LifetimeVisitor {
+    fn visit_lifetime(&mut self, lifetime: &'ast Lifetime) {
+        if lifetime.ident != "static" {
+            self.lifetimes.insert(format!("'{}", lifetime.ident));
+        }
+    }
+
+    fn visit_bound_lifetimes(&mut self, bound: &'ast BoundLifetimes) {
+        for param in bound.lifetimes.iter() {
+            if let GenericParam::Lifetime(param) = param {
+                self.bound.insert(format!("'{}", param.lifetime.ident));
+            }
+        }
+    }
+}
+
+impl LifetimeVisitor {
+    fn into_lifetimes(self) -> BTreeSet<String> {
+        let Self {
+            mut lifetimes,
+            bound,
+        } = self;
+        lifetimes.retain(|lifetime| !bound.contains(lifetime));
+        lifetimes
+    }
+}
+
+/// Collects the generic instances in a field's type, e.g. `Foo<u32>` and `Vec<Foo<u32>>` in
+/// `Vec<Foo<u32>>`, without looking into [`OPAQUE_WRAPPERS`].
+struct InstanceVisitor<'a> {
+    /// Generic parameters of the type the field belongs to.
+    params: &'a [String],
+    instances: BTreeSet<Instance>,
+}
+
+impl<'ast> Visit<'ast> for InstanceVisitor<'_> {
+    fn visit_path(&mut self, path: &'ast Path) {
+        if let Some(instance) = instance_of(path, self.params) {
+            self.instances.insert(instance);
+        }
+        let opaque = path
+            .segments
+            .last()
+            .is_some_and(|segment| OPAQUE_WRAPPERS.contains(&segment.ident.to_string().as_str()));
+        if !opaque {
+            visit::visit_path(self, path);
+        }
+    }
+}
+
+// The names of the type and const parameters
+fn param_names(generics: &Generics) -> Vec<String> {
+    generics
+        .params
+        .iter()
+        .filter_map(|param| match param {
+            GenericParam::Type(param) => Some(param.ident.to_string()),
+            GenericParam::Const(param) => Some(param.ident.to_string()),
+            GenericParam::Lifetime(_) => None,
+        })
+        .collect()
+}
+
+// The reflection bounds each type parameter lacks: `TypePath` for all of them, and `Reflect` for
+// the ones used in fields that aren't ignored. Bounds are matched by name, in the parameter list
+// as well as the `where` clause.
+fn missing_bounds(generics: &Generics, fields: &[FieldInfo]) -> BTreeMap<String, Vec<String>> {
+    const TYPE_PATH_BOUNDS: &[&str] = &["TypePath", "Typed", "Reflectable"];
+    const REFLECT_BOUNDS: &[&str] = &[
+        "Reflect",
+        "PartialReflect",
+        "FromReflect",
+        "Typed",
+        "Reflectable",
+    ];
+
+    let mut bounds: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+    for param in generics.type_params() {
+        bounds
+            .entry(param.ident.to_string())
+            .or_default()
+            .extend(bound_names(&param.bounds));
+    }
+    for predicate in generics
+        .where_clause
+        .iter()
+        .flat_map(|clause| &clause.predicates)
+    {
+        if let WherePredicate::Type(predicate) = predicate
+            && let Type::Path(bounded) = &predicate.bounded_ty
+            && let Some(ident) = bounded.path.get_ident()
+            && let Some(names) = bounds.get_mut(&ident.to_string())
+        {
+            names.extend(bound_names(&predicate.bounds));
+        }
+    }
+
+    bounds
+        .into_iter()
+        .filter_map(|(param, names)| {
+            let has = |candidates: &[&str]| candidates.iter().any(|name| names.contains(*name));
+            let used = fields
+                .iter()
+                .any(|field| !field.ignored && field.type_names.contains(&param));
+            let mut missing = Vec::new();
+            if used && !has(REFLECT_BOUNDS) {
+                missing.push("Reflect".to_string());
+            }
+            if !has(TYPE_PATH_BOUNDS) {
+                missing.push("TypePath".to_string());
+            }
+            (!missing.is_empty()).then_some((param, missing))
+        })
+        .collect()
+}
+
+fn bound_names<'a>(
+    bounds: impl IntoIterator<Item = &'a TypeParamBound>,
+) -> impl Iterator<Item = String> {
+    bounds.into_iter().filter_map(|bound| match bound {
+        TypeParamBound::Trait(bound) => bound
+            .path
+            .segments
+            .last()
+            .map(|segment| segment.ident.to_string()),
+        _ => None,
+    })
+}
+
+// The instance of a generic type a path names, like `Foo<u32>` for `a::Foo<u32>` or
+// `Foo::<u32>::new`, or `None` if it has no generic arguments. Arguments that refer to `params`
+// make it stand for any instance.
+fn instance_of(path: &Path, params: &[String]) -> Option<Instance> {
+    let segment = path.segments.iter().find(|segment| {
+        matches!(&segment.arguments, PathArguments::AngleBracketed(args)
+            if args.args.iter().any(|arg| !matches!(arg, GenericArgument::Lifetime(_))))
+    })?;
+    let first = path.segments.first()?.ident.to_string();
+    let concrete = if first == "Self" || params.contains(&first) {
+        None
+    } else {
+        concrete_segment(segment, params)
+    };
+    Some(Instance {
+        name: segment.ident.to_string(),
+        concrete,
+    })
+}
+
+// A path segment with its generic arguments, or `None` if they aren't concrete
+fn concrete_segment(segment: &PathSegment, params: &[String]) -> Option<String> {
+    let name = segment.ident.to_string();
+    if name == "Self" || params.contains(&name) {
+        return None;
+    }
+    let PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return Some(name);
+    };
+    let args = args
+        .args
+        .iter()
+        .filter_map(|arg| match arg {
+            GenericArgument::Lifetime(_) => None,
+            GenericArgument::Type(ty) => Some(concrete_type(ty, params)),
+            GenericArgument::Const(expr) => {
+                let tokens = expr.to_token_stream();
+                let generic = tokens.into_iter().any(|token| {
+                    matches!(token, TokenTree::Ident(ident) if params.contains(&ident.to_string()))
+                });
+                Some((!generic).then(|| expr.to_token_stream().to_string()))
+            }
+            _ => Some(None),
+        })
+        .collect::<Option<Vec<_>>>()?;
+    if args.is_empty() {
+        Some(name)
+    } else {
+        Some(format!("{name}<{}>", args.join(", ")))
+    }
+}
+
+// A type by the last segment of its paths, like `Vec<Vec3>` for `Vec<glam::Vec3>`, or `None` if
+// it isn't concrete
+fn concrete_type(ty: &Type, params: &[String]) -> Option<String> {
+    match ty {
+        Type::Path(path) if path.qself.is_none() => {
+            let first = path.path.segments.first()?.ident.to_string();
+            if first == "Self" || params.contains(&first) {
+                return None;
+            }
+            concrete_segment(path.path.segments.last()?, params)
+        }
+        Type::Reference(reference) => Some(format!(
+            "&{}{}",
+            if reference.mutability.is_some() {
+                "mut "
+            } else {
+                ""
+            },
+            concrete_type(&reference.elem, params)?
+        )),
+        Type::Tuple(tuple) => {
+            let elems = tuple
+                .elems
+                .iter()
+                .map(|elem| concrete_type(elem, params))
+                .collect::<Option<Vec<_>>>()?;
+            Some(format!("({})", elems.join(", ")))
+        }
+        Type::Array(array) => Some(format!(
+            "[{}; {}]",
+            concrete_type(&array.elem, params)?,
+            array.len.to_token_stream()
+        )),
+        Type::Slice(slice) => Some(format!("[{}]", concrete_type(&slice.elem, params)?)),
+        Type::Paren(paren) => concrete_type(&paren.elem, params),
+        Type::Group(group) => concrete_type(&group.elem, params),
+        _ => None,
+    }
+}
+
+// The names of the components in a bundle type, like `Player` or each of `(Player, Health)`
+fn bundle_components(ty: &Type) -> Vec<String> {
+    match ty {
+        Type::Path(ty) => ty
+            .path
+            .segments
+            .last()
+            .map(|segment| segment.ident.to_string())
+            .into_iter()
+            .collect(),
+        Type::Tuple(tuple) => tuple.elems.iter().flat_map(bundle_components).collect(),
+        Type::Paren(ty) => bundle_components(&ty.elem),
+        _ => Vec::new(),
+    }
+}
+
+// The type that `impl_reflect!(struct Foo { ... })` or `impl_reflect_opaque!(path::Foo(...))`
+// implements `Reflect` for
+fn macro_reflected_type(item: &ItemMacro) -> Option<String> {
+    let name = item.mac.path.segments.last()?.ident.to_string();
+    let mut tokens = item.mac.tokens.clone().into_iter().peekable();
+    match name.as_str() {
+        "impl_reflect" | "impl_reflect_struct" => {
+            tokens.find(|token| matches!(token, TokenTree::Ident(ident) if ident == "struct" || ident == "enum"))?;
+            match tokens.next()? {
+                TokenTree::Ident(ident) => Some(ident.to_string()),
+                _ => None,
+            }
+        }
+        "impl_reflect_opaque" | "impl_reflect_value" => macro_path_type(tokens),
+        _ => None,
+    }
+}
+
+// The type that `impl_type_path!((in path) Foo)` or `impl_type_path!(::path::Foo)` implements
+// `TypePath` for
+fn macro_type_path_type(item: &ItemMacro) -> Option<String> {
+    if item.mac.path.segments.last()?.ident != "impl_type_path" {
+        return None;
+    }
+    macro_path_type(item.mac.tokens.clone().into_iter())
+}
+
+// The last segment of the type path in a macro's input, skipping attributes and a leading
+// `(in path as Name)`
+fn macro_path_type(tokens: impl Iterator<Item = TokenTree>) -> Option<String> {
+    let mut tokens = tokens.peekable();
+    let mut last = None;
+    while let Some(token) = tokens.next() {
+        match token {
+            TokenTree::Punct(punct) if punct.as_char() == '#' => {
+                tokens.next();
+            }
+            TokenTree::Group(_) if last.is_none() => {}
+            TokenTree::Ident(ident) => last = Some(ident.to_string()),
+            TokenTree::Punct(punct) if punct.as_char() == ':' => {}
+            _ => break,
+        }
+    }
+    last
+}
+
+// The attributes of the item kinds we care about
+fn item_attrs(item: &Item) -> &[Attribute] {
+    match item {
+        Item::Fn(f) => &f.attrs,
+        Item::Struct(s) => &s.attrs,
+        Item::Enum(e) => &e.attrs,
+        Item::Impl(i) => &i.attrs,
+        Item::Trait(t) => &t.attrs,
+        Item::Macro(m) => &m.attrs,
+        Item::Mod(m) => &m.attrs,
+        Item::Use(u) => &u.attrs,
+        _ => &[],
+    }
+}
+
+// Record `use path::Trait as Alias;` anywhere in the file as `Alias` standing for `Trait`
+fn collect_use_aliases(items: &[Item], aliases: &mut BTreeMap<String, String>) {
+    for item in items {
+        match item {
+            Item::Use(u) => {
+                let mut kinds = Vec::new();
+                flatten_use_tree(&u.tree, &mut Vec::new(), &mut kinds);
+                for kind in kinds {
+                    if let BindingKind::Use { path, name } = kind
+                        && let Some(original) = path.last()
+                        && *original != name
+                        && name != "_"
+                    {
+                        aliases.insert(name, original.clone());
+                    }
+                }
+            }
+            Item::Mod(m) => {
+                if let Some((_, items)) = &m.content {
+                    collect_use_aliases(items, aliases);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+// Turn `use a::{b, c::*, d as e}` into one binding per imported name
+fn flatten_use_tree(tree: &UseTree, prefix: &mut Vec<String>, kinds: &mut Vec<BindingKind>) {
+    match tree {
+        UseTree::Path(path) => {
+            prefix.push(path.ident.to_string());
+            flatten_use_tree(&path.tree, prefix, kinds);
+            prefix.pop();
+        }
+        // `use a::{self}` imports `a` itself
+        UseTree::Name(name) if name.ident == "self" => {
+            if let Some(last) = prefix.last() {
+                kinds.push(BindingKind::Use {
+                    path: prefix.clone(),
+                    name: last.clone(),
+                });
+            }
+        }
+        UseTree::Name(name) => {
+            let mut path = prefix.clone();
+            path.push(name.ident.to_string());
+            kinds.push(BindingKind::Use {
+                path,
+                name: name.ident.to_string(),
+            });
+        }
+        UseTree::Rename(rename) => {
+            let mut path = prefix.clone();
+            if rename.ident != "self" {
+                path.push(rename.ident.to_string());
+            }
+            kinds.push(BindingKind::Use {
+                path,
+                name: rename.rename.to_string(),
+            });
+        }
+        UseTree::Glob(_) => kinds.push(BindingKind::Glob(prefix.clone())),
+        UseTree::Group(group) => {
+            for tree in &group.items {
+                flatten_use_tree(tree, prefix, kinds);
+            }
+        }
+    }
+}
+
+// The value of a `#[path = "..."]` attribute
+fn path_attr(attrs: &[Attribute]) -> Option<String> {
+    attrs.iter().find_map(|attr| match &attr.meta {
+        Meta::NameValue(name_value) if name_value.path.is_ident("path") => {
+            match &name_value.value {
+                Expr::Lit(ExprLit {
+                    lit: Lit::Str(path),
+                    ..
+                }) => Some(path.value()),
+                _ => None,
+            }
+        }
+        _ => None,
+    })
+}
+
+/// The declared visibility of an item.
+fn item_visibility(item: &Item) -> Visibility {
+    match item {
+        Item::Struct(s) => Visibility::of(&s.vis),
+        Item::Enum(e) => Visibility::of(&e.vis),
+        Item::Mod(m) => Visibility::of(&m.vis),
+        _ => Visibility::Private,
+    }
+}
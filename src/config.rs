@@ -0,0 +1,430 @@
+use crate::cli::{FailOn, PARSE_ERRORS, ScanArgs, VisibilityFilter};
+use crate::progress::Verbosity;
+use bevy_reflect_check::cfg::Target;
+use bevy_reflect_check::checks;
+use bevy_reflect_check::output::OutputFormat;
+use bevy_reflect_check::rules::AttributeRule;
+use bevy_reflect_check::{AnalysisConfig, CrateKind, Severity, Visibility};
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::{fmt, fs};
+
+/// Name of the configuration file looked up in the workspace root.
+pub const CONFIG_FILE_NAME: &str = "reflect-check.toml";
+
+/// Contents of `reflect-check.toml`.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Config {
+    /// Opt-in checks to enable.
+    pub rules: Vec<String>,
+    /// Checks to disable.
+    pub disabled_rules: Vec<String>,
+    /// Severity per check. Any level but `off` also enables opt-in checks.
+    pub severity: BTreeMap<String, RuleLevel>,
+    /// Globs of source files to skip, relative to the workspace root.
+    pub ignore_paths: Vec<String>,
+    /// Globs of directory names to skip wherever they are.
+    pub exclude_dirs: Vec<String>,
+    /// Globs of fully qualified type paths to leave out of the results.
+    pub ignore_types: Vec<String>,
+    /// Globs of fully qualified type paths that are meant to have
+    /// `#[reflect(from_reflect = false)]`.
+    pub allow_from_reflect_false: Vec<String>,
+    /// Globs of fully qualified type paths whose findings are always errors.
+    pub persistence_critical: Vec<String>,
+    /// Derive macros that stand for a trait of another name, by name or full path.
+    pub derive_aliases: BTreeMap<String, String>,
+    /// Checks of the project's own, each run as an [`AttributeRule`].
+    pub custom_rules: Vec<CustomRule>,
+    /// Globs of the package names of dependencies to scan.
+    pub deps: Option<Vec<String>>,
+    pub all_deps: Option<bool>,
+    pub no_deps: Option<bool>,
+    /// Baseline file, relative to the workspace root.
+    pub baseline: Option<PathBuf>,
+    pub visibility: Option<VisibilityFilter>,
+    pub format: Option<OutputFormat>,
+    pub fail_on: Option<FailOn>,
+}
+
+/// A `[[custom-rules]]` entry: types deriving `Reflect` and implementing all of `derives` need
+/// all of `reflect` in their `#[reflect(...)]` attributes.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct CustomRule {
+    pub id: String,
+    #[serde(default)]
+    pub derives: Vec<String>,
+    pub reflect: Vec<String>,
+    /// `warn` if not given. `off` makes the check opt-in.
+    pub severity: Option<RuleLevel>,
+}
+
+impl CustomRule {
+    fn rule(self) -> AttributeRule {
+        let level = self.severity.unwrap_or(RuleLevel::Warn);
+        let rule = AttributeRule::new(self.id, self.derives, self.reflect)
+            .severity(level.severity().unwrap_or(Severity::Warning));
+        if level == RuleLevel::Off {
+            rule.opt_in()
+        } else {
+            rule
+        }
+    }
+}
+
+/// How a check is reported, or whether at all.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RuleLevel {
+    Error,
+    Warn,
+    Info,
+    Off,
+}
+
+impl RuleLevel {
+    fn severity(self) -> Option<Severity> {
+        match self {
+            RuleLevel::Error => Some(Severity::Error),
+            RuleLevel::Warn => Some(Severity::Warning),
+            RuleLevel::Info => Some(Severity::Info),
+            RuleLevel::Off => None,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ConfigError {
+    Read(PathBuf, std::io::Error),
+    Parse(PathBuf, toml::de::Error),
+    /// The file couldn't be edited, e.g. by the `tui`, as it isn't valid TOML or can't be
+    /// written.
+    #[cfg(feature = "tui")]
+    Edit(PathBuf, String),
+    UnknownRule(String),
+    /// A rule on the command line that's neither a check nor a custom rule, with the known ones.
+    UnknownRuleArgument(String, Vec<String>),
+    /// A custom rule has the id of a built-in check or of another custom rule.
+    DuplicateRule(String),
+    InvalidPattern(globset::Error),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Read(path, err) => write!(f, "Failed to read {}: {err}", path.display()),
+            ConfigError::Parse(path, err) => write!(f, "Failed to parse {}: {err}", path.display()),
+            #[cfg(feature = "tui")]
+            ConfigError::Edit(path, err) => write!(f, "Failed to edit {}: {err}", path.display()),
+            ConfigError::UnknownRule(id) => write!(f, "Unknown rule `{id}` in configuration"),
+            ConfigError::UnknownRuleArgument(id, known) => {
+                write!(
+                    f,
+                    "Unknown rule `{id}`, expected one of: {}",
+                    known.join(", ")
+                )
+            }
+            ConfigError::DuplicateRule(id) => {
+                write!(f, "Custom rule `{id}` in configuration already exists")
+            }
+            ConfigError::InvalidPattern(err) => {
+                write!(f, "Invalid pattern in configuration: {err}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+impl Config {
+    /// The configuration file to use, which may not exist yet.
+    pub fn path(explicit_path: Option<&Path>, workspace_root: &Path) -> PathBuf {
+        explicit_path.map_or_else(|| workspace_root.join(CONFIG_FILE_NAME), Path::to_path_buf)
+    }
+
+    /// Add `type_path` to `ignore-types` in the file at `path`, creating it if needed. The rest
+    /// of the file is kept as it is, comments included.
+    #[cfg(feature = "tui")]
+    pub fn ignore_type(path: &Path, type_path: &str) -> Result<(), ConfigError> {
+        let edit_error =
+            |err: &dyn fmt::Display| ConfigError::Edit(path.to_path_buf(), err.to_string());
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(err) => return Err(ConfigError::Read(path.to_path_buf(), err)),
+        };
+        let mut document: toml_edit::DocumentMut =
+            content.parse().map_err(|err| edit_error(&err))?;
+        let ignore_types = document
+            .entry("ignore-types")
+            .or_insert_with(|| toml_edit::value(toml_edit::Array::new()))
+            .as_array_mut()
+            .ok_or_else(|| edit_error(&"`ignore-types` isn't an array"))?;
+        if !ignore_types
+            .iter()
+            .any(|existing| existing.as_str() == Some(type_path))
+        {
+            ignore_types.push(type_path);
+        }
+        fs::write(path, document.to_string()).map_err(|err| edit_error(&err))
+    }
+
+    /// Load the configuration from `explicit_path`, or from `reflect-check.toml` in the workspace
+    /// root if it exists. A missing default file is not an error.
+    pub fn load(explicit_path: Option<&Path>, workspace_root: &Path) -> Result<Self, ConfigError> {
+        let path = Self::path(explicit_path, workspace_root);
+        if explicit_path.is_none() && !path.exists() {
+            return Ok(Self::default());
+        }
+        let content =
+            fs::read_to_string(&path).map_err(|err| ConfigError::Read(path.clone(), err))?;
+        let config: Self = toml::from_str(&content).map_err(|err| ConfigError::Parse(path, err))?;
+
+        let mut custom_ids: Vec<&str> = Vec::new();
+        for rule in &config.custom_rules {
+            if checks::by_id(&rule.id).is_some() || custom_ids.contains(&rule.id.as_str()) {
+                return Err(ConfigError::DuplicateRule(rule.id.clone()));
+            }
+            custom_ids.push(&rule.id);
+        }
+        if let Some(id) = config
+            .rules
+            .iter()
+            .chain(&config.disabled_rules)
+            .chain(config.severity.keys())
+            .find(|id| checks::by_id(id).is_none() && !custom_ids.contains(&id.as_str()))
+        {
+            return Err(ConfigError::UnknownRule(id.clone()));
+        }
+        Ok(config)
+    }
+}
+
+/// The effective settings after merging the configuration file with the command line.
+#[derive(Debug)]
+pub struct Settings {
+    pub analysis: AnalysisConfig,
+    pub baseline: Option<PathBuf>,
+    pub update_baseline: bool,
+    pub fix: bool,
+    pub dry_run: bool,
+    /// Only findings of this kind of crate are reported.
+    pub only: Option<CrateKind>,
+    /// Only findings for types matching these are reported, unless it's empty.
+    pub include: GlobSet,
+    /// Findings for types matching these aren't reported.
+    pub exclude: GlobSet,
+    /// The path of the file whose content is read from stdin, instead of scanning the workspace.
+    pub stdin: Option<PathBuf>,
+    pub jobs: Option<usize>,
+    pub stats: bool,
+    pub registry_size: bool,
+    /// Print how long the stages of the analysis took.
+    pub timings: bool,
+    /// Where to write an issue draft per dependency package with findings.
+    pub issue_drafts: Option<PathBuf>,
+    pub format: OutputFormat,
+    pub fail_on: FailOn,
+    pub verbosity: Verbosity,
+    /// Fail if any source file couldn't be parsed.
+    pub deny_parse_errors: bool,
+    /// The `[[custom-rules]]` of the configuration, to register with the analyzer.
+    pub custom_rules: Vec<AttributeRule>,
+}
+
+impl Settings {
+    pub fn new(args: ScanArgs, config: Config, workspace_root: &Path) -> Result<Self, ConfigError> {
+        // Dependencies given on the command line replace those in the file
+        let deps_given = args.all_deps || !args.deps.is_empty();
+        let no_deps = args.no_deps || (!deps_given && config.no_deps.unwrap_or_default());
+        let deps = if args.all_deps {
+            glob_set(&["*".to_string()])?
+        } else if !args.deps.is_empty() {
+            glob_set(&args.deps)?
+        } else if config.all_deps.unwrap_or_default() {
+            glob_set(&["*".to_string()])?
+        } else if let Some(deps) = &config.deps {
+            glob_set(deps)?
+        } else {
+            AnalysisConfig::default().deps
+        };
+
+        // Custom rules aren't known before the file is read, so clap takes any id
+        let known: Vec<String> = checks::ids()
+            .map(str::to_string)
+            .chain(config.custom_rules.iter().map(|rule| rule.id.clone()))
+            .collect();
+        if let Some(id) = args
+            .rules
+            .iter()
+            .chain(&args.disabled_rules)
+            .chain(args.deny.iter().filter(|id| *id != PARSE_ERRORS))
+            .chain(&args.warn)
+            .chain(&args.allow)
+            .find(|id| !known.contains(id))
+        {
+            return Err(ConfigError::UnknownRuleArgument(id.clone(), known));
+        }
+
+        // The command line wins over the file, and severities over `--rule`/`--no-rule`
+        let mut rules = Rules::default();
+        config.rules.into_iter().for_each(|id| rules.enable(id));
+        config
+            .disabled_rules
+            .into_iter()
+            .for_each(|id| rules.disable(id));
+        for (id, level) in config.severity {
+            rules.set_level(id, level);
+        }
+        args.rules.into_iter().for_each(|id| rules.enable(id));
+        args.disabled_rules
+            .into_iter()
+            .for_each(|id| rules.disable(id));
+        let (deny_parse_errors, deny): (Vec<_>, Vec<_>) =
+            args.deny.into_iter().partition(|id| id == PARSE_ERRORS);
+        let levels = [
+            (deny, RuleLevel::Error),
+            (args.warn, RuleLevel::Warn),
+            (args.allow, RuleLevel::Off),
+        ];
+        for (ids, level) in levels {
+            ids.into_iter().for_each(|id| rules.set_level(id, level));
+        }
+
+        let analysis = AnalysisConfig {
+            root: args.root,
+            paths: args.paths,
+            crate_name: args.crate_name,
+            crate_root: args.crate_root,
+            deps,
+            no_deps,
+            // Set by the `crate` subcommand
+            package: None,
+            rules: rules.enabled,
+            disabled_rules: rules.disabled,
+            severities: rules.severities,
+            bevy_version: args.bevy_version,
+            allow_from_reflect_false: glob_set(&config.allow_from_reflect_false)?,
+            persistence_critical: glob_set(&config.persistence_critical)?,
+            ignore_paths: glob_set(&config.ignore_paths)?,
+            exclude_dirs: glob_set(&[config.exclude_dirs, args.exclude_dirs].concat())?,
+            include_examples: args.include_examples,
+            include_tests: args.include_tests,
+            include_benches: args.include_benches,
+            expand: args.expand,
+            backend: args.backend,
+            ignore_types: glob_set(&config.ignore_types)?,
+            derive_aliases: config.derive_aliases,
+            // Private types are registered as well
+            visibility: if args.registry_size {
+                Visibility::Private
+            } else {
+                args.visibility
+                    .or(config.visibility)
+                    .unwrap_or_default()
+                    .min_visibility()
+            },
+            features: args.features,
+            all_features: args.all_features,
+            no_default_features: args.no_default_features,
+            target: args
+                .target
+                .as_deref()
+                .map_or_else(Target::host, Target::from_triple),
+            use_cache: !args.no_cache,
+            offline: args.offline,
+        };
+        Ok(Self {
+            analysis,
+            baseline: args
+                .baseline
+                .or_else(|| config.baseline.map(|path| workspace_root.join(path))),
+            update_baseline: args.update_baseline,
+            fix: args.fix,
+            dry_run: args.dry_run,
+            only: if args.only_workspace {
+                Some(CrateKind::Workspace)
+            } else if args.only_deps {
+                Some(CrateKind::Dependency)
+            } else {
+                None
+            },
+            include: glob_set(&args.include)?,
+            exclude: glob_set(&args.exclude)?,
+            jobs: args.jobs,
+            stats: args.stats,
+            registry_size: args.registry_size,
+            timings: args.timings,
+            issue_drafts: args.emit_issue_drafts,
+            // Editors read the findings of a single file, people the ones of a scan
+            format: if args.stdin {
+                args.format.unwrap_or(OutputFormat::Json)
+            } else {
+                args.format.or(config.format).unwrap_or_default()
+            },
+            stdin: args.file_path.filter(|_| args.stdin),
+            fail_on: args.fail_on.or(config.fail_on).unwrap_or_default(),
+            deny_parse_errors: !deny_parse_errors.is_empty(),
+            custom_rules: config
+                .custom_rules
+                .into_iter()
+                .map(CustomRule::rule)
+                .collect(),
+            verbosity: if args.quiet {
+                Verbosity::Quiet
+            } else if args.verbose {
+                Verbosity::Verbose
+            } else {
+                Verbosity::Normal
+            },
+        })
+    }
+}
+
+/// Which checks are switched on or off and how they're reported, built up from the file and
+/// the command line with later settings overriding earlier ones.
+#[derive(Debug, Default)]
+struct Rules {
+    enabled: Vec<String>,
+    disabled: Vec<String>,
+    severities: HashMap<String, Severity>,
+}
+
+impl Rules {
+    fn enable(&mut self, id: String) {
+        self.disabled.retain(|disabled| *disabled != id);
+        if !self.enabled.contains(&id) {
+            self.enabled.push(id);
+        }
+    }
+
+    fn disable(&mut self, id: String) {
+        self.enabled.retain(|enabled| *enabled != id);
+        self.severities.remove(&id);
+        if !self.disabled.contains(&id) {
+            self.disabled.push(id);
+        }
+    }
+
+    fn set_level(&mut self, id: String, level: RuleLevel) {
+        match level.severity() {
+            Some(severity) => {
+                self.severities.insert(id.clone(), severity);
+                self.enable(id);
+            }
+            None => self.disable(id),
+        }
+    }
+}
+
+fn glob_set(patterns: &[String]) -> Result<GlobSet, ConfigError> {
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        builder.add(Glob::new(pattern).map_err(ConfigError::InvalidPattern)?);
+    }
+    builder.build().map_err(ConfigError::InvalidPattern)
+}
@@ -0,0 +1,71 @@
+use crate::finding::{Severity, Span};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::BTreeSet;
+use std::path::Path;
+use std::{fs, io};
+
+/// A finding as read back from a JSON report (`--format json`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReportedFinding {
+    pub check: String,
+    pub severity: Severity,
+    pub type_path: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub variant: Option<String>,
+    pub file: String,
+    pub span: Span,
+    /// The remaining fields, passed through as they are.
+    #[serde(flatten)]
+    pub other: Map<String, Value>,
+}
+
+impl ReportedFinding {
+    // Findings are matched by check, type path and variant like in the baseline, so that moving
+    // a type doesn't count as removing and adding it
+    fn key(&self) -> (&str, &str, Option<&str>) {
+        (&self.check, &self.type_path, self.variant.as_deref())
+    }
+}
+
+/// How the findings of two runs compare.
+#[derive(Debug, Default, Serialize)]
+pub struct ReportDiff {
+    /// Only in the new report.
+    pub added: Vec<ReportedFinding>,
+    /// Only in the old report.
+    pub removed: Vec<ReportedFinding>,
+    /// In both reports, as found in the new one.
+    pub unchanged: Vec<ReportedFinding>,
+}
+
+impl ReportDiff {
+    pub fn new(old: Vec<ReportedFinding>, new: Vec<ReportedFinding>) -> Self {
+        let old_keys: BTreeSet<_> = old.iter().map(ReportedFinding::key).collect();
+        let new_keys: BTreeSet<_> = new.iter().map(ReportedFinding::key).collect();
+        let removed = old
+            .iter()
+            .filter(|finding| !new_keys.contains(&finding.key()))
+            .cloned()
+            .collect();
+        let (unchanged, added) = new
+            .iter()
+            .cloned()
+            .partition(|finding| old_keys.contains(&finding.key()));
+        Self {
+            added,
+            removed,
+            unchanged,
+        }
+    }
+
+    /// Compare two JSON reports on disk.
+    pub fn load(old: &Path, new: &Path) -> io::Result<Self> {
+        Ok(Self::new(read_report(old)?, read_report(new)?))
+    }
+}
+
+fn read_report(path: &Path) -> io::Result<Vec<ReportedFinding>> {
+    let content = fs::read_to_string(path)?;
+    Ok(serde_json::from_str(&content)?)
+}
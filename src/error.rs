@@ -0,0 +1,93 @@
+use crate::config::ConfigError;
+use anstyle::{AnsiColor, Style};
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+/// Exit status when there are findings at or above `--fail-on`, a scene that can't be loaded,
+/// or files that couldn't be parsed while that's denied.
+pub const EXIT_FINDINGS: u8 = 1;
+/// Exit status when the project or the configuration can't be loaded, or an input file can't be
+/// read.
+pub const EXIT_CONFIG: u8 = 2;
+/// Exit status when something went wrong while running, like output that can't be written.
+pub const EXIT_INTERNAL: u8 = 3;
+
+const ERROR: Style = AnsiColor::Red.on_default().bold();
+
+/// Why a run stopped before it could report its findings.
+#[derive(Debug)]
+pub enum Error {
+    Config(ConfigError),
+    /// `cargo metadata` failed and there was no local workspace to fall back on.
+    Metadata(cargo_metadata::Error),
+    /// The package given to `crate` isn't in the dependency graph.
+    Package(String),
+    /// A file named on the command line or in the configuration couldn't be read.
+    Read(PathBuf, String),
+    /// The reports to compare with `diff` couldn't be read.
+    Reports(io::Error),
+    /// Writing to stdout, a source file or the baseline failed, with what was being done.
+    Io(&'static str, io::Error),
+    Internal(String),
+}
+
+impl Error {
+    pub fn exit_code(&self) -> ExitCode {
+        match self {
+            Error::Config(_)
+            | Error::Metadata(_)
+            | Error::Package(_)
+            | Error::Read(..)
+            | Error::Reports(_) => ExitCode::from(EXIT_CONFIG),
+            Error::Io(..) | Error::Internal(_) => ExitCode::from(EXIT_INTERNAL),
+        }
+    }
+
+    /// Print the error to stderr, with a colored label if it's a terminal.
+    pub fn report(&self) {
+        anstream::eprintln!("{ERROR}error{ERROR:#}: {self}");
+        if let Error::Metadata(_) = self {
+            anstream::eprintln!(
+                "Pass --offline if cargo can't reach the network, or the source directories to \
+                scan as arguments"
+            );
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Config(err) => write!(f, "{err}"),
+            Error::Metadata(err) => {
+                write!(
+                    f,
+                    "Failed to fetch cargo metadata: {}",
+                    err.to_string().trim_end()
+                )
+            }
+            Error::Package(name) => {
+                write!(f, "There's no package `{name}` in the dependency graph")
+            }
+            Error::Read(path, err) => write!(f, "Failed to read {}: {err}", path.display()),
+            Error::Reports(err) => write!(f, "Failed to read reports: {err}"),
+            Error::Io(action, err) => write!(f, "Failed to {action}: {err}"),
+            Error::Internal(message) => write!(f, "{message}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<ConfigError> for Error {
+    fn from(err: ConfigError) -> Self {
+        Error::Config(err)
+    }
+}
+
+/// Writing the output to stdout failed.
+pub fn output_error(err: io::Error) -> Error {
+    Error::Io("write output", err)
+}
@@ -0,0 +1,80 @@
+use crate::analyzer::AnalysisConfig;
+use cargo_metadata::Package;
+use std::path::Path;
+use std::process::Command;
+
+/// The library of `package` with all macros expanded, as printed by rustc's
+/// `-Zunpretty=expanded`. Like `cargo expand`, this enables the unstable flag on stable
+/// toolchains with `RUSTC_BOOTSTRAP`. Dependencies are checked into `target_dir`, so that the
+/// regular build isn't invalidated.
+pub(crate) fn expand_lib(
+    package: &Package,
+    config: &AnalysisConfig,
+    is_root: bool,
+    target_dir: &Path,
+) -> Result<String, String> {
+    let mut command = cargo_command("rustc", package, config, is_root, target_dir);
+    command
+        .arg("--profile=check")
+        .arg("--")
+        .arg("-Zunpretty=expanded");
+    String::from_utf8(run(command)?).map_err(|err| err.to_string())
+}
+
+/// `cargo <subcommand>` for the library of `package`, with the features of the analysis and
+/// unstable flags allowed.
+pub(crate) fn cargo_command(
+    subcommand: &str,
+    package: &Package,
+    config: &AnalysisConfig,
+    is_root: bool,
+    target_dir: &Path,
+) -> Command {
+    let mut command = Command::new(std::env::var_os("CARGO").unwrap_or_else(|| "cargo".into()));
+    command
+        .arg(subcommand)
+        .arg("--quiet")
+        .arg("--lib")
+        .arg("--manifest-path")
+        .arg(package.manifest_path.as_std_path())
+        .arg("--target-dir")
+        .arg(target_dir)
+        .env("RUSTC_BOOTSTRAP", "1");
+    if config.offline {
+        command.arg("--offline");
+    }
+    if config.all_features {
+        command.arg("--all-features");
+    } else if is_root {
+        // Features of dependencies are left to cargo's resolution, like for `cargo metadata`
+        let features: Vec<_> = config
+            .features
+            .iter()
+            .filter(|feature| !feature.contains('/'))
+            .map(String::as_str)
+            .collect();
+        if !features.is_empty() {
+            command.arg("--features").arg(features.join(","));
+        }
+        if config.no_default_features {
+            command.arg("--no-default-features");
+        }
+    }
+    command
+}
+
+/// Run a cargo command, returning its stdout or the first error it printed.
+pub(crate) fn run(mut command: Command) -> Result<Vec<u8>, String> {
+    let output = command
+        .output()
+        .map_err(|err| format!("Failed to run cargo: {err}"))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let message = stderr
+            .lines()
+            .find(|line| line.starts_with("error"))
+            .unwrap_or("cargo failed");
+        return Err(message.to_string());
+    }
+    Ok(output.stdout)
+}
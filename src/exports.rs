@@ -0,0 +1,216 @@
+use crate::modules::Visibility;
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
+
+/// How deep `use` chains are followed before giving up, to stay clear of cycles.
+const MAX_DEPTH: usize = 32;
+
+/// A name that an item or a `use` declaration introduces into a module.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Binding {
+    /// Inline modules (`mod a { ... }`) the declaration is nested in.
+    pub inline_path: Vec<String>,
+    /// Visibility as declared, regardless of the surrounding modules.
+    pub visibility: Visibility,
+    pub kind: BindingKind,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum BindingKind {
+    /// A struct or enum.
+    Type(String),
+    /// A module, inline or in its own file.
+    Module(String),
+    /// `use path as name;`, where `name` is the last segment if there's no `as`.
+    Use { path: Vec<String>, name: String },
+    /// `use path::*;`
+    Glob(Vec<String>),
+}
+
+/// What a path refers to, by the fully qualified path of its definition.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Target {
+    Type(String),
+    Module(String),
+}
+
+/// The namespaces of all modules, to find out under which paths types can be named from other
+/// crates.
+#[derive(Debug, Default)]
+pub struct Exports {
+    /// Bindings by the fully qualified path of the module they're declared in.
+    modules: HashMap<String, Vec<(Visibility, BindingKind)>>,
+    /// Names of all crates, the starting points for resolving paths.
+    crates: BTreeSet<String>,
+    /// Results of [`Exports::lookup`]. While a lookup is in progress, its entry is `None`, which
+    /// also cuts cycles short.
+    lookups: RefCell<HashMap<(String, String), Option<Target>>>,
+}
+
+impl Exports {
+    /// Add the bindings declared in the module `module_path`, which is the file they're in.
+    pub fn add(&mut self, module_path: &str, bindings: &[Binding]) {
+        if let Some(crate_name) = module_path.split("::").next() {
+            self.crates.insert(crate_name.to_string());
+        }
+        for binding in bindings {
+            let mut module = module_path.to_string();
+            for segment in &binding.inline_path {
+                module.push_str("::");
+                module.push_str(segment);
+            }
+            self.modules
+                .entry(module)
+                .or_default()
+                .push((binding.visibility, binding.kind.clone()));
+        }
+    }
+
+    /// All paths that types can be named by from outside of their crate, keyed by the path of
+    /// their definition. Modules are walked breadth-first from the crate roots, following `pub`
+    /// items and `pub use` declarations.
+    pub fn public_paths(&self) -> HashMap<String, Vec<String>> {
+        let mut paths: HashMap<String, Vec<String>> = HashMap::new();
+        let mut visited = HashSet::new();
+        // All crate roots go first, so that crates re-exporting each other don't take over
+        let mut pending: VecDeque<(String, String)> = self
+            .crates
+            .iter()
+            .map(|name| (name.clone(), name.clone()))
+            .collect();
+
+        while let Some((module, public_path)) = pending.pop_front() {
+            // A module may be reached through a re-export first, but its definition path should
+            // always be among the results
+            let at_definition = public_path == module;
+            if visited.contains(&(module.clone(), true))
+                || !visited.insert((module.clone(), at_definition))
+            {
+                continue;
+            }
+            let mut names = Vec::new();
+            self.public_names(&module, &mut names, &mut HashSet::new());
+            for (name, target) in names {
+                let path = format!("{public_path}::{name}");
+                match target {
+                    Target::Type(definition) => paths.entry(definition).or_default().push(path),
+                    Target::Module(definition) => pending.push_back((definition, path)),
+                }
+            }
+        }
+        paths
+    }
+
+    // The names a module exports, including those pulled in by `pub use ...::*`
+    fn public_names(
+        &self,
+        module: &str,
+        names: &mut Vec<(String, Target)>,
+        globbed: &mut HashSet<String>,
+    ) {
+        for (visibility, kind) in self.modules.get(module).into_iter().flatten() {
+            if *visibility != Visibility::Public {
+                continue;
+            }
+            match kind {
+                BindingKind::Type(name) => {
+                    names.push((name.clone(), Target::Type(format!("{module}::{name}"))));
+                }
+                BindingKind::Module(name) => {
+                    names.push((name.clone(), Target::Module(format!("{module}::{name}"))));
+                }
+                // `pub use Trait as _` doesn't make anything nameable
+                BindingKind::Use { name, .. } if name == "_" => {}
+                BindingKind::Use { path, name } => {
+                    if let Some(target) = self.resolve(module, path, 0) {
+                        names.push((name.clone(), target));
+                    }
+                }
+                BindingKind::Glob(path) => {
+                    if let Some(Target::Module(source)) = self.resolve(module, path, 0)
+                        && globbed.insert(source.clone())
+                    {
+                        self.public_names(&source, names, globbed);
+                    }
+                }
+            }
+        }
+    }
+
+    // What `path` refers to when used in `module`
+    fn resolve(&self, module: &str, path: &[String], depth: usize) -> Option<Target> {
+        if depth > MAX_DEPTH {
+            return None;
+        }
+        let (first, rest) = path.split_first()?;
+        let mut current = match first.as_str() {
+            "crate" => Target::Module(module.split("::").next()?.to_string()),
+            "self" => Target::Module(module.to_string()),
+            "super" => Target::Module(parent(module)?.to_string()),
+            // Names in scope take precedence over crates of the same name
+            name => self.lookup(module, name, depth).or_else(|| {
+                self.crates
+                    .contains(name)
+                    .then(|| Target::Module(name.to_string()))
+            })?,
+        };
+        for segment in rest {
+            let Target::Module(module) = current else {
+                return None;
+            };
+            current = match segment.as_str() {
+                "self" => Target::Module(module),
+                "super" => Target::Module(parent(&module)?.to_string()),
+                name => self.lookup(&module, name, depth)?,
+            };
+        }
+        Some(current)
+    }
+
+    // What `name` refers to in `module`. Explicit items and imports shadow glob imports.
+    fn lookup(&self, module: &str, name: &str, depth: usize) -> Option<Target> {
+        if depth > MAX_DEPTH {
+            return None;
+        }
+        let key = (module.to_string(), name.to_string());
+        if let Some(target) = self.lookups.borrow().get(&key) {
+            return target.clone();
+        }
+        self.lookups.borrow_mut().insert(key.clone(), None);
+        let target = self.lookup_uncached(module, name, depth);
+        self.lookups.borrow_mut().insert(key, target.clone());
+        target
+    }
+
+    fn lookup_uncached(&self, module: &str, name: &str, depth: usize) -> Option<Target> {
+        let bindings = self.modules.get(module)?;
+        let explicit = bindings.iter().find_map(|(_, kind)| match kind {
+            BindingKind::Type(ty) if ty == name => Some(Target::Type(format!("{module}::{ty}"))),
+            BindingKind::Module(m) if m == name => Some(Target::Module(format!("{module}::{m}"))),
+            BindingKind::Use { path, name: alias } if alias == name => {
+                self.resolve(module, path, depth + 1)
+            }
+            _ => None,
+        });
+        // Private globs like `use super::*` only serve the module itself and are hardly ever
+        // part of a path, but following them is expensive
+        explicit.or_else(|| {
+            bindings.iter().find_map(|(visibility, kind)| match kind {
+                BindingKind::Glob(path) if *visibility > Visibility::Private => {
+                    match self.resolve(module, path, depth + 1)? {
+                        Target::Module(source) if source != module => {
+                            self.lookup(&source, name, depth + 1)
+                        }
+                        _ => None,
+                    }
+                }
+                _ => None,
+            })
+        })
+    }
+}
+
+fn parent(module: &str) -> Option<&str> {
+    module.rsplit_once("::").map(|(parent, _)| parent)
+}
@@ -0,0 +1,227 @@
+use crate::checks::{Check, FieldNotes};
+use crate::types::TypeInfo;
+use proc_macro2::Span as SynSpan;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// How bad a finding is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+/// Whether a finding is in the scanned workspace or in one of its dependencies.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CrateKind {
+    Workspace,
+    Dependency,
+}
+
+/// The package a dependency finding is in, for reporting it upstream.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Upstream {
+    pub package: String,
+    pub version: String,
+    /// Where the package comes from: `crates.io`, `path`, or the URL of its git repository or
+    /// other registry.
+    pub source: String,
+    /// The repository URL from the package's manifest.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub repository: Option<String>,
+}
+
+/// A 1-based line/column range in a source file, plus the equivalent 0-based byte range.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Span {
+    pub line: usize,
+    pub column: usize,
+    pub end_line: usize,
+    pub end_column: usize,
+    pub start_byte: usize,
+    pub end_byte: usize,
+}
+
+impl From<SynSpan> for Span {
+    fn from(span: SynSpan) -> Self {
+        // proc-macro2 columns are 0-based, editors count from 1
+        let (start, end, bytes) = (span.start(), span.end(), span.byte_range());
+        Self {
+            line: start.line,
+            column: start.column + 1,
+            end_line: end.line,
+            end_column: end.column + 1,
+            start_byte: bytes.start,
+            end_byte: bytes.end,
+        }
+    }
+}
+
+/// A source file that couldn't be parsed, so its types are missing from the analysis.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ParseError {
+    pub file: String,
+    /// The error from the parser.
+    pub message: String,
+    pub span: Span,
+}
+
+/// A type declaration found while scanning.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TypeLocation {
+    pub type_path: String,
+    /// Other public paths the type can be named by, through `pub use`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub reexports: Vec<String>,
+    /// The path of the module the type is defined in followed by its name, which `TypePath`
+    /// derives and scene files use. Differs from `type_path` when the type is re-exported.
+    #[serde(default)]
+    pub definition_path: String,
+    pub file: String,
+    pub span: Span,
+}
+
+/// A problem reported by one of the checks.
+#[derive(Debug, Clone, Serialize)]
+pub struct Finding {
+    pub check: &'static str,
+    pub severity: Severity,
+    pub message: String,
+    pub type_path: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub reexports: Vec<String>,
+    pub crate_kind: CrateKind,
+    /// How the type is stored if it derives `Component`, `Table` or `SparseSet`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub component_storage: Option<String>,
+    pub file: String,
+    pub span: Span,
+    /// The enum variant the finding is about, for checks about fields.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub variant: Option<String>,
+    /// The package of a finding in a dependency, if it's known from cargo metadata.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub upstream: Option<Upstream>,
+}
+
+impl Finding {
+    pub fn new(check: &Check, ty: &TypeInfo, crate_kind: CrateKind) -> Self {
+        let mut message = format!("`{}` {}", ty.location.type_path, check.problem);
+        if let Some(detail) = check.detail {
+            message.push_str(": ");
+            message.push_str(&detail(ty));
+        }
+        if let Some(field_detail) = check.field_detail {
+            message.push_str(": ");
+            message.push_str(&field_list(field_detail(ty)));
+        }
+        Self::at_type(check.id, check.default_severity, message, ty, crate_kind)
+    }
+
+    /// A finding of the rule `check` pointing at the name of `ty`.
+    pub fn at_type(
+        check: &'static str,
+        severity: Severity,
+        message: String,
+        ty: &TypeInfo,
+        crate_kind: CrateKind,
+    ) -> Self {
+        let location = ty.location.clone();
+        Self {
+            check,
+            severity,
+            message,
+            type_path: location.type_path,
+            reexports: location.reexports,
+            crate_kind,
+            component_storage: ty.attrs.component_storage().map(str::to_string),
+            file: location.file,
+            span: location.span,
+            variant: None,
+            upstream: None,
+        }
+    }
+
+    /// What the finding is about: the type path, followed by the variant if there is one.
+    pub fn subject(&self) -> String {
+        match &self.variant {
+            Some(variant) => format!("{}::{variant}", self.type_path),
+            None => self.type_path.clone(),
+        }
+    }
+
+    /// The findings of `check` for `ty`: one for the type, or for checks about fields of an enum,
+    /// one per offending variant that points at the variant.
+    pub fn all(check: &Check, ty: &TypeInfo, crate_kind: CrateKind) -> Vec<Self> {
+        let Some(field_detail) = check.field_detail.filter(|_| !ty.variants.is_empty()) else {
+            return vec![Self::new(check, ty, crate_kind)];
+        };
+        let mut by_variant: BTreeMap<&str, FieldNotes<'_>> = BTreeMap::new();
+        for (field, note) in field_detail(ty) {
+            if let Some((variant, field)) = field.split_once("::") {
+                by_variant.entry(variant).or_default().push((field, note));
+            }
+        }
+        ty.variants
+            .iter()
+            .filter_map(|variant| {
+                let fields = by_variant.remove(variant.name.as_str())?;
+                let mut finding = Self::new(check, ty, crate_kind);
+                finding.span = variant.span;
+                finding.variant = Some(variant.name.clone());
+                finding.message = format!(
+                    "`{}` {}: {}",
+                    finding.subject(),
+                    check.problem,
+                    field_list(fields)
+                );
+                Some(finding)
+            })
+            .collect()
+    }
+}
+
+/// Sort findings by crate, file, position and type, so that reports don't change between runs
+/// of the same code and can be diffed.
+pub fn sort(findings: &mut [Finding]) {
+    findings.sort_by(|a, b| sort_key(a).cmp(&sort_key(b)));
+}
+
+type SortKey<'a> = (
+    &'a str,
+    &'a str,
+    usize,
+    usize,
+    &'a str,
+    Option<&'a str>,
+    &'a str,
+);
+
+fn sort_key(finding: &Finding) -> SortKey<'_> {
+    (
+        finding.type_path.split("::").next().unwrap_or_default(),
+        &finding.file,
+        finding.span.line,
+        finding.span.column,
+        &finding.type_path,
+        finding.variant.as_deref(),
+        finding.check,
+    )
+}
+
+fn field_list(fields: FieldNotes<'_>) -> String {
+    fields
+        .into_iter()
+        .map(|(field, note)| {
+            if note.is_empty() {
+                format!("`{field}`")
+            } else {
+                format!("`{field}` ({note})")
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
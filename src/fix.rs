@@ -0,0 +1,252 @@
+use crate::attrs::reflect_entries;
+use crate::checks;
+use crate::finding::Finding;
+use similar::TextDiff;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::io;
+use syn::punctuated::Punctuated;
+use syn::spanned::Spanned;
+use syn::{Attribute, Item, Meta, Token};
+
+/// The new content of a source file with fixes applied.
+#[derive(Debug)]
+pub struct FileFix {
+    pub path: String,
+    pub original: String,
+    pub fixed: String,
+    /// Types that were fixed, by where their name starts.
+    fixed_types: BTreeSet<usize>,
+}
+
+impl FileFix {
+    /// Whether `finding` is taken care of by this fix.
+    pub fn covers(&self, finding: &Finding) -> bool {
+        finding.file == self.path
+            && self.fixed_types.contains(&finding.span.start_byte)
+            && checks::by_id(finding.check).is_some_and(|check| check.fix.is_some())
+    }
+
+    /// Write the fixed content back to the file.
+    pub fn apply(&self) -> io::Result<()> {
+        fs::write(&self.path, &self.fixed)
+    }
+
+    /// The change as a unified diff.
+    pub fn diff(&self) -> String {
+        TextDiff::from_lines(&self.original, &self.fixed)
+            .unified_diff()
+            .header(&self.path, &self.path)
+            .to_string()
+    }
+}
+
+/// Work out the edits for all findings of checks that can be fixed by adding type data to
+/// `#[reflect(...)]`. Files that can't be read or parsed are left alone.
+pub fn plan(findings: &[Finding]) -> Vec<FileFix> {
+    requested(findings)
+        .into_iter()
+        .filter_map(|(path, types)| fix_file(path, fs::read_to_string(path).ok()?, &types))
+        .collect()
+}
+
+/// Like [`plan`] for findings in a single file, but with `content` standing for the file, like
+/// an editor's unsaved buffer the findings were reported for.
+pub fn plan_source(findings: &[Finding], content: &str) -> Option<FileFix> {
+    let (path, types) = requested(findings).into_iter().next()?;
+    fix_file(path, content.to_string(), &types)
+}
+
+// Type data to add per file and type, with types identified by where their name starts, in the
+// order the checks list them
+fn requested(findings: &[Finding]) -> BTreeMap<&str, BTreeMap<usize, Vec<&'static str>>> {
+    let mut requested: BTreeMap<&str, BTreeMap<usize, Vec<&'static str>>> = BTreeMap::new();
+    for finding in findings {
+        if let Some(fix) = checks::by_id(finding.check).and_then(|check| check.fix) {
+            let names = requested
+                .entry(&finding.file)
+                .or_default()
+                .entry(finding.span.start_byte)
+                .or_default();
+            for name in fix {
+                if !names.contains(name) {
+                    names.push(name);
+                }
+            }
+        }
+    }
+    requested
+}
+
+fn fix_file(
+    path: &str,
+    original: String,
+    types: &BTreeMap<usize, Vec<&'static str>>,
+) -> Option<FileFix> {
+    let syntax = syn::parse_file(&original).ok()?;
+    let mut edits = Vec::new();
+    let mut fixed_types = BTreeSet::new();
+    visit_types(&syntax.items, &mut |ident, attrs| {
+        let start = ident.span().byte_range().start;
+        if let Some(names) = types.get(&start)
+            && let Some(edit) = edit_for(&original, attrs, names)
+        {
+            edits.push(edit);
+            fixed_types.insert(start);
+        }
+    });
+    if edits.is_empty() {
+        return None;
+    }
+
+    // Back to front, so that earlier offsets stay valid
+    edits.sort_by_key(|(offset, _)| *offset);
+    let mut fixed = original.clone();
+    for (offset, text) in edits.into_iter().rev() {
+        fixed.insert_str(offset, &text);
+    }
+    Some(FileFix {
+        path: path.to_string(),
+        original,
+        fixed,
+        fixed_types,
+    })
+}
+
+// Call `f` with the name and attributes of every struct and enum, including those in inline
+// modules
+fn visit_types(items: &[Item], f: &mut impl FnMut(&syn::Ident, &[Attribute])) {
+    for item in items {
+        match item {
+            Item::Struct(s) => f(&s.ident, &s.attrs),
+            Item::Enum(e) => f(&e.ident, &e.attrs),
+            Item::Mod(m) => {
+                if let Some((_, items)) = &m.content {
+                    visit_types(items, f);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+// Where to insert what to register `names` as type data. An existing `#[reflect(...)]` is
+// extended, otherwise one is added below the `derive` that brings in `Reflect`, behind the same
+// `cfg_attr` condition if there is one. Names the type already reflects, like `State` when only
+// `FreelyMutableState` is missing, aren't added again.
+fn edit_for(source: &str, attrs: &[Attribute], names: &[&str]) -> Option<(usize, String)> {
+    let existing = reflected(attrs);
+    let names: Vec<&str> = names
+        .iter()
+        .copied()
+        .filter(|name| !existing.iter().any(|entry| entry == name))
+        .collect();
+    if names.is_empty() {
+        return None;
+    }
+    let names = names.join(", ");
+    let (derive_attr, condition) = attrs.iter().find_map(|attr| reflect_derive(source, attr))?;
+
+    if condition.is_none()
+        && let Some(Meta::List(list)) = attrs
+            .iter()
+            .map(|attr| &attr.meta)
+            .find(|meta| meta.path().is_ident("reflect"))
+    {
+        let delimiter = list.delimiter.span();
+        let open = delimiter.open().byte_range().end;
+        let close = delimiter.close().byte_range().start;
+        let existing = source.get(open..close)?.trim_end();
+        let text = if existing.trim().is_empty() {
+            names
+        } else if existing.ends_with(',') {
+            format!(" {names}")
+        } else {
+            format!(", {names}")
+        };
+        // Insert right after the last entry, before any trailing whitespace
+        return Some((open + existing.len(), text));
+    }
+
+    let start = derive_attr.pound_token.span.byte_range().start;
+    let end = derive_attr.bracket_token.span.close().byte_range().end;
+    if source.get(start..start + 1) != Some("#") {
+        return None;
+    }
+    let line_start = source[..start].rfind('\n').map_or(0, |index| index + 1);
+    let indent: String = source[line_start..start]
+        .chars()
+        .take_while(|c| c.is_whitespace())
+        .collect();
+    let attribute = match condition {
+        Some(condition) => format!("#[cfg_attr({condition}, reflect({names}))]"),
+        None => format!("#[reflect({names})]"),
+    };
+    Some((end, format!("\n{indent}{attribute}")))
+}
+
+// The entries of all `#[reflect(...)]` attributes, including those behind `cfg_attr`
+fn reflected(attrs: &[Attribute]) -> Vec<String> {
+    let mut entries = Vec::new();
+    for attr in attrs {
+        let Meta::List(list) = &attr.meta else {
+            continue;
+        };
+        if list.path.is_ident("reflect") {
+            entries.extend(reflect_entries(list.tokens.clone()));
+        } else if list.path.is_ident("cfg_attr")
+            && let Ok(nested) =
+                list.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)
+        {
+            for meta in nested.iter().skip(1) {
+                if let Meta::List(list) = meta
+                    && list.path.is_ident("reflect")
+                {
+                    entries.extend(reflect_entries(list.tokens.clone()));
+                }
+            }
+        }
+    }
+    entries
+}
+
+// If `attr` derives `Reflect`, the attribute along with the source of its `cfg_attr` condition
+fn reflect_derive<'a>(
+    source: &str,
+    attr: &'a Attribute,
+) -> Option<(&'a Attribute, Option<String>)> {
+    let Meta::List(list) = &attr.meta else {
+        return None;
+    };
+    if list.path.is_ident("derive") {
+        return derives_reflect(list).then_some((attr, None));
+    }
+    if list.path.is_ident("cfg_attr") {
+        let nested = list
+            .parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)
+            .ok()?;
+        let mut nested = nested.iter();
+        let condition = nested.next()?;
+        if nested.any(|meta| {
+            matches!(meta, Meta::List(list) if list.path.is_ident("derive") && derives_reflect(list))
+        }) {
+            let range = condition.span().byte_range();
+            return Some((attr, Some(source.get(range)?.to_string())));
+        }
+    }
+    None
+}
+
+fn derives_reflect(list: &syn::MetaList) -> bool {
+    let mut found = false;
+    list.parse_nested_meta(|meta| {
+        found |= meta
+            .path
+            .segments
+            .last()
+            .is_some_and(|segment| segment.ident == "Reflect");
+        Ok(())
+    })
+    .ok();
+    found
+}
@@ -0,0 +1,145 @@
+use globset::{GlobBuilder, GlobMatcher};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Files with gitignore-style patterns of paths to skip, looked up in every scanned directory.
+/// `.gitignore` only counts within a git repository.
+const IGNORE_FILES: &[&str] = &[".gitignore", ".ignore", ".reflectcheckignore"];
+
+/// The ignore files that apply at some point of a directory walk, from the outermost directory
+/// to the innermost one.
+#[derive(Debug, Default)]
+pub(crate) struct Ignores {
+    /// Each file with the depth of its directory in the walk, ancestors of the walk's root being
+    /// at depth 0 along with the root itself.
+    files: Vec<(usize, IgnoreFile)>,
+    in_git_repo: bool,
+}
+
+impl Ignores {
+    /// The ignore files of `root` and its ancestors. Those of `.gitignore` only go up to the root
+    /// of the git repository.
+    pub fn for_root(root: &Path) -> Self {
+        let root = std::path::absolute(root).unwrap_or_else(|_| root.to_path_buf());
+        let git_root = root.ancestors().find(|dir| dir.join(".git").exists());
+        let mut files = Vec::new();
+        for dir in root.ancestors() {
+            let in_repo = git_root.is_some_and(|git_root| dir.starts_with(git_root));
+            // Files closer to the root come later, as they take precedence
+            for name in IGNORE_FILES.iter().rev() {
+                if (*name != ".gitignore" || in_repo)
+                    && let Some(file) = IgnoreFile::load(dir, name)
+                {
+                    files.push((0, file));
+                }
+            }
+        }
+        files.reverse();
+        Self {
+            files,
+            in_git_repo: git_root.is_some(),
+        }
+    }
+
+    /// Move to the entry at `depth` below the root, dropping the files of the directories the
+    /// walk has left.
+    pub fn enter(&mut self, depth: usize) {
+        self.files
+            .retain(|(file_depth, _)| *file_depth == 0 || *file_depth < depth);
+    }
+
+    /// Add the ignore files of a directory at `depth`, which apply to everything below it.
+    pub fn add_dir(&mut self, dir: &Path, depth: usize) {
+        for name in IGNORE_FILES {
+            if (*name != ".gitignore" || self.in_git_repo)
+                && let Some(file) = IgnoreFile::load(dir, name)
+            {
+                self.files.push((depth, file));
+            }
+        }
+    }
+
+    /// Whether the innermost pattern that matches `path` ignores it.
+    pub fn is_ignored(&self, path: &Path, is_dir: bool) -> bool {
+        let path = std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf());
+        self.files
+            .iter()
+            .rev()
+            .find_map(|(_, file)| file.matches(&path, is_dir))
+            .unwrap_or(false)
+    }
+}
+
+/// The patterns of one ignore file.
+#[derive(Debug)]
+struct IgnoreFile {
+    /// The directory the patterns are relative to.
+    dir: PathBuf,
+    patterns: Vec<Pattern>,
+}
+
+#[derive(Debug)]
+struct Pattern {
+    matcher: GlobMatcher,
+    /// `!pattern`, which brings back a path that an earlier pattern ignores.
+    negated: bool,
+    /// `pattern/`, which only matches directories.
+    dir_only: bool,
+}
+
+impl IgnoreFile {
+    fn load(dir: &Path, name: &str) -> Option<Self> {
+        let content = fs::read_to_string(dir.join(name)).ok()?;
+        let patterns: Vec<Pattern> = content.lines().filter_map(Pattern::parse).collect();
+        (!patterns.is_empty()).then(|| Self {
+            dir: std::path::absolute(dir).unwrap_or_else(|_| dir.to_path_buf()),
+            patterns,
+        })
+    }
+
+    // `Some(true)` if the last matching pattern ignores the path, `Some(false)` if it brings it
+    // back, `None` if no pattern matches
+    fn matches(&self, path: &Path, is_dir: bool) -> Option<bool> {
+        let relative = path.strip_prefix(&self.dir).ok()?;
+        self.patterns
+            .iter()
+            .rev()
+            .find(|pattern| (is_dir || !pattern.dir_only) && pattern.matcher.is_match(relative))
+            .map(|pattern| !pattern.negated)
+    }
+}
+
+impl Pattern {
+    // A line of an ignore file in gitignore syntax, `None` for blank lines and comments
+    fn parse(line: &str) -> Option<Self> {
+        let line = line.trim_end();
+        if line.is_empty() || line.starts_with('#') {
+            return None;
+        }
+        let (negated, line) = match line.strip_prefix('!') {
+            Some(rest) => (true, rest),
+            None => (false, line.strip_prefix('\\').unwrap_or(line)),
+        };
+        let (dir_only, line) = match line.strip_suffix('/') {
+            Some(rest) => (true, rest),
+            None => (false, line),
+        };
+        // A slash anywhere but at the end ties the pattern to the ignore file's directory,
+        // otherwise it matches names at any depth
+        let glob = match line.strip_prefix('/') {
+            Some(anchored) => anchored.to_string(),
+            None if line.contains('/') => line.to_string(),
+            None => format!("**/{line}"),
+        };
+        let matcher = GlobBuilder::new(&glob)
+            .literal_separator(true)
+            .build()
+            .ok()?
+            .compile_matcher();
+        Some(Self {
+            matcher,
+            negated,
+            dir_only,
+        })
+    }
+}
@@ -0,0 +1,40 @@
+//! Finds Bevy types with an incomplete reflection setup, e.g. components that derive `Reflect`
+//! but lack `#[reflect(Component)]`.
+//!
+//! ```no_run
+//! use bevy_reflect_check::{AnalysisConfig, Analyzer};
+//!
+//! let metadata = cargo_metadata::MetadataCommand::new().exec().unwrap();
+//! let analysis = Analyzer::new(AnalysisConfig::default(), metadata).analyze();
+//! for finding in &analysis.findings {
+//!     println!("{}: {}", finding.file, finding.message);
+//! }
+//! ```
+
+mod analyzer;
+pub mod attrs;
+pub mod baseline;
+mod cache;
+pub mod cfg;
+pub mod checks;
+mod collect;
+pub mod diff;
+mod expand;
+mod exports;
+pub mod finding;
+pub mod fix;
+mod ignore;
+mod modules;
+pub mod output;
+mod prefilter;
+pub mod rules;
+mod rustdoc;
+pub mod scene;
+pub mod stats;
+pub mod suppress;
+pub mod types;
+mod versions;
+
+pub use analyzer::{Analysis, AnalysisConfig, Analyzer, Backend, Progress, Timings};
+pub use finding::{CrateKind, Finding, ParseError, Severity, Span, TypeLocation, Upstream};
+pub use modules::Visibility;
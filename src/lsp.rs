@@ -0,0 +1,430 @@
+use bevy_reflect_check::{Analysis, Finding, Severity, Span, checks, fix};
+use serde_json::{Value, json};
+use std::borrow::Cow;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::io::{self, BufRead, Write};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Name of the diagnostics' source, shown by editors next to the message.
+const SOURCE: &str = "reflect-check";
+/// JSON-RPC error code for requests the server doesn't handle.
+const METHOD_NOT_FOUND: i64 = -32601;
+/// How long the client has to stop sending changes before the changed documents are checked.
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Speak the Language Server Protocol on stdin and stdout until the client exits.
+///
+/// `analyze` is called once the client is ready and whenever a document is saved, and the
+/// findings are published as diagnostics per file. While typing, the open documents that changed
+/// are checked on their own with `analyze_source` once the changes pause, which doesn't see
+/// other files until the next save. Findings that `--fix` can handle come with a quick fix as
+/// code action.
+pub fn serve(
+    analyze: impl Fn() -> Analysis,
+    analyze_source: impl Fn(&str, &str) -> Analysis,
+) -> io::Result<()> {
+    let messages = read_messages();
+    let mut output = io::stdout().lock();
+    let mut server = Server {
+        findings: Vec::new(),
+        published: BTreeSet::new(),
+        documents: BTreeMap::new(),
+        changed: BTreeSet::new(),
+    };
+    // When to check the changed documents, if any
+    let mut deadline: Option<Instant> = None;
+    loop {
+        let received = match deadline {
+            Some(at) => messages.recv_timeout(at.saturating_duration_since(Instant::now())),
+            None => messages.recv().map_err(RecvTimeoutError::from),
+        };
+        let message = match received {
+            Ok(message) => message?,
+            Err(RecvTimeoutError::Timeout) => {
+                deadline = None;
+                server.check_changed(&mut output, &analyze_source)?;
+                continue;
+            }
+            Err(RecvTimeoutError::Disconnected) => return Ok(()),
+        };
+        let id = message.get("id").cloned();
+        // Responses from the client have no method and need no handling
+        let method = message["method"].as_str().unwrap_or_default();
+        let params = &message["params"];
+        let uri = params["textDocument"]["uri"].as_str().unwrap_or_default();
+        match (method, id) {
+            ("initialize", Some(id)) => respond(&mut output, id, capabilities())?,
+            ("initialized", _) => server.check(&mut output, analyze())?,
+            ("textDocument/didOpen", _) => {
+                let text = params["textDocument"]["text"].as_str().unwrap_or_default();
+                server.documents.insert(uri.to_string(), text.to_string());
+            }
+            ("textDocument/didChange", _) => {
+                // Full sync, so the last change holds the whole text
+                if let Some(text) = params["contentChanges"]
+                    .as_array()
+                    .and_then(|changes| changes.last())
+                    .and_then(|change| change["text"].as_str())
+                {
+                    server.documents.insert(uri.to_string(), text.to_string());
+                    server.changed.insert(uri.to_string());
+                    deadline = Some(Instant::now() + DEBOUNCE);
+                }
+            }
+            ("textDocument/didSave", _) => {
+                server.changed.remove(uri);
+                server.check(&mut output, analyze())?;
+            }
+            ("textDocument/didClose", _) => {
+                server.documents.remove(uri);
+                server.changed.remove(uri);
+            }
+            ("textDocument/codeAction", Some(id)) => {
+                respond(&mut output, id, server.code_actions(params))?;
+            }
+            ("shutdown", Some(id)) => respond(&mut output, id, Value::Null)?,
+            ("exit", _) => return Ok(()),
+            (method, Some(id)) if !method.is_empty() => {
+                let error = json!({
+                    "code": METHOD_NOT_FOUND,
+                    "message": format!("Unsupported method {method}"),
+                });
+                send(
+                    &mut output,
+                    json!({"jsonrpc": "2.0", "id": id, "error": error}),
+                )?;
+            }
+            _ => {}
+        }
+    }
+}
+
+// Read the messages of the client on a thread of their own, so that waiting for them can time
+// out when changed documents are due to be checked
+fn read_messages() -> Receiver<io::Result<Value>> {
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        let mut input = io::stdin().lock();
+        loop {
+            let message = match read_message(&mut input) {
+                Ok(Some(message)) => Ok(message),
+                Ok(None) => return,
+                Err(err) => Err(err),
+            };
+            let failed = message.is_err();
+            if sender.send(message).is_err() || failed {
+                return;
+            }
+        }
+    });
+    receiver
+}
+
+struct Server {
+    /// Findings of the last analysis, for code actions.
+    findings: Vec<Finding>,
+    /// Documents that got diagnostics, so that they can be cleared when they're fixed.
+    published: BTreeSet<String>,
+    /// The text of the open documents by URI, as the client last sent it.
+    documents: BTreeMap<String, String>,
+    /// Open documents that changed since they were last checked.
+    changed: BTreeSet<String>,
+}
+
+impl Server {
+    // Publish the diagnostics of a fresh analysis
+    fn check(&mut self, output: &mut impl Write, analysis: Analysis) -> io::Result<()> {
+        let mut diagnostics = self.diagnostics(&analysis);
+
+        // Files without findings anymore get an empty list to clear what was shown before
+        let published: BTreeSet<String> = diagnostics.keys().cloned().collect();
+        for uri in self.published.difference(&published) {
+            diagnostics.entry(uri.clone()).or_default();
+        }
+        for (uri, diagnostics) in diagnostics {
+            publish(output, &uri, diagnostics)?;
+        }
+        self.published = published;
+        self.findings = analysis.findings;
+        Ok(())
+    }
+
+    // Check the documents that changed from their text in the editor and publish their
+    // diagnostics, replacing those of the last analysis
+    fn check_changed(
+        &mut self,
+        output: &mut impl Write,
+        analyze_source: &impl Fn(&str, &str) -> Analysis,
+    ) -> io::Result<()> {
+        for uri in std::mem::take(&mut self.changed) {
+            let (Some(path), Some(text)) = (uri_path(&uri), self.documents.get(&uri)) else {
+                continue;
+            };
+            let analysis = analyze_source(&path.to_string_lossy(), text);
+            let diagnostics = self.diagnostics(&analysis).remove(&uri).unwrap_or_default();
+            if diagnostics.is_empty() {
+                self.published.remove(&uri);
+            } else {
+                self.published.insert(uri.clone());
+            }
+            publish(output, &uri, diagnostics)?;
+            self.findings
+                .retain(|finding| absolute_path(&finding.file) != path);
+            self.findings.extend(analysis.findings);
+        }
+        Ok(())
+    }
+
+    // The diagnostics of the findings and parse errors of `analysis` by document
+    fn diagnostics(&self, analysis: &Analysis) -> BTreeMap<String, Vec<Value>> {
+        let mut diagnostics: BTreeMap<String, Vec<Value>> = BTreeMap::new();
+        let mut texts: BTreeMap<String, Option<Cow<'_, str>>> = BTreeMap::new();
+        for finding in &analysis.findings {
+            let uri = file_uri(&finding.file);
+            let text = texts
+                .entry(uri.clone())
+                .or_insert_with(|| self.text(&finding.file));
+            let diagnostic = diagnostic(finding, text.as_deref());
+            diagnostics.entry(uri).or_default().push(diagnostic);
+        }
+        for error in &analysis.parse_errors {
+            let uri = file_uri(&error.file);
+            let text = texts
+                .entry(uri.clone())
+                .or_insert_with(|| self.text(&error.file));
+            let diagnostic = json!({
+                "range": range(text.as_deref(), &error.span),
+                "severity": severity(Severity::Warning),
+                "source": SOURCE,
+                "message": format!("failed to parse, skipped: {}", error.message),
+            });
+            diagnostics.entry(uri).or_default().push(diagnostic);
+        }
+        diagnostics
+    }
+
+    // The content of `file`, from the editor if it's open there
+    fn text(&self, file: &str) -> Option<Cow<'_, str>> {
+        match self.documents.get(&file_uri(file)) {
+            Some(text) => Some(Cow::Borrowed(text)),
+            None => fs::read_to_string(file).ok().map(Cow::Owned),
+        }
+    }
+
+    // Quick fixes for the fixable findings in the requested range
+    fn code_actions(&self, params: &Value) -> Value {
+        let Some(path) = params["textDocument"]["uri"].as_str().and_then(uri_path) else {
+            return json!([]);
+        };
+        let start_line = params["range"]["start"]["line"].as_u64().unwrap_or(0) as usize;
+        let end_line = params["range"]["end"]["line"].as_u64().unwrap_or(0) as usize;
+        let actions: Vec<Value> = self
+            .findings
+            .iter()
+            .filter(|finding| {
+                absolute_path(&finding.file) == path
+                    && finding.span.line - 1 <= end_line
+                    && finding.span.end_line > start_line
+            })
+            .filter_map(|finding| {
+                let type_data = checks::by_id(finding.check)?.fix?;
+                let text = self.text(&finding.file)?;
+                let file_fix = fix::plan_source(std::slice::from_ref(finding), &text)?;
+                let edit = text_edit(&file_fix.original, &file_fix.fixed);
+                Some(json!({
+                    "title": format!("Add `{}` to `#[reflect(...)]`", type_data.join(", ")),
+                    "kind": "quickfix",
+                    "diagnostics": [diagnostic(finding, Some(&text))],
+                    "edit": {"changes": {file_uri(&finding.file): [edit]}},
+                }))
+            })
+            .collect();
+        json!(actions)
+    }
+}
+
+fn publish(output: &mut impl Write, uri: &str, diagnostics: Vec<Value>) -> io::Result<()> {
+    send(
+        output,
+        json!({
+            "jsonrpc": "2.0",
+            "method": "textDocument/publishDiagnostics",
+            "params": {"uri": uri, "diagnostics": diagnostics},
+        }),
+    )
+}
+
+fn capabilities() -> Value {
+    json!({
+        "capabilities": {
+            "textDocumentSync": {"openClose": true, "change": 1, "save": true},
+            "codeActionProvider": true,
+        },
+        "serverInfo": {"name": SOURCE, "version": env!("CARGO_PKG_VERSION")},
+    })
+}
+
+fn diagnostic(finding: &Finding, text: Option<&str>) -> Value {
+    json!({
+        "range": range(text, &finding.span),
+        "severity": severity(finding.severity),
+        "code": finding.check,
+        "source": SOURCE,
+        "message": finding.message,
+    })
+}
+
+fn severity(severity: Severity) -> u8 {
+    match severity {
+        Severity::Error => 1,
+        Severity::Warning => 2,
+        Severity::Info => 3,
+    }
+}
+
+// The protocol counts lines from 0 and columns in UTF-16 code units, which the span's byte
+// offsets into `text` give. Spans count from 1 and in characters, which is used as it is if the
+// offsets don't fit the text, e.g. because the file changed since.
+fn range(text: Option<&str>, span: &Span) -> Value {
+    if let Some(text) = text
+        && text.is_char_boundary(span.start_byte)
+        && text.is_char_boundary(span.end_byte)
+        && span.start_byte <= span.end_byte
+        && text[..span.start_byte].matches('\n').count() + 1 == span.line
+    {
+        return json!({
+            "start": position(text, span.start_byte),
+            "end": position(text, span.end_byte),
+        });
+    }
+    json!({
+        "start": {"line": span.line - 1, "character": span.column - 1},
+        "end": {"line": span.end_line - 1, "character": span.end_column - 1},
+    })
+}
+
+// A single edit replacing the part of `original` that differs from `fixed`
+fn text_edit(original: &str, fixed: &str) -> Value {
+    // Boundaries are kept on characters, so that slicing doesn't panic
+    let prefix = original
+        .bytes()
+        .zip(fixed.bytes())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let prefix = (0..=prefix)
+        .rev()
+        .find(|&offset| original.is_char_boundary(offset) && fixed.is_char_boundary(offset))
+        .unwrap_or(0);
+    let suffix = original[prefix..]
+        .bytes()
+        .rev()
+        .zip(fixed[prefix..].bytes().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let suffix = (0..=suffix)
+        .rev()
+        .find(|&len| {
+            original.is_char_boundary(original.len() - len)
+                && fixed.is_char_boundary(fixed.len() - len)
+        })
+        .unwrap_or(0);
+    json!({
+        "range": {
+            "start": position(original, prefix),
+            "end": position(original, original.len() - suffix),
+        },
+        "newText": fixed[prefix..fixed.len() - suffix],
+    })
+}
+
+// Line and UTF-16 offset of a byte offset, as the protocol counts characters
+fn position(text: &str, offset: usize) -> Value {
+    let before = &text[..offset];
+    let line_start = before.rfind('\n').map_or(0, |index| index + 1);
+    json!({
+        "line": before.matches('\n').count(),
+        "character": before[line_start..].encode_utf16().count(),
+    })
+}
+
+fn absolute_path(file: &str) -> PathBuf {
+    std::path::absolute(file).unwrap_or_else(|_| PathBuf::from(file))
+}
+
+fn file_uri(file: &str) -> String {
+    let path = absolute_path(file);
+    let mut uri = String::from("file://");
+    for byte in path.to_string_lossy().bytes() {
+        if byte.is_ascii_alphanumeric() || b"/-._~".contains(&byte) {
+            uri.push(byte as char);
+        } else {
+            uri.push_str(&format!("%{byte:02X}"));
+        }
+    }
+    uri
+}
+
+fn uri_path(uri: &str) -> Option<PathBuf> {
+    let encoded = uri.strip_prefix("file://")?.as_bytes();
+    let mut bytes = Vec::with_capacity(encoded.len());
+    let mut index = 0;
+    while index < encoded.len() {
+        let decoded = (encoded[index] == b'%')
+            .then(|| encoded.get(index + 1..index + 3))
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok());
+        match decoded {
+            Some(byte) => {
+                bytes.push(byte);
+                index += 3;
+            }
+            None => {
+                bytes.push(encoded[index]);
+                index += 1;
+            }
+        }
+    }
+    Some(Path::new(&String::from_utf8(bytes).ok()?).to_path_buf())
+}
+
+// A message framed by a `Content-Length` header, or `None` at the end of the input
+fn read_message(input: &mut impl BufRead) -> io::Result<Option<Value>> {
+    let mut length = None;
+    loop {
+        let mut line = String::new();
+        if input.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':')
+            && name.eq_ignore_ascii_case("Content-Length")
+        {
+            length = value.trim().parse().ok();
+        }
+    }
+    let length = length
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "Missing Content-Length"))?;
+    let mut body = vec![0; length];
+    input.read_exact(&mut body)?;
+    Ok(Some(serde_json::from_slice(&body)?))
+}
+
+fn respond(output: &mut impl Write, id: Value, result: Value) -> io::Result<()> {
+    send(
+        output,
+        json!({"jsonrpc": "2.0", "id": id, "result": result}),
+    )
+}
+
+fn send(output: &mut impl Write, message: Value) -> io::Result<()> {
+    let body = message.to_string();
+    write!(output, "Content-Length: {}\r\n\r\n{body}", body.len())?;
+    output.flush()
+}
@@ -1,274 +1,587 @@
-use cargo_metadata::camino::Utf8PathBuf;
-use cargo_metadata::{Metadata, MetadataCommand};
-use std::collections::HashMap;
-use std::{fs, path::Path};
-use syn::punctuated::Punctuated;
-use syn::{Attribute, File, Item, Meta, Token, Visibility, parse_file};
-use walkdir::{DirEntry, WalkDir};
-
-fn main() {
-    // Fetch metadata for dependency crates
-    let metadata = MetadataCommand::new()
-        .exec()
-        .expect("Failed to fetch cargo metadata");
-
-    // Collect all source files from the current project and dependencies
-    let mut source_files = Vec::new();
-    collect_source_files("./src", &mut source_files); // Scan only `src` in the current project
-    collect_dependency_files(&metadata, &mut source_files); // Dependencies
-
-    // Build a module hierarchy
-    let mut module_tree = HashMap::new();
-    for path in &source_files {
-        if let Ok(content) = fs::read_to_string(path) {
-            if let Ok(syntax) = parse_file(&content) {
-                build_module_tree(path, &syntax, &mut module_tree);
+mod cli;
+mod config;
+mod error;
+mod lsp;
+mod progress;
+#[cfg(feature = "tui")]
+mod tui;
+mod watch;
+
+use bevy_reflect_check::baseline::Baseline;
+use bevy_reflect_check::diff::ReportDiff;
+use bevy_reflect_check::output::OutputFormat;
+use bevy_reflect_check::stats::{RegistrySize, Stats};
+use bevy_reflect_check::types::TypeInfo;
+use bevy_reflect_check::{Analysis, Analyzer, CrateKind, Finding, ParseError};
+use bevy_reflect_check::{checks, fix, output, scene};
+use cargo_metadata::{CargoOpt, MetadataCommand};
+use clap::Parser;
+use cli::{Cli, Command, ScanArgs};
+use config::{Config, Settings};
+use error::{EXIT_FINDINGS, EXIT_INTERNAL, Error, output_error};
+use globset::Glob;
+use progress::{ProgressReporter, Verbosity};
+use std::env;
+use std::ffi::OsString;
+use std::fs;
+use std::io::{self, IsTerminal, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::time::Instant;
+use walkdir::WalkDir;
+
+/// How many modules `--stats` lists as the ones with the most findings.
+const WORST_MODULES: usize = 10;
+
+fn main() -> ExitCode {
+    // Panics are bugs, which get an exit status of their own rather than the one for findings
+    std::panic::set_hook(Box::new(|info| {
+        Error::Internal(format!("internal error, this is a bug: {info}")).report();
+        std::process::exit(EXIT_INTERNAL.into());
+    }));
+
+    // As `cargo reflect-check`, cargo passes the name of the subcommand as the first argument
+    let mut args: Vec<OsString> = env::args_os().collect();
+    if args.get(1).is_some_and(|arg| arg == "reflect-check") {
+        args.remove(1);
+    }
+    match run(Cli::parse_from(args)) {
+        Ok(code) => code,
+        // The reader went away, e.g. `head`, which isn't a problem
+        Err(Error::Io(_, err)) if err.kind() == io::ErrorKind::BrokenPipe => ExitCode::SUCCESS,
+        Err(err) => {
+            err.report();
+            err.exit_code()
+        }
+    }
+}
+
+fn run(mut cli: Cli) -> Result<ExitCode, Error> {
+    let command = cli.command.take().unwrap_or(Command::Check);
+
+    if let Command::Explain { rule } = &command {
+        let check = checks::by_id(rule).expect("rule ids are validated by clap");
+        io::stdout()
+            .write_all(check.explanation.as_bytes())
+            .map_err(output_error)?;
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    if let Command::Diff { old, new } = &command {
+        // Comparing reports doesn't scan anything, so only the output options apply
+        let diff = ReportDiff::load(old, new).map_err(Error::Reports)?;
+        output::print_diff(&diff, cli.scan.format.unwrap_or_default()).map_err(output_error)?;
+        let failed = cli
+            .scan
+            .fail_on
+            .unwrap_or_default()
+            .threshold()
+            .is_some_and(|threshold| {
+                diff.added
+                    .iter()
+                    .any(|finding| finding.severity >= threshold)
+            });
+        return Ok(exit_code(failed));
+    }
+
+    // Fetch metadata for dependency crates, unless only the given source trees are scanned
+    let metadata_started = Instant::now();
+    let mut metadata = None;
+    if cli.scan.paths.is_empty() {
+        let manifest_path = cli.scan.manifest_path.clone().or_else(|| {
+            env::var_os("CARGO_MANIFEST_DIR").map(|dir| PathBuf::from(dir).join("Cargo.toml"))
+        });
+        match metadata_command(&cli.scan, manifest_path.as_deref()).exec() {
+            Ok(fetched) => metadata = Some(fetched),
+            // A single file is placed by the `Cargo.toml` above it, if there's one at all
+            Err(_) if cli.scan.stdin => {}
+            Err(err) => {
+                // Without metadata, the workspace members are taken from the manifests, and
+                // dependencies can't be found
+                let root = manifest_path
+                    .as_deref()
+                    .and_then(Path::parent)
+                    .filter(|dir| !dir.as_os_str().is_empty())
+                    .map_or_else(|| PathBuf::from("."), Path::to_path_buf);
+                cli.scan.paths = local_workspace_dirs(&root);
+                if cli.scan.paths.is_empty() {
+                    return Err(Error::Metadata(err));
+                }
+                if !cli.scan.quiet {
+                    eprintln!(
+                        "Failed to fetch cargo metadata, scanning only the local workspace \
+                        sources: {}",
+                        err.to_string().trim_end()
+                    );
+                }
             }
         }
     }
 
-    // Track collected types with fully qualified paths
-    let mut reflect_types = Vec::new();
-    for (path, syntax) in &module_tree {
-        if let Some(module_path) = resolve_module_path(path, &metadata) {
-            collect_reflect_types(
-                syntax,
-                &module_path,
-                &mut reflect_types,
-                /* public_only = */ true,
-                /* parent_is_public = */ true,
-            );
+    let metadata_time = metadata_started.elapsed();
+
+    // Settings from the command line override the ones from the configuration file
+    let workspace_root = match &metadata {
+        Some(metadata) => metadata.workspace_root.clone().into_std_path_buf(),
+        None => env::current_dir().map_err(|err| {
+            Error::Internal(format!("Failed to get the current directory: {err}"))
+        })?,
+    };
+    #[cfg(feature = "tui")]
+    let config_path = Config::path(cli.scan.config.as_deref(), &workspace_root);
+    let config = Config::load(cli.scan.config.as_deref(), &workspace_root)?;
+    let mut settings = Settings::new(cli.scan, config, &workspace_root)?;
+    if let Command::Crate { name } = &command {
+        if !metadata.as_ref().is_some_and(|metadata| {
+            metadata
+                .packages
+                .iter()
+                .any(|package| package.name == *name)
+        }) {
+            return Err(Error::Package(name.clone()));
         }
+        settings.analysis.package = Some(name.clone());
     }
-    println!("{:?}", reflect_types);
-}
 
-// Recursively collect all `.rs` files in a directory, excluding `examples` and `tests`
-fn collect_source_files(dir: &str, source_files: &mut Vec<String>) {
-    for entry in WalkDir::new(dir)
-        .into_iter()
-        .filter_entry(should_include_dir)
-        .filter_map(|e| e.ok())
-    {
-        if entry.path().extension().and_then(|ext| ext.to_str()) == Some("rs") {
-            source_files.push(entry.path().to_string_lossy().into_owned());
+    if let Some(jobs) = settings.jobs {
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(jobs)
+            .build_global()
+            .map_err(|err| Error::Internal(format!("Failed to set up thread pool: {err}")))?;
+    }
+
+    let analyzer = match metadata {
+        Some(metadata) => Analyzer::new(settings.analysis.clone(), metadata),
+        None => Analyzer::without_metadata(settings.analysis.clone()),
+    };
+    let analyzer = settings
+        .custom_rules
+        .iter()
+        .fold(analyzer, |analyzer, rule| {
+            analyzer.register_rule(rule.clone())
+        });
+    let analyzer = if settings.verbosity > Verbosity::Quiet {
+        let reporter = ProgressReporter::new(settings.verbosity);
+        analyzer.on_progress(move |progress| reporter.report(progress))
+    } else {
+        analyzer
+    };
+
+    if let Command::Watch = command {
+        watch_workspace(&analyzer, &settings, &workspace_root)?;
+    }
+    if let Command::Lsp = command {
+        // The baseline is only read, like in `watch`
+        let baseline = match &settings.baseline {
+            Some(path) => load_baseline(path)?,
+            None => None,
+        };
+        lsp::serve(
+            || reanalyze(&analyzer, &settings, baseline.as_ref()),
+            |path, content| {
+                reanalyze_source(&analyzer, &settings, baseline.as_ref(), path, content)
+            },
+        )
+        .map_err(|err| Error::Io("communicate with the language client", err))?;
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    let analysis = match &settings.stdin {
+        Some(path) => {
+            let mut content = String::new();
+            io::stdin()
+                .read_to_string(&mut content)
+                .map_err(|err| Error::Read(PathBuf::from("stdin"), err.to_string()))?;
+            analyzer.analyze_source(&path.to_string_lossy(), &content)
+        }
+        None => analyzer.analyze(),
+    };
+    if settings.verbosity > Verbosity::Quiet {
+        for warning in &analysis.warnings {
+            eprintln!("{warning}");
         }
     }
-}
+    if settings.timings {
+        progress::print_timings(metadata_time, &analysis.timings);
+    }
+    let Analysis {
+        types,
+        mut findings,
+        parse_errors,
+        ..
+    } = analysis;
 
-// Exclude `examples` and `tests` directories
-fn should_include_dir(entry: &DirEntry) -> bool {
-    let path = entry.path();
-    let name = path
-        .file_name()
-        .and_then(|name| name.to_str())
-        .unwrap_or("");
-    !(name == "examples" || name == "tests")
-}
+    if let Command::List = command {
+        // `list` takes inventory of all reflected types instead of looking for mistakes
+        let reflect_types: Vec<_> = types
+            .iter()
+            .filter(|ty| ty.implements("Reflect"))
+            .map(|ty| ty.location.clone())
+            .collect();
+        output::print_types(&reflect_types, settings.format).map_err(output_error)?;
+        let failed = report_parse_errors(
+            &parse_errors,
+            settings.verbosity,
+            settings.deny_parse_errors,
+        );
+        return Ok(exit_code(failed));
+    }
+
+    if let Command::ValidateScene { scene } = &command {
+        // Types in files that couldn't be parsed would be reported as missing from the scene
+        if report_parse_errors(
+            &parse_errors,
+            settings.verbosity,
+            settings.deny_parse_errors,
+        ) {
+            return Ok(exit_code(true));
+        }
+        return validate_scene(scene, &types, settings.format);
+    }
 
-// Collect `.rs` files from dependencies
-fn collect_dependency_files(metadata: &Metadata, source_files: &mut Vec<String>) {
-    for package in &metadata.packages {
-        if package.name.starts_with("bevy_") {
-            if let Some(source) = package.manifest_path.parent() {
-                collect_source_files(source.as_str(), source_files);
+    findings.retain(|finding| is_selected(finding, &settings));
+
+    // Ignored findings are added to the baseline instead of replacing it with the current ones
+    #[cfg(feature = "tui")]
+    if let Command::Tui = command {
+        let baseline = match &settings.baseline {
+            Some(path) => Some((path.clone(), load_baseline(path)?.unwrap_or_default())),
+            None => None,
+        };
+        let triage = tui::Triage {
+            baseline,
+            config: config_path,
+        };
+        tui::run(triage, findings, || {
+            reanalyze(&analyzer, &settings, None).findings
+        })
+        .map_err(|err| Error::Io("run the terminal interface", err))?;
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    // Only report what's new compared to the baseline, or record a new one
+    if let Some(path) = &settings.baseline {
+        match load_baseline(path)? {
+            Some(baseline) if !settings.update_baseline => {
+                findings.retain(|finding| !baseline.contains(finding));
+            }
+            _ => {
+                let baseline = Baseline::from_findings(&findings);
+                baseline
+                    .save(path)
+                    .map_err(|err| Error::Io("write the baseline", err))?;
+                if settings.verbosity > Verbosity::Quiet {
+                    eprintln!(
+                        "Recorded {} findings in baseline {}",
+                        baseline.len(),
+                        path.display()
+                    );
+                }
+                findings.clear();
             }
         }
     }
-}
 
-// Parse the module hierarchy from `mod` declarations
-fn build_module_tree(path: &str, file: &File, module_tree: &mut HashMap<String, File>) {
-    module_tree.insert(path.to_string(), file.clone());
-}
+    // Sources of dependencies are off limits, only the workspace gets fixed
+    if settings.fix {
+        let fixable: Vec<_> = findings
+            .iter()
+            .filter(|finding| finding.crate_kind == CrateKind::Workspace)
+            .cloned()
+            .collect();
+        let fixes = fix::plan(&fixable);
+        let fixed_count = findings
+            .iter()
+            .filter(|finding| fixes.iter().any(|file_fix| file_fix.covers(finding)))
+            .count();
+        for file_fix in &fixes {
+            if settings.dry_run {
+                io::stdout()
+                    .write_all(file_fix.diff().as_bytes())
+                    .map_err(output_error)?;
+            } else {
+                file_fix
+                    .apply()
+                    .map_err(|err| Error::Io("write a fixed file", err))?;
+            }
+        }
+        if settings.verbosity > Verbosity::Quiet {
+            eprintln!(
+                "{} {fixed_count} findings in {} files",
+                if settings.dry_run {
+                    "Would fix"
+                } else {
+                    "Fixed"
+                },
+                fixes.len()
+            );
+            for file_fix in &fixes {
+                eprintln!("    {}", file_fix.path);
+            }
+        }
+        if settings.dry_run {
+            return Ok(ExitCode::SUCCESS);
+        }
+        findings.retain(|finding| !fixes.iter().any(|file_fix| file_fix.covers(finding)));
+    }
 
-// Resolve the fully qualified module path from a file's relative path
-fn resolve_module_path(path: &str, metadata: &Metadata) -> Option<String> {
-    let path = Path::new(path);
+    if let Some(dir) = &settings.issue_drafts {
+        let written = output::write_issue_drafts(&findings, dir)
+            .map_err(|err| Error::Io("write the issue drafts", err))?;
+        if settings.verbosity > Verbosity::Quiet {
+            eprintln!("Wrote {written} issue drafts to {}", dir.display());
+        }
+    }
 
-    if let Some(crate_name) = crate_root_for_file(path, metadata) {
-        let relative_path = path
-            .strip_prefix(crate_root_path(&crate_name, metadata)?)
-            .ok()?;
-        let module_path = relative_path_to_module_path(relative_path);
-        Some(format!("{}::{}", crate_name, module_path))
+    let result = match command {
+        _ if settings.registry_size => {
+            output::print_registry_size(&RegistrySize::new(&types), settings.format)
+        }
+        _ if settings.stats => output::print_stats(
+            &Stats::new(&types, &findings, WORST_MODULES),
+            settings.format,
+        ),
+        Command::Check | Command::Crate { .. } | Command::Report
+            if settings.format == OutputFormat::Dot =>
+        {
+            output::print_graph(&types, &findings)
+        }
+        Command::Check | Command::Crate { .. } | Command::Report
+            if settings.format == OutputFormat::Checkstyle =>
+        {
+            output::print_checkstyle(&findings, &workspace_root)
+        }
+        Command::Report => output::print_report(&findings, settings.format),
+        Command::Check
+        | Command::Crate { .. }
+        | Command::List
+        | Command::Diff { .. }
+        | Command::Watch
+        | Command::Lsp
+        | Command::Explain { .. }
+        | Command::ValidateScene { .. } => output::print_findings(&findings, settings.format),
+        #[cfg(feature = "tui")]
+        Command::Tui => output::print_findings(&findings, settings.format),
+    };
+    result.map_err(output_error)?;
+    let parse_failed = report_parse_errors(
+        &parse_errors,
+        settings.verbosity,
+        settings.deny_parse_errors,
+    );
+
+    let failed = settings
+        .fail_on
+        .threshold()
+        .is_some_and(|threshold| findings.iter().any(|finding| finding.severity >= threshold));
+    Ok(exit_code(failed || parse_failed))
+}
+
+// Status 1 for a failed check, 0 otherwise
+fn exit_code(failed: bool) -> ExitCode {
+    if failed {
+        ExitCode::from(EXIT_FINDINGS)
     } else {
-        let relative_path = path.strip_prefix("src").ok()?;
-        Some(relative_path_to_module_path(relative_path))
+        ExitCode::SUCCESS
     }
 }
 
-// Find the crate name for a given file
-fn crate_root_for_file(path: &Path, metadata: &Metadata) -> Option<String> {
-    for package in &metadata.packages {
-        let crate_root = Path::new(&package.manifest_path).parent()?;
-        if path.starts_with(crate_root) {
-            return Some(package.name.clone());
-        }
-    }
-    None
+// Whether a finding passes the `--only`, `--include` and `--exclude` filters
+fn is_selected(finding: &Finding, settings: &Settings) -> bool {
+    settings
+        .only
+        .is_none_or(|crate_kind| finding.crate_kind == crate_kind)
+        && (settings.include.is_empty() || settings.include.is_match(&finding.type_path))
+        && !settings.exclude.is_match(&finding.type_path)
 }
 
-// Get the root path of a crate
-fn crate_root_path(crate_name: &str, metadata: &Metadata) -> Option<Utf8PathBuf> {
-    metadata
-        .packages
-        .iter()
-        .find(|pkg| pkg.name == crate_name)
-        .and_then(|pkg| pkg.manifest_path.parent().map(|p| p.to_path_buf()))
+fn load_baseline(path: &Path) -> Result<Option<Baseline>, Error> {
+    Baseline::load(path).map_err(|err| Error::Read(path.to_path_buf(), err.to_string()))
+}
+
+// Run the analysis again, keeping the selected findings that aren't in the baseline
+fn reanalyze(analyzer: &Analyzer, settings: &Settings, baseline: Option<&Baseline>) -> Analysis {
+    keep_selected(analyzer.analyze(), settings, baseline)
 }
 
-// Convert a relative path to a Rust module path
-fn relative_path_to_module_path(path: &Path) -> String {
-    path.iter()
-        .filter_map(|comp| comp.to_str())
-        .map(|s| s.trim_end_matches(".rs"))
-        .filter(|s| *s != "mod")
-        .collect::<Vec<_>>()
-        .join("::")
+// Check a single file like `reanalyze`, with `content` standing for it
+fn reanalyze_source(
+    analyzer: &Analyzer,
+    settings: &Settings,
+    baseline: Option<&Baseline>,
+    path: &str,
+    content: &str,
+) -> Analysis {
+    keep_selected(analyzer.analyze_source(path, content), settings, baseline)
 }
 
-// Check if a struct or module has the `#[cfg(test)]` attribute
-fn has_cfg_test(attrs: &[Attribute]) -> bool {
-    attrs.iter().any(|attr| {
-        if let syn::Meta::List(meta_list) = &attr.meta {
-            return meta_list.path.is_ident("cfg") && meta_list.tokens.to_string().contains("test");
+fn keep_selected(
+    mut analysis: Analysis,
+    settings: &Settings,
+    baseline: Option<&Baseline>,
+) -> Analysis {
+    analysis.findings.retain(|finding| {
+        is_selected(finding, settings)
+            && !baseline.is_some_and(|baseline| baseline.contains(finding))
+    });
+    analysis
+}
+
+// Re-run the analysis whenever a source file changes and show the current findings. Unchanged
+// files come from the cache, so only the changed ones are parsed again.
+fn watch_workspace(
+    analyzer: &Analyzer,
+    settings: &Settings,
+    workspace_root: &Path,
+) -> Result<(), Error> {
+    let dirs = if settings.analysis.paths.is_empty() {
+        vec![workspace_root.to_path_buf()]
+    } else {
+        settings.analysis.paths.iter().map(PathBuf::from).collect()
+    };
+    // The baseline is only read, a watch never records one
+    let baseline = match &settings.baseline {
+        Some(path) => load_baseline(path)?,
+        None => None,
+    };
+    watch::watch(&dirs, || {
+        let analysis = reanalyze(analyzer, settings, baseline.as_ref());
+        if io::stdout().is_terminal() {
+            print!("\x1b[2J\x1b[H");
+        }
+        if settings.verbosity > Verbosity::Quiet {
+            for warning in &analysis.warnings {
+                eprintln!("{warning}");
+            }
+        }
+        let printed = output::print_findings(&analysis.findings, settings.format);
+        if let Err(err) = printed {
+            // Nothing to watch for if the findings can't be shown
+            let err = output_error(err);
+            err.report();
+            std::process::exit(EXIT_INTERNAL.into());
+        }
+        report_parse_errors(&analysis.parse_errors, settings.verbosity, false);
+        if settings.verbosity > Verbosity::Quiet {
+            eprintln!("Watching for changes, press Ctrl+C to stop");
         }
-        false
     })
 }
 
-/// Check if an item is public.
-fn is_public(item: &Item) -> bool {
-    match item {
-        Item::Struct(s) => matches!(s.vis, Visibility::Public(_)),
-        Item::Enum(e) => matches!(e.vis, Visibility::Public(_)),
-        Item::Mod(m) => matches!(m.vis, Visibility::Public(_)),
-        _ => false,
-    }
+// Print what keeps the entries of a scene file from loading, failing if anything does or the
+// file can't be parsed
+fn validate_scene(
+    path: &Path,
+    types: &[TypeInfo],
+    format: OutputFormat,
+) -> Result<ExitCode, Error> {
+    let file = path.to_string_lossy();
+    let content =
+        fs::read_to_string(path).map_err(|err| Error::Read(path.to_path_buf(), err.to_string()))?;
+    let entries = match scene::parse_scene(&file, &content) {
+        Ok(entries) => entries,
+        Err(err) => {
+            eprintln!(
+                "{}:{}:{}: failed to parse: {}",
+                err.file, err.span.line, err.span.column, err.message
+            );
+            return Ok(exit_code(true));
+        }
+    };
+    let severity = checks::UNLOADABLE_SCENE_TYPE.default_severity;
+    let findings = scene::check_scene(&file, &entries, types, severity);
+    output::print_findings(&findings, format).map_err(output_error)?;
+    Ok(exit_code(!findings.is_empty()))
 }
 
-/// Checks if a struct derives `Reflect` and `Component` but does not have `#[reflect(Component)]`.
-fn derives_reflect_and_component_but_no_reflect_component(attrs: &[Attribute]) -> bool {
-    let mut derives_reflect = false;
-    let mut derives_component = false;
-    let mut has_reflect_component_attr = false;
-
-    for attr in attrs {
-        match &attr.meta {
-            // Check for `#[derive(...)]`
-            Meta::List(meta_list) if meta_list.path.is_ident("derive") => {
-                meta_list
-                    .parse_nested_meta(|nested_meta| {
-                        if nested_meta.path.is_ident("Reflect") {
-                            derives_reflect = true;
-                        } else if nested_meta.path.is_ident("Component") {
-                            derives_component = true;
-                        }
-                        Ok(())
-                    })
-                    .ok();
-            }
-            Meta::List(meta_list) if meta_list.path.is_ident("cfg_attr") => {
-                let nested = meta_list
-                    .parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)
-                    .unwrap();
-                for meta in nested {
-                    if let Meta::List(meta_list) = meta {
-                        if meta_list.path.is_ident("derive") {
-                            meta_list
-                                .parse_nested_meta(|inner_nested_meta| {
-                                    if inner_nested_meta.path.is_ident("Reflect") {
-                                        derives_reflect = true;
-                                    } else if inner_nested_meta.path.is_ident("Component") {
-                                        derives_component = true;
-                                    }
-                                    Ok(())
-                                })
-                                .unwrap();
-                        } else if meta_list.path.is_ident("reflect") {
-                            // We don't handle where clauses like this:
-                            // #[cfg_attr(
-                            //     feature = "bevy_reflect",
-                            //     reflect(where T: TypePath),
-                            // )]
-                            // but I don't think that we need to.
-                            meta_list
-                                .parse_nested_meta(|inner_nested_meta| {
-                                    if inner_nested_meta.path.is_ident("Component") {
-                                        has_reflect_component_attr = true;
-                                    }
-                                    Ok(())
-                                })
-                                .ok();
-                        }
-                    }
-                }
-            }
-            // Check for `#[reflect(Component)]`
-            Meta::List(meta_list) if meta_list.path.is_ident("reflect") => {
-                meta_list
-                    .parse_nested_meta(|nested_meta| {
-                        if nested_meta.path.is_ident("Component") {
-                            has_reflect_component_attr = true;
-                        }
-                        Ok(())
-                    })
-                    .ok();
-            }
-            _ => {}
+// List the files that couldn't be parsed, returning whether that fails the run because it's
+// denied
+fn report_parse_errors(parse_errors: &[ParseError], verbosity: Verbosity, deny: bool) -> bool {
+    if verbosity > Verbosity::Quiet || deny {
+        for error in parse_errors {
+            eprintln!(
+                "{}:{}:{}: failed to parse, skipped: {}",
+                error.file, error.span.line, error.span.column, error.message
+            );
         }
     }
+    deny && !parse_errors.is_empty()
+}
 
-    derives_reflect && derives_component && !has_reflect_component_attr
+// `cargo metadata` for the selected manifest, with the features of the current project
+fn metadata_command(args: &ScanArgs, manifest_path: Option<&Path>) -> MetadataCommand {
+    let mut command = MetadataCommand::new();
+    if let Some(manifest_path) = manifest_path {
+        command.manifest_path(manifest_path);
+    }
+    // Features of dependencies are only used for evaluating `#[cfg(...)]`, cargo would reject
+    // the ones that aren't direct dependencies
+    let features: Vec<_> = args
+        .features
+        .iter()
+        .filter(|feature| !feature.contains('/'))
+        .cloned()
+        .collect();
+    if !features.is_empty() {
+        command.features(CargoOpt::SomeFeatures(features));
+    }
+    if args.all_features {
+        command.features(CargoOpt::AllFeatures);
+    }
+    if args.no_default_features {
+        command.features(CargoOpt::NoDefaultFeatures);
+    }
+    if args.offline {
+        command.other_options(vec!["--offline".to_string()]);
+    }
+    command
 }
 
-/// Recursively collect `#[derive(Reflect)]` types while respecting visibility.
-fn collect_reflect_types(
-    file: &File,
-    module_path: &str,
-    reflect_types: &mut Vec<String>,
-    public_only: bool,
-    parent_is_public: bool,
-) {
-    for item in &file.items {
-        let item_is_public = is_public(item) && parent_is_public;
-        match item {
-            Item::Struct(s) if derives_reflect_and_component_but_no_reflect_component(&s.attrs) => {
-                if public_only && !item_is_public {
-                    continue;
-                }
-                let full_path = format!("{}::{}", module_path, s.ident);
-                reflect_types.push(full_path);
-            }
-            Item::Enum(s) if derives_reflect_and_component_but_no_reflect_component(&s.attrs) => {
-                if public_only && !item_is_public {
-                    continue;
-                }
-                let full_path = format!("{}::{}", module_path, s.ident);
-                reflect_types.push(full_path);
-            }
-            Item::Mod(m) if !has_cfg_test(&m.attrs) => {
-                if public_only && !item_is_public {
-                    continue;
-                }
-                if let Some((_, items)) = &m.content {
-                    let nested_path = format!("{}::{}", module_path, m.ident);
-                    let nested_file = File {
-                        items: items.clone(),
-                        attrs: vec![],
-                        shebang: None,
-                    };
-                    collect_reflect_types(
-                        &nested_file,
-                        &nested_path,
-                        reflect_types,
-                        public_only,
-                        item_is_public,
-                    );
-                }
-            }
-            _ => {}
-        }
+// The package and workspace members of the manifest in `root`, found without cargo. Members are
+// matched against their globs, like `crates/*`.
+fn local_workspace_dirs(root: &Path) -> Vec<String> {
+    let Some(manifest) = fs::read_to_string(root.join("Cargo.toml"))
+        .ok()
+        .and_then(|content| content.parse::<toml::Table>().ok())
+    else {
+        return Vec::new();
+    };
+    let mut dirs = Vec::new();
+    if manifest.contains_key("package") {
+        dirs.push(root.to_path_buf());
+    }
+    let members = manifest
+        .get("workspace")
+        .and_then(|workspace| workspace.get("members"))
+        .and_then(|members| members.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|member| member.as_str());
+    for member in members {
+        let Ok(glob) = Glob::new(member) else {
+            continue;
+        };
+        let matcher = glob.compile_matcher();
+        let depth = Path::new(member).components().count();
+        dirs.extend(
+            WalkDir::new(root)
+                .min_depth(1)
+                .max_depth(depth)
+                .into_iter()
+                .filter_map(Result::ok)
+                .filter(|entry| entry.path().join("Cargo.toml").is_file())
+                .filter(|entry| {
+                    entry
+                        .path()
+                        .strip_prefix(root)
+                        .is_ok_and(|relative| matcher.is_match(relative))
+                })
+                .map(|entry| entry.into_path()),
+        );
     }
+    dirs.sort();
+    dirs.dedup();
+    dirs.into_iter()
+        .map(|dir| dir.to_string_lossy().into_owned())
+        .collect()
 }
@@ -0,0 +1,177 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// How far an item can be seen, ordered from least to most visible.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Visibility {
+    /// Private or `pub(self)`: only the module itself and its children.
+    Private,
+    /// `pub(super)` or `pub(in path)`: some of the surrounding modules.
+    Restricted,
+    /// `pub(crate)`, or private but in the crate root.
+    Crate,
+    /// `pub`, as long as all surrounding modules are too.
+    Public,
+}
+
+impl Visibility {
+    /// The visibility an item declares, without taking its surrounding modules into account.
+    pub fn of(vis: &syn::Visibility) -> Self {
+        match vis {
+            syn::Visibility::Public(_) => Self::Public,
+            syn::Visibility::Restricted(restricted) if restricted.path.is_ident("crate") => {
+                Self::Crate
+            }
+            syn::Visibility::Restricted(restricted) if restricted.path.is_ident("self") => {
+                Self::Private
+            }
+            syn::Visibility::Restricted(_) => Self::Restricted,
+            syn::Visibility::Inherited => Self::Private,
+        }
+    }
+
+    /// The effective visibility of an item declared with `self` inside a module that is
+    /// visible as far as `parent`. An item can't be seen further than its module.
+    pub fn within(self, parent: Self) -> Self {
+        self.min(parent)
+    }
+}
+
+/// A `mod foo;` declaration, which pulls in another file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModDecl {
+    /// Inline modules (`mod a { ... }`) the declaration is nested in.
+    pub inline_path: Vec<String>,
+    pub name: String,
+    /// Override from a `#[path = "..."]` attribute.
+    pub path_attr: Option<String>,
+    /// Effective visibility of the module, including the inline modules around it.
+    pub visibility: Visibility,
+    /// Whether all `#[cfg(...)]`s on the declaration hold.
+    pub enabled: bool,
+}
+
+/// Where a file ended up in the module tree.
+#[derive(Debug, Clone)]
+pub struct Placement {
+    pub module_path: String,
+    /// Effective visibility of the module the file makes up.
+    pub visibility: Visibility,
+}
+
+/// Maps files to their modules by following `mod` declarations from the crate roots.
+#[derive(Debug, Default)]
+pub struct ModuleTree {
+    placements: HashMap<PathBuf, Placement>,
+    /// Files and directories of modules that are configured out.
+    excluded: Vec<PathBuf>,
+}
+
+impl ModuleTree {
+    /// `roots` are the crate root files (like `src/lib.rs`) along with their crate names,
+    /// `mods` the module declarations of each file.
+    pub fn build(roots: &[(PathBuf, String)], mods: &BTreeMap<String, Vec<ModDecl>>) -> Self {
+        // Files are compared by their canonical path, since `#[path]` can contain `..`
+        let mods: HashMap<PathBuf, &Vec<ModDecl>> = mods
+            .iter()
+            .filter_map(|(path, decls)| Some((fs::canonicalize(path).ok()?, decls)))
+            .collect();
+
+        let mut tree = Self::default();
+        let mut visited = HashSet::new();
+        let mut pending: Vec<(PathBuf, Placement, bool)> = roots
+            .iter()
+            .filter_map(|(path, crate_name)| {
+                let placement = Placement {
+                    module_path: crate_name.clone(),
+                    visibility: Visibility::Public,
+                };
+                Some((fs::canonicalize(path).ok()?, placement, true))
+            })
+            .collect();
+
+        while let Some((file, placement, owns_directory)) = pending.pop() {
+            if !visited.insert(file.clone()) {
+                continue;
+            }
+            let parent = file.parent().unwrap_or(Path::new("")).to_path_buf();
+            // `foo.rs` keeps its submodules in `foo/`, `mod.rs` and crate roots next to them
+            let module_dir = if owns_directory {
+                parent.clone()
+            } else {
+                parent.join(file.file_stem().unwrap_or_default())
+            };
+
+            for decl in mods.get(&file).copied().into_iter().flatten() {
+                let base = decl
+                    .inline_path
+                    .iter()
+                    .fold(module_dir.clone(), |dir, module| dir.join(module));
+                let candidates = match &decl.path_attr {
+                    // Outside of inline modules, `#[path]` is relative to the declaring file
+                    Some(path) if decl.inline_path.is_empty() => vec![(parent.join(path), true)],
+                    Some(path) => vec![(base.join(path), true)],
+                    None => vec![
+                        (base.join(format!("{}.rs", decl.name)), false),
+                        (base.join(&decl.name).join("mod.rs"), true),
+                    ],
+                };
+
+                if !decl.enabled {
+                    tree.excluded
+                        .extend(candidates.into_iter().map(|(candidate, _)| candidate));
+                    tree.excluded.push(base.join(&decl.name));
+                    continue;
+                }
+
+                let Some((child, owns_directory)) = candidates
+                    .into_iter()
+                    .find_map(|(candidate, owns)| Some((fs::canonicalize(candidate).ok()?, owns)))
+                else {
+                    continue;
+                };
+                let mut module_path = placement.module_path.clone();
+                for segment in decl.inline_path.iter().chain([&decl.name]) {
+                    module_path.push_str("::");
+                    module_path.push_str(segment);
+                }
+                let visibility = decl.visibility.within(placement.visibility);
+                pending.push((
+                    child,
+                    Placement {
+                        module_path,
+                        visibility,
+                    },
+                    owns_directory,
+                ));
+            }
+
+            tree.placements.insert(file, placement);
+        }
+
+        tree.excluded = tree
+            .excluded
+            .iter()
+            .filter_map(|path| fs::canonicalize(path).ok())
+            .collect();
+        tree
+    }
+
+    /// Where `file` is in the module tree, if it's reachable from a crate root.
+    pub fn placement(&self, file: &str) -> Option<&Placement> {
+        self.placements.get(&fs::canonicalize(file).ok()?)
+    }
+
+    /// Whether `file` belongs to a module that is configured out.
+    pub fn is_excluded(&self, file: &str) -> bool {
+        let Ok(file) = fs::canonicalize(file) else {
+            return false;
+        };
+        self.excluded
+            .iter()
+            .any(|excluded| file.starts_with(excluded))
+    }
+}
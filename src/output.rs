@@ -0,0 +1,343 @@
+mod checkstyle;
+mod csv;
+mod dot;
+mod github;
+mod gitlab;
+mod html;
+mod human;
+mod issues;
+mod junit;
+mod markdown;
+mod sarif;
+
+use crate::diff::ReportDiff;
+use crate::finding::{CrateKind, Finding, Severity, TypeLocation};
+use crate::stats::{RegistrySize, Stats};
+use crate::types::TypeInfo;
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    /// Findings grouped by crate and module, with colored severities, the offending code and a
+    /// hint on how to fix each (like `text` for the other subcommands).
+    #[default]
+    Human,
+    /// One finding per line, as `file:line:column: type path` (just the type path for `list`),
+    /// for editors and scripts.
+    Text,
+    /// A JSON array of findings (or types, for `list`).
+    Json,
+    /// SARIF 2.1.0, e.g. for GitHub code scanning.
+    Sarif,
+    /// GitHub Actions workflow commands, which show the findings as annotations on the lines
+    /// of a pull request.
+    Github,
+    /// A GitLab Code Quality report, which merge requests show along with the changes.
+    Gitlab,
+    /// Checkstyle XML, e.g. for Jenkins' Warnings Next Generation plugin.
+    Checkstyle,
+    /// JUnit XML with a test suite per check and a failed test case per finding, for CI
+    /// systems that only understand test reports.
+    Junit,
+    /// A standalone HTML report with statistics and a sortable table per crate.
+    Html,
+    /// A summary table with collapsible details, for pull request comments.
+    Markdown,
+    /// Comma separated values with one finding per row, for spreadsheets.
+    Csv,
+    /// A Graphviz graph of the reflected types and the types of their fields, colored by
+    /// whether they're registered and reflected (`check` and `report` only).
+    Dot,
+}
+
+/// Print the findings of `check` in the requested format.
+pub fn print_findings(findings: &[Finding], format: OutputFormat) -> io::Result<()> {
+    match format {
+        OutputFormat::Text => {
+            let mut out = io::stdout().lock();
+            for finding in findings {
+                writeln!(out, "{}: {}", location(finding), finding.subject())?;
+            }
+            Ok(())
+        }
+        OutputFormat::Human => human::print_human(findings),
+        OutputFormat::Json => print_json(findings),
+        OutputFormat::Sarif => print_json(&sarif::sarif_log(findings)),
+        OutputFormat::Github => {
+            let mut out = io::stdout().lock();
+            out.write_all(github::workflow_commands(findings).as_bytes())
+        }
+        OutputFormat::Gitlab => print_json(&gitlab::code_quality_report(findings)),
+        OutputFormat::Checkstyle => print_checkstyle(findings, &std::env::current_dir()?),
+        OutputFormat::Junit => {
+            let mut out = io::stdout().lock();
+            out.write_all(junit::junit_report(findings).as_bytes())
+        }
+        OutputFormat::Html => {
+            let mut out = io::stdout().lock();
+            out.write_all(html::html_report(findings).as_bytes())
+        }
+        OutputFormat::Markdown => {
+            let mut out = io::stdout().lock();
+            out.write_all(markdown::markdown_summary(findings).as_bytes())
+        }
+        OutputFormat::Csv => {
+            let mut out = io::stdout().lock();
+            out.write_all(csv::csv_table(findings).as_bytes())
+        }
+        OutputFormat::Dot => Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "`--format dot` is only supported by `check` and `report`",
+        )),
+    }
+}
+
+/// Print the graph of reflected types and their field types for `--format dot`.
+pub fn print_graph(types: &[TypeInfo], findings: &[Finding]) -> io::Result<()> {
+    let mut out = io::stdout().lock();
+    out.write_all(dot::dot_graph(types, findings).as_bytes())
+}
+
+/// Print the findings as Checkstyle XML for `--format checkstyle`, with paths relative to
+/// `root`.
+pub fn print_checkstyle(findings: &[Finding], root: &Path) -> io::Result<()> {
+    let mut out = io::stdout().lock();
+    out.write_all(checkstyle::checkstyle_report(findings, root).as_bytes())
+}
+
+/// Write an issue draft in Markdown for each dependency package with findings into `dir`,
+/// returning how many were written.
+pub fn write_issue_drafts(findings: &[Finding], dir: &Path) -> io::Result<usize> {
+    let drafts = issues::issue_drafts(findings);
+    fs::create_dir_all(dir)?;
+    for (file_name, draft) in &drafts {
+        fs::write(dir.join(file_name), draft)?;
+    }
+    Ok(drafts.len())
+}
+
+/// Print the findings grouped by the crate they belong to.
+pub fn print_report(findings: &[Finding], format: OutputFormat) -> io::Result<()> {
+    if !matches!(format, OutputFormat::Human | OutputFormat::Text) {
+        return print_findings(findings, format);
+    }
+
+    let mut by_crate: BTreeMap<&str, Vec<&Finding>> = BTreeMap::new();
+    for finding in findings {
+        let crate_name = finding.type_path.split("::").next().unwrap_or_default();
+        by_crate.entry(crate_name).or_default().push(finding);
+    }
+
+    let mut out = io::stdout().lock();
+    for (crate_name, findings) in by_crate {
+        writeln!(out, "{crate_name} ({})", findings.len())?;
+        for finding in findings {
+            writeln!(out, "    {} ({})", finding.subject(), location(finding))?;
+        }
+    }
+    let in_workspace = findings
+        .iter()
+        .filter(|finding| finding.crate_kind == CrateKind::Workspace)
+        .count();
+    writeln!(
+        out,
+        "{} total ({in_workspace} in the workspace, {} in dependencies)",
+        findings.len(),
+        findings.len() - in_workspace
+    )
+}
+
+/// Print the findings that were added and removed between two runs.
+pub fn print_diff(diff: &ReportDiff, format: OutputFormat) -> io::Result<()> {
+    match format {
+        OutputFormat::Human | OutputFormat::Text => {
+            let mut out = io::stdout().lock();
+            for (sign, findings) in [("+", &diff.added), ("-", &diff.removed)] {
+                for finding in findings {
+                    writeln!(
+                        out,
+                        "{sign} {}:{}:{}: {} ({})",
+                        finding.file,
+                        finding.span.line,
+                        finding.span.column,
+                        finding.type_path,
+                        finding.check
+                    )?;
+                }
+            }
+            writeln!(
+                out,
+                "{} added, {} removed, {} unchanged",
+                diff.added.len(),
+                diff.removed.len(),
+                diff.unchanged.len()
+            )
+        }
+        OutputFormat::Json => print_json(diff),
+        _ => Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "`diff` only supports text and JSON output",
+        )),
+    }
+}
+
+/// Print reflection coverage per crate and the modules with the most findings.
+pub fn print_stats(stats: &Stats, format: OutputFormat) -> io::Result<()> {
+    match format {
+        OutputFormat::Human | OutputFormat::Text => {
+            let mut out = io::stdout().lock();
+            let width = stats
+                .crates
+                .iter()
+                .map(|crate_stats| crate_stats.crate_name.len())
+                .max()
+                .unwrap_or_default()
+                .max("crate".len());
+            writeln!(
+                out,
+                "{:width$}  {:>7}  {:>10}  {:>18}  {:>8}  {:>8}",
+                "crate", "reflect", "components", "reflect(Component)", "coverage", "findings"
+            )?;
+            for crate_stats in stats.crates.iter().chain([&stats.total]) {
+                let coverage = crate_stats
+                    .coverage
+                    .map_or_else(|| "-".to_string(), |coverage| format!("{coverage:.1}%"));
+                writeln!(
+                    out,
+                    "{:width$}  {:>7}  {:>10}  {:>18}  {:>8}  {:>8}",
+                    crate_stats.crate_name,
+                    crate_stats.reflect_types,
+                    crate_stats.components,
+                    crate_stats.reflected_components,
+                    coverage,
+                    crate_stats.findings
+                )?;
+            }
+            if !stats.worst_modules.is_empty() {
+                writeln!(out, "\nModules with the most findings:")?;
+                for module in &stats.worst_modules {
+                    writeln!(out, "    {} ({})", module.module_path, module.findings)?;
+                }
+            }
+            Ok(())
+        }
+        OutputFormat::Json => print_json(stats),
+        _ => Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "`--stats` only supports text and JSON output",
+        )),
+    }
+}
+
+/// Print the estimated type registry size per crate.
+pub fn print_registry_size(size: &RegistrySize, format: OutputFormat) -> io::Result<()> {
+    match format {
+        OutputFormat::Human | OutputFormat::Text => {
+            let mut out = io::stdout().lock();
+            let width = size
+                .crates
+                .iter()
+                .map(|crate_size| crate_size.crate_name.len())
+                .max()
+                .unwrap_or_default()
+                .max("crate".len());
+            writeln!(
+                out,
+                "{:width$}  {:>6}  {:>6}  {:>9}  {:>10}  {:>8}  {:>6}",
+                "crate", "types", "fields", "type data", "memory", "startup", "share"
+            )?;
+            for crate_size in size.crates.iter().chain([&size.total]) {
+                writeln!(
+                    out,
+                    "{:width$}  {:>6}  {:>6}  {:>9}  {:>10}  {:>8}  {:>6}",
+                    crate_size.crate_name,
+                    crate_size.types,
+                    crate_size.fields,
+                    crate_size.type_data,
+                    format!("{:.1} KiB", crate_size.bytes as f64 / 1024.0),
+                    format!("{:.2} ms", crate_size.startup_micros / 1000.0),
+                    format!("{:.1}%", crate_size.share)
+                )?;
+            }
+            Ok(())
+        }
+        OutputFormat::Json => print_json(size),
+        _ => Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "`--registry-size` only supports text and JSON output",
+        )),
+    }
+}
+
+/// Print the types found by `list`.
+pub fn print_types(types: &[TypeLocation], format: OutputFormat) -> io::Result<()> {
+    match format {
+        OutputFormat::Human | OutputFormat::Text => {
+            let mut out = io::stdout().lock();
+            for location in types {
+                writeln!(out, "{}", location.type_path)?;
+            }
+            Ok(())
+        }
+        OutputFormat::Json => print_json(types),
+        OutputFormat::Sarif
+        | OutputFormat::Github
+        | OutputFormat::Gitlab
+        | OutputFormat::Checkstyle
+        | OutputFormat::Junit
+        | OutputFormat::Html
+        | OutputFormat::Markdown
+        | OutputFormat::Csv
+        | OutputFormat::Dot => Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "`list` doesn't produce findings, use `--format json` instead",
+        )),
+    }
+}
+
+// The path of a file as CI systems expect it, relative to the repository root, which the project's
+// files are when run from there: without the leading `./` and with forward slashes. Files of
+// dependencies are left absolute.
+fn relative_path(file: &str) -> String {
+    let path = Path::new(file);
+    path.strip_prefix(".")
+        .unwrap_or(path)
+        .to_string_lossy()
+        .replace('\\', "/")
+}
+
+// `file:line:column`, which editors and terminals can jump to
+fn location(finding: &Finding) -> String {
+    format!(
+        "{}:{}:{}",
+        finding.file, finding.span.line, finding.span.column
+    )
+}
+
+fn severity_name(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Info => "info",
+        Severity::Warning => "warning",
+        Severity::Error => "error",
+    }
+}
+
+// Text escaped for HTML and XML, in content as well as in double-quoted attribute values
+fn escape_markup(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn print_json<T: Serialize + ?Sized>(value: &T) -> io::Result<()> {
+    let mut out = io::stdout().lock();
+    serde_json::to_writer_pretty(&mut out, value)?;
+    writeln!(out)
+}
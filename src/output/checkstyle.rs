@@ -0,0 +1,50 @@
+use super::{escape_markup, severity_name};
+use crate::finding::Finding;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+
+/// Render a Checkstyle XML report with a `file` element per file and an `error` per finding,
+/// with the check as its source, as read by Jenkins' Warnings Next Generation plugin. Paths are
+/// relative to `root` where possible, so that the plugin finds the files in the workspace.
+pub fn checkstyle_report(findings: &[Finding], root: &Path) -> String {
+    let current_dir = std::env::current_dir().unwrap_or_default();
+    let mut by_file: BTreeMap<String, Vec<&Finding>> = BTreeMap::new();
+    for finding in findings {
+        by_file
+            .entry(relative_path(&current_dir.join(&finding.file), root))
+            .or_default()
+            .push(finding);
+    }
+
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str("<checkstyle version=\"4.3\">\n");
+    for (file, findings) in by_file {
+        let _ = writeln!(xml, "  <file name=\"{}\">", escape_markup(&file));
+        for finding in findings {
+            let _ = writeln!(
+                xml,
+                "    <error line=\"{}\" column=\"{}\" severity=\"{}\" message=\"{}\" \
+                source=\"{}\"/>",
+                finding.span.line,
+                finding.span.column,
+                severity_name(finding.severity),
+                escape_markup(&finding.message),
+                finding.check
+            );
+        }
+        xml.push_str("  </file>\n");
+    }
+    xml.push_str("</checkstyle>\n");
+    xml
+}
+
+// Files outside of `root`, like those of dependencies, keep their absolute path. Collecting the
+// components drops the `.` of a joined `./src/lib.rs`.
+fn relative_path(path: &Path, root: &Path) -> String {
+    let path: PathBuf = path.components().collect();
+    path.strip_prefix(root)
+        .unwrap_or(&path)
+        .to_string_lossy()
+        .replace('\\', "/")
+}
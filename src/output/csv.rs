@@ -0,0 +1,45 @@
+use crate::finding::{CrateKind, Finding};
+
+const HEADER: &[&str] = &["crate", "module", "type", "kind", "rule", "file", "line"];
+
+/// Render one row per finding for spreadsheets, with a header row. The module is the full path
+/// of the module the type is in, the kind tells apart `workspace` and `dependency` crates.
+pub fn csv_table(findings: &[Finding]) -> String {
+    let mut csv = row(HEADER.iter().map(|column| column.to_string()));
+    for finding in findings {
+        let (module, name) = finding
+            .type_path
+            .rsplit_once("::")
+            .unwrap_or(("", &finding.type_path));
+        let crate_name = finding.type_path.split("::").next().unwrap_or_default();
+        let kind = match finding.crate_kind {
+            CrateKind::Workspace => "workspace",
+            CrateKind::Dependency => "dependency",
+        };
+        csv.push_str(&row([
+            crate_name.to_string(),
+            module.to_string(),
+            name.to_string(),
+            kind.to_string(),
+            finding.check.to_string(),
+            finding.file.clone(),
+            finding.span.line.to_string(),
+        ]));
+    }
+    csv
+}
+
+// A line of comma separated fields, terminated with CRLF as RFC 4180 asks for
+fn row(fields: impl IntoIterator<Item = String>) -> String {
+    let fields: Vec<String> = fields.into_iter().map(|field| escape(&field)).collect();
+    format!("{}\r\n", fields.join(","))
+}
+
+// Quote fields that contain separators, quotes or line breaks
+fn escape(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
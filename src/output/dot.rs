@@ -0,0 +1,127 @@
+use crate::finding::Finding;
+use crate::types::TypeInfo;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fmt::Write;
+
+const REGISTERED: &str = "#c8e6c9";
+const INCOMPLETE: &str = "#fff3cd";
+const NOT_REFLECTED: &str = "#f8d7da";
+
+/// Render the types that implement `Reflect` and the scanned types their fields refer to as a
+/// Graphviz graph, with a cluster per crate and an edge per field type. Green nodes are
+/// reflected, registered (directly or through a registered type that has them as a field) and
+/// without findings, yellow ones are reflected but unregistered or have findings, and red ones
+/// don't implement `Reflect`. Components are drawn as boxes.
+pub fn dot_graph(types: &[TypeInfo], findings: &[Finding]) -> String {
+    let mut by_name: HashMap<&str, Vec<&TypeInfo>> = HashMap::new();
+    for ty in types {
+        by_name.entry(&ty.ident).or_default().push(ty);
+    }
+
+    // Field types are matched by name, preferring types of the same crate
+    let mut edges: BTreeMap<&str, BTreeSet<&str>> = BTreeMap::new();
+    let mut nodes: BTreeMap<&str, &TypeInfo> = BTreeMap::new();
+    for ty in types.iter().filter(|ty| ty.implements("Reflect")) {
+        nodes.insert(&ty.location.type_path, ty);
+        let targets = edges.entry(&ty.location.type_path).or_default();
+        for field in ty.fields.iter().filter(|field| !field.ignored) {
+            for name in &field.type_names {
+                let candidates = by_name.get(name.as_str()).map_or(&[][..], Vec::as_slice);
+                let same_crate: Vec<&TypeInfo> = candidates
+                    .iter()
+                    .copied()
+                    .filter(|candidate| candidate.crate_name == ty.crate_name)
+                    .collect();
+                let candidates = if same_crate.is_empty() {
+                    candidates.to_vec()
+                } else {
+                    same_crate
+                };
+                for target in candidates {
+                    if target.location.type_path != ty.location.type_path {
+                        targets.insert(&target.location.type_path);
+                        nodes.insert(&target.location.type_path, target);
+                    }
+                }
+            }
+        }
+    }
+
+    // Registering a type registers the types of its fields along with it
+    let mut registered: HashSet<&str> = HashSet::new();
+    let mut pending: Vec<&str> = nodes
+        .values()
+        .filter(|ty| ty.registered && ty.implements("Reflect"))
+        .map(|ty| ty.location.type_path.as_str())
+        .collect();
+    while let Some(path) = pending.pop() {
+        if !registered.insert(path) {
+            continue;
+        }
+        pending.extend(
+            edges
+                .get(path)
+                .into_iter()
+                .flatten()
+                .filter(|target| nodes[*target].implements("Reflect")),
+        );
+    }
+    let flagged: HashSet<&str> = findings
+        .iter()
+        .map(|finding| finding.type_path.as_str())
+        .collect();
+
+    let mut by_crate: BTreeMap<&str, Vec<&TypeInfo>> = BTreeMap::new();
+    for (path, ty) in &nodes {
+        by_crate
+            .entry(path.split("::").next().unwrap_or_default())
+            .or_default()
+            .push(ty);
+    }
+
+    let mut dot = String::from("digraph reflect_types {\n");
+    dot.push_str("    rankdir=LR;\n");
+    dot.push_str("    node [style=filled, fontname=\"monospace\"];\n");
+    for (index, (crate_name, types)) in by_crate.iter().enumerate() {
+        let _ = writeln!(dot, "    subgraph cluster_{index} {{");
+        let _ = writeln!(dot, "        label={};", quote(crate_name));
+        for ty in types {
+            let path = ty.location.type_path.as_str();
+            let color = if !ty.implements("Reflect") {
+                NOT_REFLECTED
+            } else if registered.contains(path) && !flagged.contains(path) {
+                REGISTERED
+            } else {
+                INCOMPLETE
+            };
+            let shape = if ty.implements("Component") {
+                "box"
+            } else {
+                "ellipse"
+            };
+            let _ = writeln!(
+                dot,
+                "        {} [label={}, shape={shape}, fillcolor=\"{color}\"];",
+                quote(path),
+                quote(
+                    path.strip_prefix(*crate_name)
+                        .unwrap_or(path)
+                        .trim_start_matches("::")
+                )
+            );
+        }
+        dot.push_str("    }\n");
+    }
+    for (source, targets) in &edges {
+        for target in targets {
+            let _ = writeln!(dot, "    {} -> {};", quote(source), quote(target));
+        }
+    }
+    dot.push_str("}\n");
+    dot
+}
+
+// A quoted DOT identifier
+fn quote(id: &str) -> String {
+    format!("\"{}\"", id.replace('\\', "\\\\").replace('"', "\\\""))
+}
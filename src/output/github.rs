@@ -0,0 +1,61 @@
+use super::relative_path;
+use crate::finding::{Finding, Severity};
+use std::fmt::Write;
+use std::path::Path;
+
+/// Render a GitHub Actions workflow command per finding, like
+/// `::warning file=src/lib.rs,line=3,col=12,title=...::message`, which the runner turns into an
+/// annotation on the line in pull requests. Files outside the project can't be annotated, so
+/// findings in dependencies become annotations of the run with the location in the message.
+pub fn workflow_commands(findings: &[Finding]) -> String {
+    let mut out = String::new();
+    for finding in findings {
+        let title = escape_property(finding.check);
+        let path = Path::new(&finding.file);
+        let _ = if path.is_absolute() {
+            writeln!(
+                out,
+                "::{} title={title}::{}",
+                command(finding.severity),
+                escape_data(&format!(
+                    "{}:{}:{}: {}",
+                    finding.file, finding.span.line, finding.span.column, finding.message
+                ))
+            )
+        } else {
+            writeln!(
+                out,
+                "::{} file={},line={},endLine={},col={},endColumn={},title={title}::{}",
+                command(finding.severity),
+                escape_property(&relative_path(&finding.file)),
+                finding.span.line,
+                finding.span.end_line,
+                finding.span.column,
+                finding.span.end_column,
+                escape_data(&finding.message)
+            )
+        };
+    }
+    out
+}
+
+// GitHub has no `info` level, `notice` is the closest
+fn command(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Info => "notice",
+        Severity::Warning => "warning",
+        Severity::Error => "error",
+    }
+}
+
+// The message, with the characters that would end the command percent-encoded
+fn escape_data(data: &str) -> String {
+    data.replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+// A property value, which additionally can't contain the separators between properties
+fn escape_property(value: &str) -> String {
+    escape_data(value).replace(':', "%3A").replace(',', "%2C")
+}
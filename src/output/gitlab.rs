@@ -0,0 +1,46 @@
+use super::relative_path;
+use crate::finding::{Finding, Severity};
+use serde_json::{Value, json};
+
+/// Build a GitLab Code Quality report, which merge requests show next to the changed lines.
+/// Fingerprints are made of the check and type path like the baseline's entries, so that
+/// moving a type doesn't make GitLab report its findings as fixed and new.
+pub fn code_quality_report(findings: &[Finding]) -> Value {
+    findings.iter().map(issue).collect()
+}
+
+fn issue(finding: &Finding) -> Value {
+    let mut hasher = blake3::Hasher::new();
+    for part in [
+        finding.check,
+        &finding.type_path,
+        finding.variant.as_deref().unwrap_or_default(),
+    ] {
+        hasher.update(part.as_bytes());
+        hasher.update(&[0]);
+    }
+
+    json!({
+        "type": "issue",
+        "check_name": finding.check,
+        "description": finding.message,
+        "categories": ["Bug Risk"],
+        "fingerprint": hasher.finalize().to_hex().to_string(),
+        "severity": severity(finding.severity),
+        "location": {
+            "path": relative_path(&finding.file),
+            "lines": {
+                "begin": finding.span.line,
+                "end": finding.span.end_line,
+            }
+        }
+    })
+}
+
+fn severity(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Info => "info",
+        Severity::Warning => "minor",
+        Severity::Error => "major",
+    }
+}
@@ -0,0 +1,199 @@
+use super::{escape_markup, severity_name};
+use crate::finding::{CrateKind, Finding, Severity};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::path::Path;
+
+const STYLE: &str = "
+body { font-family: system-ui, sans-serif; margin: 2em; color: #222; }
+h1 { margin-bottom: 0.2em; }
+table { border-collapse: collapse; width: 100%; margin-bottom: 2em; }
+th, td { text-align: left; padding: 0.3em 0.6em; border-bottom: 1px solid #ddd; }
+th { cursor: pointer; user-select: none; background: #f4f4f4; }
+td code { font-size: 0.9em; }
+.summary td:first-child { width: 16em; }
+.error { color: #b00020; } .warning { color: #a05a00; } .info { color: #1565c0; }
+";
+
+// Sorts a table by the clicked column, toggling between ascending and descending
+const SCRIPT: &str = "
+document.querySelectorAll('table.findings th').forEach((th, column) => {
+  th.addEventListener('click', () => {
+    const body = th.closest('table').tBodies[0];
+    const ascending = th.dataset.order !== 'asc';
+    th.dataset.order = ascending ? 'asc' : 'desc';
+    const rows = Array.from(body.rows);
+    rows.sort((a, b) => a.cells[column].textContent.localeCompare(
+      b.cells[column].textContent, undefined, { numeric: true }));
+    if (!ascending) rows.reverse();
+    rows.forEach(row => body.appendChild(row));
+  });
+});
+";
+
+/// Render a standalone HTML page with summary statistics and a sortable table of findings per
+/// crate.
+pub fn html_report(findings: &[Finding]) -> String {
+    let mut by_crate: BTreeMap<&str, Vec<&Finding>> = BTreeMap::new();
+    for finding in findings {
+        let crate_name = finding.type_path.split("::").next().unwrap_or_default();
+        by_crate.entry(crate_name).or_default().push(finding);
+    }
+
+    let mut html = String::new();
+    html.push_str("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n");
+    html.push_str("<title>Reflection check report</title>\n");
+    let _ = writeln!(html, "<style>{STYLE}</style>\n</head>\n<body>");
+    html.push_str("<h1>Reflection check report</h1>\n");
+    let _ = writeln!(
+        html,
+        "<p>Generated by {} {}.</p>",
+        env!("CARGO_PKG_NAME"),
+        env!("CARGO_PKG_VERSION")
+    );
+
+    summary(&mut html, findings, &by_crate);
+
+    for (crate_name, findings) in &by_crate {
+        let _ = writeln!(
+            html,
+            "<h2 id=\"{0}\">{0} ({1})</h2>",
+            escape_markup(crate_name),
+            findings.len()
+        );
+        if let Some(upstream) = findings
+            .iter()
+            .find_map(|finding| finding.upstream.as_ref())
+        {
+            let package = format!(
+                "{} {} from {}",
+                escape_markup(&upstream.package),
+                escape_markup(&upstream.version),
+                escape_markup(&upstream.source)
+            );
+            let _ = match &upstream.repository {
+                Some(repository) => writeln!(
+                    html,
+                    "<p>{package}, <a href=\"{0}\">{0}</a></p>",
+                    escape_markup(repository)
+                ),
+                None => writeln!(html, "<p>{package}</p>"),
+            };
+        }
+        html.push_str("<table class=\"findings\">\n<thead><tr>");
+        html.push_str("<th>Severity</th><th>Check</th><th>Type</th><th>Message</th>");
+        html.push_str("<th>Location</th></tr></thead>\n<tbody>\n");
+        for finding in findings {
+            row(&mut html, finding);
+        }
+        html.push_str("</tbody>\n</table>\n");
+    }
+
+    let _ = writeln!(html, "<script>{SCRIPT}</script>\n</body>\n</html>");
+    html
+}
+
+// Totals by kind of crate, severity and check, followed by links to the crate sections
+fn summary(html: &mut String, findings: &[Finding], by_crate: &BTreeMap<&str, Vec<&Finding>>) {
+    let count = |predicate: &dyn Fn(&Finding) -> bool| {
+        findings.iter().filter(|finding| predicate(finding)).count()
+    };
+    let mut by_check: BTreeMap<&str, usize> = BTreeMap::new();
+    for finding in findings {
+        *by_check.entry(finding.check).or_default() += 1;
+    }
+
+    html.push_str("<h2>Summary</h2>\n<table class=\"summary\">\n<tbody>\n");
+    let mut rows = vec![
+        ("Findings".to_string(), findings.len()),
+        (
+            "In the workspace".to_string(),
+            count(&|finding| finding.crate_kind == CrateKind::Workspace),
+        ),
+        (
+            "In dependencies".to_string(),
+            count(&|finding| finding.crate_kind == CrateKind::Dependency),
+        ),
+    ];
+    for severity in [Severity::Error, Severity::Warning, Severity::Info] {
+        rows.push((
+            format!("Severity {}", severity_name(severity)),
+            count(&|finding| finding.severity == severity),
+        ));
+    }
+    rows.extend(
+        by_check
+            .into_iter()
+            .map(|(check, count)| (format!("Check <code>{check}</code>"), count)),
+    );
+    for (label, count) in rows {
+        let _ = writeln!(html, "<tr><td>{label}</td><td>{count}</td></tr>");
+    }
+    html.push_str("</tbody>\n</table>\n<p>Crates: ");
+    let links: Vec<String> = by_crate
+        .iter()
+        .map(|(crate_name, findings)| {
+            let crate_name = escape_markup(crate_name);
+            format!(
+                "<a href=\"#{crate_name}\">{crate_name}</a> ({})",
+                findings.len()
+            )
+        })
+        .collect();
+    html.push_str(&links.join(", "));
+    html.push_str("</p>\n");
+}
+
+fn row(html: &mut String, finding: &Finding) {
+    let severity = severity_name(finding.severity);
+    let type_path = escape_markup(&finding.type_path);
+    let type_cell = match docs_url(finding) {
+        Some(url) => format!(
+            "<a href=\"{}\"><code>{type_path}</code></a>",
+            escape_markup(&url)
+        ),
+        None => format!("<code>{type_path}</code>"),
+    };
+    let _ = writeln!(
+        html,
+        "<tr><td class=\"{severity}\">{severity}</td><td><code>{}</code></td><td>{type_cell}</td>\
+         <td>{}</td><td><code>{}:{}:{}</code></td></tr>",
+        finding.check,
+        escape_markup(&finding.message),
+        escape_markup(&finding.file),
+        finding.span.line,
+        finding.span.column,
+    );
+}
+
+// A docs.rs search for the type within the version of its crate that was scanned, for types in
+// dependencies from crates.io. Without cargo metadata, the version comes from the directory.
+fn docs_url(finding: &Finding) -> Option<String> {
+    if finding.crate_kind != CrateKind::Dependency {
+        return None;
+    }
+    let crate_name = finding.type_path.split("::").next()?;
+    let name = finding.type_path.rsplit("::").next()?;
+    if let Some(upstream) = &finding.upstream {
+        return (upstream.source == "crates.io").then(|| {
+            format!(
+                "https://docs.rs/{}/{}/{crate_name}/?search={name}",
+                upstream.package, upstream.version
+            )
+        });
+    }
+    // Registry sources live in directories named `package-version`
+    let version = Path::new(&finding.file).ancestors().find_map(|dir| {
+        let dir_name = dir.file_name()?.to_str()?;
+        // The version is everything after the first dash followed by a digit, which also
+        // covers pre-releases like `0.15.0-rc.1`
+        let (dash, _) = dir_name
+            .match_indices('-')
+            .find(|(index, _)| dir_name[index + 1..].starts_with(|c: char| c.is_ascii_digit()))?;
+        let (package, version) = (&dir_name[..dash], &dir_name[dash + 1..]);
+        (package.replace('-', "_") == crate_name).then(|| version.to_string())
+    })?;
+    Some(format!(
+        "https://docs.rs/{crate_name}/{version}/{crate_name}/?search={name}"
+    ))
+}
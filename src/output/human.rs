@@ -0,0 +1,174 @@
+use crate::checks;
+use crate::finding::{Finding, Severity};
+use anstyle::{AnsiColor, Style};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io::{self, Write};
+
+/// How many attribute lines above a type are shown, to include its derives.
+const MAX_ATTRIBUTE_LINES: usize = 6;
+
+const BOLD: Style = Style::new().bold();
+const DIM: Style = AnsiColor::BrightBlue.on_default().bold();
+
+/// Findings grouped by crate, then module, each with its severity, the offending code and a
+/// hint on how to fix it. Colors are left out when stdout isn't a terminal or `NO_COLOR` is set.
+pub(super) fn print_human(findings: &[Finding]) -> io::Result<()> {
+    let mut by_module: BTreeMap<&str, BTreeMap<&str, Vec<&Finding>>> = BTreeMap::new();
+    for finding in findings {
+        let (module, _) = finding
+            .type_path
+            .rsplit_once("::")
+            .unwrap_or((&finding.type_path, ""));
+        let crate_name = module.split("::").next().unwrap_or(module);
+        by_module
+            .entry(crate_name)
+            .or_default()
+            .entry(module)
+            .or_default()
+            .push(finding);
+    }
+
+    let mut out = anstream::stdout().lock();
+    let mut sources = Sources::default();
+    for (crate_name, modules) in by_module {
+        let count: usize = modules.values().map(Vec::len).sum();
+        writeln!(
+            out,
+            "{BOLD}{crate_name}{BOLD:#} ({})",
+            plural(count, "finding")
+        )?;
+        for (module, findings) in modules {
+            writeln!(out, "  {DIM}{module}{DIM:#}")?;
+            for finding in findings {
+                print_finding(&mut out, finding, &mut sources)?;
+            }
+        }
+        writeln!(out)?;
+    }
+
+    let count = |severity| {
+        findings
+            .iter()
+            .filter(|finding| finding.severity == severity)
+            .count()
+    };
+    writeln!(
+        out,
+        "{}, {}, {} info",
+        plural(count(Severity::Error), "error"),
+        plural(count(Severity::Warning), "warning"),
+        count(Severity::Info),
+    )
+}
+
+fn print_finding(out: &mut impl Write, finding: &Finding, sources: &mut Sources) -> io::Result<()> {
+    let style = severity_style(finding.severity);
+    let label = match finding.severity {
+        Severity::Error => "error",
+        Severity::Warning => "warning",
+        Severity::Info => "info",
+    };
+    writeln!(
+        out,
+        "    {style}{label}[{}]{style:#}{BOLD}: {}{BOLD:#}",
+        finding.check, finding.message
+    )?;
+    writeln!(
+        out,
+        "      {DIM}-->{DIM:#} {}:{}:{}",
+        finding.file, finding.span.line, finding.span.column
+    )?;
+
+    if let Some(upstream) = &finding.upstream {
+        write!(
+            out,
+            "      {DIM}={DIM:#} {BOLD}upstream{BOLD:#}: {} {} from {}",
+            upstream.package, upstream.version, upstream.source
+        )?;
+        match &upstream.repository {
+            Some(repository) => writeln!(out, ", {repository}")?,
+            None => writeln!(out)?,
+        }
+    }
+
+    if let Some(lines) = sources.lines(&finding.file) {
+        let frame = code_frame(lines, finding.span.line);
+        let width = finding.span.line.to_string().len();
+        writeln!(out, "      {:width$} {DIM}|{DIM:#}", "")?;
+        for (number, line) in frame {
+            writeln!(out, "      {DIM}{number:>width$} |{DIM:#} {line}")?;
+        }
+        // The type's name is underlined, if it's on a single line
+        if finding.span.end_line == finding.span.line
+            && finding.span.end_column > finding.span.column
+        {
+            let indent = " ".repeat(finding.span.column - 1);
+            let marker = "^".repeat(finding.span.end_column - finding.span.column);
+            writeln!(
+                out,
+                "      {:width$} {DIM}|{DIM:#} {indent}{style}{marker}{style:#}",
+                ""
+            )?;
+        }
+    }
+
+    if let Some(check) = checks::by_id(finding.check) {
+        writeln!(
+            out,
+            "      {DIM}={DIM:#} {BOLD}help{BOLD:#}: {}",
+            check.hint
+        )?;
+    }
+    Ok(())
+}
+
+// The line of a finding along with the attributes right above it, like its derives
+fn code_frame(lines: &[String], line: usize) -> Vec<(usize, &str)> {
+    let Some(index) = line.checked_sub(1).filter(|index| *index < lines.len()) else {
+        return Vec::new();
+    };
+    let mut start = index;
+    while start > 0
+        && index - start < MAX_ATTRIBUTE_LINES
+        && lines[start - 1].trim_start().starts_with("#[")
+    {
+        start -= 1;
+    }
+    (start..=index)
+        .map(|index| (index + 1, lines[index].as_str()))
+        .collect()
+}
+
+fn severity_style(severity: Severity) -> Style {
+    let color = match severity {
+        Severity::Error => AnsiColor::Red,
+        Severity::Warning => AnsiColor::Yellow,
+        Severity::Info => AnsiColor::Cyan,
+    };
+    color.on_default().bold()
+}
+
+fn plural(count: usize, noun: &str) -> String {
+    if count == 1 {
+        format!("{count} {noun}")
+    } else {
+        format!("{count} {noun}s")
+    }
+}
+
+/// Source files read so far, for showing code, `None` for files that can't be read.
+#[derive(Default)]
+struct Sources(HashMap<String, Option<Vec<String>>>);
+
+impl Sources {
+    fn lines(&mut self, file: &str) -> Option<&[String]> {
+        self.0
+            .entry(file.to_string())
+            .or_insert_with(|| {
+                let content = fs::read_to_string(file).ok()?;
+                Some(content.lines().map(str::to_string).collect())
+            })
+            .as_deref()
+    }
+}
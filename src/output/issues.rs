@@ -0,0 +1,134 @@
+use crate::checks;
+use crate::finding::{Finding, Upstream};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::path::Path;
+
+const REPOSITORY: &str = "https://github.com/anlumo/bevy-reflect-check";
+
+/// Render an issue for each dependency package with findings, keyed by a file name made of the
+/// package name and version: the findings grouped by check with how to fix them, and links to
+/// the code at the scanned version where the source allows it.
+pub fn issue_drafts(findings: &[Finding]) -> BTreeMap<String, String> {
+    let mut by_package: BTreeMap<(&str, &str), Vec<&Finding>> = BTreeMap::new();
+    for finding in findings {
+        if let Some(upstream) = &finding.upstream {
+            by_package
+                .entry((&upstream.package, &upstream.version))
+                .or_default()
+                .push(finding);
+        }
+    }
+
+    by_package
+        .into_iter()
+        .map(|((package, version), findings)| {
+            (
+                format!("{package}-{version}.md"),
+                issue(
+                    findings[0].upstream.as_ref().expect("grouped by upstream"),
+                    &findings,
+                ),
+            )
+        })
+        .collect()
+}
+
+fn issue(upstream: &Upstream, findings: &[&Finding]) -> String {
+    let mut by_check: BTreeMap<&str, Vec<&Finding>> = BTreeMap::new();
+    for finding in findings {
+        by_check.entry(finding.check).or_default().push(finding);
+    }
+
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        "# Incomplete reflection setup in `{}` ({})\n",
+        upstream.package,
+        plural(findings.len(), "finding")
+    );
+    let _ = writeln!(
+        out,
+        "Some reflected types of `{}` {} ({}) are missing parts of their reflection setup, \
+        which breaks them at runtime in scenes, the type registry or reflection-based tools like \
+        inspectors. Found with [{}]({REPOSITORY}) {}.\n",
+        upstream.package,
+        upstream.version,
+        upstream.source,
+        env!("CARGO_PKG_NAME"),
+        env!("CARGO_PKG_VERSION")
+    );
+    for (check_id, findings) in by_check {
+        // Custom rules have no documentation to link to
+        if checks::by_id(check_id).is_some() {
+            let _ = writeln!(
+                out,
+                "## [`{check_id}`]({REPOSITORY}/blob/main/docs/rules/{check_id}.md) ({})\n",
+                findings.len()
+            );
+        } else {
+            let _ = writeln!(out, "## `{check_id}` ({})\n", findings.len());
+        }
+        for finding in findings {
+            let location = relative_path(upstream, &finding.file)
+                .map(|path| {
+                    let text = format!("{path}:{}", finding.span.line);
+                    match source_url(upstream, &path, finding.span.line) {
+                        Some(url) => format!("[{text}]({url})"),
+                        None => format!("`{text}`"),
+                    }
+                })
+                .unwrap_or_else(|| format!("`{}:{}`", finding.file, finding.span.line));
+            let _ = writeln!(
+                out,
+                "- `{}` ({location}): {}",
+                finding.subject(),
+                finding.message.replace('\n', " ")
+            );
+        }
+        out.push('\n');
+    }
+    out
+}
+
+// The path of `file` within the package directory for registry sources, which cargo unpacks to
+// a directory named `package-version`, or within the repository for git checkouts, which are in
+// `checkouts/<repository>/<commit>`
+fn relative_path(upstream: &Upstream, file: &str) -> Option<String> {
+    let dir_name = format!("{}-{}", upstream.package, upstream.version);
+    let file = Path::new(file);
+    let dir = file.ancestors().find(|dir| {
+        if upstream.source.starts_with("git+") {
+            dir.parent()
+                .and_then(Path::parent)
+                .and_then(Path::file_name)
+                .is_some_and(|name| name == "checkouts")
+        } else {
+            dir.file_name().is_some_and(|name| *name == *dir_name)
+        }
+    })?;
+    let path = file.strip_prefix(dir).ok()?;
+    Some(path.to_string_lossy().replace('\\', "/"))
+}
+
+// A link to the line on docs.rs for packages from crates.io, or in the repository at the pinned
+// commit for git sources like `git+https://github.com/owner/repo?branch=main#<commit>`
+fn source_url(upstream: &Upstream, path: &str, line: usize) -> Option<String> {
+    if upstream.source == "crates.io" {
+        return Some(format!(
+            "https://docs.rs/crate/{}/{}/source/{path}#{line}",
+            upstream.package, upstream.version
+        ));
+    }
+    let (url, commit) = upstream.source.strip_prefix("git+")?.split_once('#')?;
+    let repository = url.split('?').next()?.trim_end_matches(".git");
+    Some(format!("{repository}/blob/{commit}/{path}#L{line}"))
+}
+
+fn plural(count: usize, noun: &str) -> String {
+    if count == 1 {
+        format!("{count} {noun}")
+    } else {
+        format!("{count} {noun}s")
+    }
+}
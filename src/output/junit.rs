@@ -0,0 +1,48 @@
+use super::{escape_markup, location, relative_path, severity_name};
+use crate::finding::Finding;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+/// Render a JUnit XML report with a test suite per check and a failed test case per finding,
+/// named after the type, for CI systems that only understand test reports.
+pub fn junit_report(findings: &[Finding]) -> String {
+    let mut by_check: BTreeMap<&str, Vec<&Finding>> = BTreeMap::new();
+    for finding in findings {
+        by_check.entry(finding.check).or_default().push(finding);
+    }
+
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    let _ = writeln!(
+        xml,
+        "<testsuites name=\"{}\" tests=\"{count}\" failures=\"{count}\">",
+        env!("CARGO_PKG_NAME"),
+        count = findings.len()
+    );
+    for (check, findings) in by_check {
+        let _ = writeln!(
+            xml,
+            "  <testsuite name=\"{check}\" tests=\"{count}\" failures=\"{count}\">",
+            count = findings.len()
+        );
+        for finding in findings {
+            let _ = writeln!(
+                xml,
+                "    <testcase name=\"{}\" classname=\"{check}\" file=\"{}\" line=\"{}\">",
+                escape_markup(&finding.subject()),
+                escape_markup(&relative_path(&finding.file)),
+                finding.span.line
+            );
+            let _ = writeln!(
+                xml,
+                "      <failure message=\"{}\" type=\"{}\">{}</failure>",
+                escape_markup(&finding.message),
+                severity_name(finding.severity),
+                escape_markup(&location(finding))
+            );
+            xml.push_str("    </testcase>\n");
+        }
+        xml.push_str("  </testsuite>\n");
+    }
+    xml.push_str("</testsuites>\n");
+    xml
+}
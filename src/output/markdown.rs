@@ -0,0 +1,115 @@
+use super::severity_name;
+use crate::checks;
+use crate::finding::{CrateKind, Finding};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+
+/// How many crates are named as worst offenders per check.
+const WORST_OFFENDERS: usize = 3;
+
+/// Render a compact summary for pull request comments: one table row per check with the crates
+/// that have the most findings, and the findings themselves in a collapsed `<details>` block.
+pub fn markdown_summary(findings: &[Finding]) -> String {
+    let mut out = String::from("## Reflection check\n\n");
+    if findings.is_empty() {
+        out.push_str("No findings.\n");
+        return out;
+    }
+
+    let in_workspace = findings
+        .iter()
+        .filter(|finding| finding.crate_kind == CrateKind::Workspace)
+        .count();
+    let _ = writeln!(
+        out,
+        "**{}** findings ({in_workspace} in the workspace, {} in dependencies)\n",
+        findings.len(),
+        findings.len() - in_workspace
+    );
+
+    out.push_str("| Rule | Severity | Count | Worst offenders |\n");
+    out.push_str("| --- | --- | ---: | --- |\n");
+    // Built-in checks in their usual order, then custom rules
+    let mut check_ids: Vec<&str> = checks::ids().collect();
+    let custom_ids: BTreeSet<&str> = findings
+        .iter()
+        .map(|finding| finding.check)
+        .filter(|id| checks::by_id(id).is_none())
+        .collect();
+    check_ids.extend(custom_ids);
+    for check_id in check_ids {
+        let of_check: Vec<&Finding> = findings
+            .iter()
+            .filter(|finding| finding.check == check_id)
+            .collect();
+        let Some(worst) = of_check.iter().map(|finding| finding.severity).max() else {
+            continue;
+        };
+        let mut per_crate: BTreeMap<&str, usize> = BTreeMap::new();
+        for finding in &of_check {
+            *per_crate.entry(crate_name(finding)).or_default() += 1;
+        }
+        let mut per_crate: Vec<_> = per_crate.into_iter().collect();
+        per_crate.sort_by(|(a_name, a), (b_name, b)| b.cmp(a).then(a_name.cmp(b_name)));
+        let offenders: Vec<String> = per_crate
+            .iter()
+            .take(WORST_OFFENDERS)
+            .map(|(name, count)| format!("`{name}` ({count})"))
+            .collect();
+        let _ = writeln!(
+            out,
+            "| `{}` | {} | {} | {} |",
+            check_id,
+            severity_name(worst),
+            of_check.len(),
+            offenders.join(", ")
+        );
+    }
+
+    let _ = writeln!(
+        out,
+        "\n<details>\n<summary>All {} findings</summary>\n",
+        findings.len()
+    );
+    let mut by_crate: BTreeMap<&str, Vec<&Finding>> = BTreeMap::new();
+    for finding in findings {
+        by_crate
+            .entry(crate_name(finding))
+            .or_default()
+            .push(finding);
+    }
+    for (crate_name, findings) in by_crate {
+        match findings
+            .iter()
+            .find_map(|finding| finding.upstream.as_ref())
+        {
+            Some(upstream) => {
+                let version = match &upstream.repository {
+                    Some(repository) => format!("[{}]({repository})", upstream.version),
+                    None => upstream.version.clone(),
+                };
+                let _ = writeln!(out, "#### `{crate_name}` {version}\n");
+            }
+            None => {
+                let _ = writeln!(out, "#### `{crate_name}`\n");
+            }
+        }
+        for finding in findings {
+            let _ = writeln!(
+                out,
+                "- `{}`: {} (`{}:{}`)",
+                finding.check,
+                finding.message.replace('\n', " "),
+                finding.file,
+                finding.span.line
+            );
+        }
+        out.push('\n');
+    }
+    out.push_str("</details>\n");
+    out
+}
+
+fn crate_name(finding: &Finding) -> &str {
+    finding.type_path.split("::").next().unwrap_or_default()
+}
@@ -0,0 +1,93 @@
+use crate::checks::{self, Check};
+use crate::finding::{Finding, Severity};
+use serde_json::{Value, json};
+use std::path::Path;
+
+const SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+/// Build a SARIF 2.1.0 log with a single run containing all findings.
+pub fn sarif_log(findings: &[Finding]) -> Value {
+    let rules: Vec<Value> = checks::ALL.iter().map(|check| rule(check)).collect();
+    let results: Vec<Value> = findings.iter().map(result).collect();
+
+    json!({
+        "$schema": SCHEMA,
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": env!("CARGO_PKG_NAME"),
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "informationUri": "https://github.com/anlumo/bevy-reflect-check",
+                    "rules": rules,
+                }
+            },
+            "columnKind": "unicodeCodePoints",
+            "results": results,
+        }]
+    })
+}
+
+fn rule(check: &Check) -> Value {
+    json!({
+        "id": check.id,
+        "name": check.name,
+        "shortDescription": { "text": format!("Type {}", check.problem) },
+        "fullDescription": { "text": check.description },
+        "help": { "text": check.description },
+        "defaultConfiguration": { "level": level(check.default_severity) },
+    })
+}
+
+fn result(finding: &Finding) -> Value {
+    let rule_index = checks::ALL
+        .iter()
+        .position(|check| check.id == finding.check);
+    let span = &finding.span;
+
+    json!({
+        "ruleId": finding.check,
+        "ruleIndex": rule_index,
+        "level": level(finding.severity),
+        "message": { "text": finding.message },
+        "locations": [{
+            "physicalLocation": {
+                "artifactLocation": { "uri": artifact_uri(&finding.file) },
+                "region": {
+                    "startLine": span.line,
+                    "startColumn": span.column,
+                    "endLine": span.end_line,
+                    "endColumn": span.end_column,
+                    "byteOffset": span.start_byte,
+                    "byteLength": span.end_byte - span.start_byte,
+                }
+            },
+            "logicalLocations": [{
+                "fullyQualifiedName": finding.type_path,
+                "kind": "type",
+            }]
+        }]
+    })
+}
+
+fn level(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Info => "note",
+        Severity::Warning => "warning",
+        Severity::Error => "error",
+    }
+}
+
+// Project files are reported relative to the current directory (which is what code scanning
+// expects), everything else as an absolute `file://` URI.
+fn artifact_uri(file: &str) -> String {
+    let path = Path::new(file);
+    if path.is_absolute() {
+        format!("file://{}", path.display())
+    } else {
+        path.strip_prefix(".")
+            .unwrap_or(path)
+            .to_string_lossy()
+            .replace('\\', "/")
+    }
+}
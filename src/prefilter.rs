@@ -0,0 +1,125 @@
+use memchr::memmem::Finder;
+
+/// Words at least one of which a file needs to contain for its syntax tree to matter: the traits
+/// and registrations the checks look at.
+const WORDS: &[&str] = &[
+    "Reflect",
+    "reflect",
+    "Component",
+    "Resource",
+    "Bundle",
+    "States",
+    "Asset",
+    "TypePath",
+    "register_type",
+];
+
+/// A byte search that tells files which can't contain anything the analysis records from the
+/// rest, so that they can be skipped without parsing them.
+pub struct Prefilter {
+    finders: Vec<Finder<'static>>,
+}
+
+impl Prefilter {
+    /// `derive_aliases` are the names of derive macros standing for other traits, which count as
+    /// relevant as well.
+    pub fn new<'a>(derive_aliases: impl IntoIterator<Item = &'a str>) -> Self {
+        let finders = WORDS
+            .iter()
+            .copied()
+            .chain(derive_aliases)
+            .map(|word| Finder::new(word).into_owned())
+            .collect();
+        Self { finders }
+    }
+
+    /// Whether `content` contains any of the words, and so has to be parsed.
+    pub fn is_relevant(&self, content: &str) -> bool {
+        self.finders
+            .iter()
+            .any(|finder| finder.find(content.as_bytes()).is_some())
+    }
+}
+
+/// The parts of a skipped file that still decide how the types of other files are placed and
+/// judged: the top-level `mod` and `use` declarations and the headers of `impl` blocks, along
+/// with their attributes. They're found by looking at the start of each line, which assumes
+/// formatted code, and returned as source that parses on its own.
+pub fn outline(content: &str) -> String {
+    let mut outline = String::new();
+    // Where the attributes right above the current line start
+    let mut attrs_start = None;
+    let mut pos = 0;
+    while pos < content.len() {
+        let rest = &content[pos..];
+        let mut end = rest.find('\n').map_or(content.len(), |end| pos + end + 1);
+        if rest.starts_with("#[") {
+            // Attributes can span lines, and be followed by their item on the same line
+            end = closing_bracket(rest).map_or(content.len(), |close| pos + close + 1);
+            end = content.len() - content[end..].trim_start().len();
+            attrs_start.get_or_insert(pos);
+        } else if let Some(terminators) = item_terminators(rest) {
+            let Some(last) = rest.find(terminators) else {
+                break;
+            };
+            outline.push_str(&content[attrs_start.unwrap_or(pos)..=pos + last]);
+            // Only the header of a block is kept
+            if rest[last..].starts_with('{') {
+                outline.push('}');
+            }
+            outline.push('\n');
+            end = rest[last..]
+                .find('\n')
+                .map_or(content.len(), |end| pos + last + end + 1);
+            attrs_start = None;
+        } else if !rest.starts_with("//") {
+            attrs_start = None;
+        }
+        pos = end;
+    }
+    outline
+}
+
+// The characters that end the declaration at the start of `line` if it's one the outline keeps
+fn item_terminators(line: &str) -> Option<&'static [char]> {
+    let mut item = line;
+    if let Some(rest) = item.strip_prefix("pub") {
+        item = match rest.strip_prefix('(') {
+            Some(restricted) => &restricted[restricted.find(')')? + 1..],
+            None => rest,
+        }
+        .trim_start();
+    }
+    let item = item.strip_prefix("unsafe ").unwrap_or(item);
+    if item.starts_with("use ") {
+        Some(&[';'])
+    } else if item.starts_with("mod ") || item.starts_with("impl ") || item.starts_with("impl<") {
+        Some(&[';', '{'])
+    } else {
+        None
+    }
+}
+
+// The offset of the `]` that closes the attribute `source` starts with
+fn closing_bracket(source: &str) -> Option<usize> {
+    let mut depth = 0;
+    let mut in_string = false;
+    let mut escaped = false;
+    for (offset, c) in source.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if in_string => escaped = true,
+            '"' => in_string = !in_string,
+            _ if in_string => {}
+            '[' => depth += 1,
+            ']' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(offset);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
@@ -0,0 +1,139 @@
+use bevy_reflect_check::{Progress, Timings};
+use indicatif::{MultiProgress, ProgressBar, ProgressDrawTarget, ProgressStyle};
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// How often the bars are redrawn at most, so that fast runs from the cache don't flood the
+/// terminal.
+const REDRAW_RATE: u8 = 10;
+
+/// How much the tool tells about what it's doing on stderr.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Verbosity {
+    /// Nothing but the results and errors.
+    Quiet,
+    /// A progress bar while scanning (if stderr is a terminal) and warnings.
+    #[default]
+    Normal,
+    /// Additionally, a line for each crate that was parsed and a summary at the end.
+    Verbose,
+}
+
+/// Shows the progress of an analysis on stderr, with a bar for the parsed files and one for the
+/// crates. `indicatif` leaves the bars out if stderr isn't a terminal.
+pub struct ProgressReporter {
+    verbosity: Verbosity,
+    bars: MultiProgress,
+    /// The bars of the files and crates of the current analysis, once it started.
+    current: Mutex<Option<(ProgressBar, ProgressBar)>>,
+}
+
+impl ProgressReporter {
+    pub fn new(verbosity: Verbosity) -> Self {
+        Self {
+            verbosity,
+            bars: MultiProgress::with_draw_target(ProgressDrawTarget::stderr_with_hz(REDRAW_RATE)),
+            current: Mutex::new(None),
+        }
+    }
+
+    /// Update the bars with a step of the analysis.
+    pub fn report(&self, progress: Progress<'_>) {
+        let mut current = self.current.lock().unwrap_or_else(|err| err.into_inner());
+        match progress {
+            Progress::Started { files, crates } => {
+                self.verbose(|| eprintln!("Scanning {files} files in {crates} crates"));
+                // `watch` and the language server run one analysis after another
+                if let Some((files, crates)) = current.take() {
+                    self.remove(&files, &crates);
+                }
+                *current = Some((
+                    self.bars.add(bar(files, "files")),
+                    self.bars.add(bar(crates, "crates")),
+                ));
+            }
+            Progress::FileParsed { .. } => {
+                if let Some((files, _)) = &*current {
+                    files.inc(1);
+                }
+            }
+            Progress::CrateParsed { name, files } => {
+                self.verbose(|| eprintln!("Parsed {name} ({files} files)"));
+                if let Some((_, crates)) = &*current {
+                    crates.inc(1);
+                }
+            }
+            Progress::Finished { findings } => {
+                let Some((files, crates)) = current.take() else {
+                    return;
+                };
+                self.remove(&files, &crates);
+                if self.verbosity == Verbosity::Verbose {
+                    eprintln!(
+                        "Found {findings} findings in {:.2}s",
+                        files.elapsed().as_secs_f64()
+                    );
+                }
+            }
+        }
+    }
+
+    // Print a line above the bars in verbose mode
+    fn verbose(&self, print: impl FnOnce()) {
+        if self.verbosity == Verbosity::Verbose {
+            self.bars.suspend(print);
+        }
+    }
+
+    // Take the bars off the terminal, so that other output starts on a clean line
+    fn remove(&self, files: &ProgressBar, crates: &ProgressBar) {
+        for bar in [files, crates] {
+            bar.finish_and_clear();
+            self.bars.remove(bar);
+        }
+    }
+}
+
+fn bar(len: usize, unit: &str) -> ProgressBar {
+    let style = ProgressStyle::with_template(&format!("[{{bar:30}}] {{pos}}/{{len}} {unit}"))
+        .expect("the template is valid")
+        .progress_chars("== ");
+    ProgressBar::new(len as u64).with_style(style)
+}
+
+/// Print how long fetching `metadata` and the stages of the analysis took, followed by the rules
+/// from the slowest to the fastest, for `--timings`.
+pub fn print_timings(metadata: Duration, timings: &Timings) {
+    let stages: Vec<(&str, Duration)> = [("metadata", metadata)]
+        .into_iter()
+        .chain(timings.stages.iter().copied())
+        .collect();
+    let mut rules = timings.rules.clone();
+    rules.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+    let width = stages
+        .iter()
+        .chain(&rules)
+        .map(|(name, _)| name.len())
+        .max()
+        .unwrap_or_default();
+
+    eprintln!(
+        "Timings ({} files in {} crates):",
+        timings.files, timings.crates
+    );
+    for (name, duration) in &stages {
+        eprintln!("    {name:width$}  {:>10.1} ms", millis(*duration));
+    }
+    let total: Duration = stages.iter().map(|(_, duration)| *duration).sum();
+    eprintln!("    {:width$}  {:>10.1} ms", "total", millis(total));
+    if !rules.is_empty() {
+        eprintln!("Rules:");
+        for (id, duration) in &rules {
+            eprintln!("    {id:width$}  {:>10.3} ms", millis(*duration));
+        }
+    }
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
@@ -0,0 +1,300 @@
+//! Rules that look at each type and report findings.
+//!
+//! The built-in checks in [`checks`](crate::checks) are rules, and further ones can be added
+//! to an [`Analyzer`](crate::Analyzer) with
+//! [`register_rule`](crate::Analyzer::register_rule), e.g. for conventions of a project:
+//!
+//! ```
+//! use bevy_reflect_check::rules::{ItemCtx, Rule};
+//! use bevy_reflect_check::{Finding, Severity};
+//!
+//! struct NoUnitComponents;
+//!
+//! impl Rule for NoUnitComponents {
+//!     fn id(&self) -> &'static str {
+//!         "no-unit-components"
+//!     }
+//!
+//!     fn name(&self) -> &'static str {
+//!         "NoUnitComponents"
+//!     }
+//!
+//!     fn description(&self) -> &'static str {
+//!         "Marker components should be named `*Marker`."
+//!     }
+//!
+//!     fn default_severity(&self) -> Severity {
+//!         Severity::Info
+//!     }
+//!
+//!     fn check_item(&self, ctx: &ItemCtx<'_>) -> Vec<Finding> {
+//!         let ty = ctx.ty;
+//!         if !ty.implements("Component") || !ty.fields.is_empty() || ty.ident.ends_with("Marker") {
+//!             return Vec::new();
+//!         }
+//!         let message = format!("`{}` is a marker without `Marker` in its name", ty.ident);
+//!         let severity = self.default_severity();
+//!         vec![Finding::at_type(self.id(), severity, message, ty, ctx.crate_kind)]
+//!     }
+//! }
+//! ```
+
+use crate::checks::{self, Check};
+use crate::finding::{CrateKind, Finding, Severity};
+use crate::types::TypeInfo;
+use cargo_metadata::semver::Version;
+use std::fmt;
+
+/// What a rule gets to see when checking a type.
+#[derive(Debug, Clone, Copy)]
+pub struct ItemCtx<'a> {
+    /// The type to check.
+    pub ty: &'a TypeInfo,
+    /// Whether the type is in the workspace or a dependency.
+    pub crate_kind: CrateKind,
+    /// All types of the analysis, for rules that look at related types.
+    pub types: &'a [TypeInfo],
+}
+
+/// A check that reports problems with types.
+pub trait Rule: Send + Sync {
+    /// Stable identifier, used on the command line, in the configuration and in reports.
+    fn id(&self) -> &'static str;
+
+    /// Human readable name.
+    fn name(&self) -> &'static str;
+
+    /// What the rule looks for and why it matters.
+    fn description(&self) -> &'static str;
+
+    fn default_severity(&self) -> Severity;
+
+    /// Whether the rule runs without being requested.
+    fn enabled_by_default(&self) -> bool {
+        true
+    }
+
+    /// The `bevy_reflect` version (major, minor) the rule needs, if it doesn't apply to all.
+    fn min_bevy_version(&self) -> Option<(u64, u64)> {
+        None
+    }
+
+    /// The `bevy_reflect` version (major, minor) from which on the rule no longer applies, if
+    /// it's obsolete in newer versions.
+    fn max_bevy_version(&self) -> Option<(u64, u64)> {
+        None
+    }
+
+    /// The findings for one type, with the rule's id and default severity. Severities
+    /// configured by the user are applied afterwards.
+    fn check_item(&self, ctx: &ItemCtx<'_>) -> Vec<Finding>;
+}
+
+impl Rule for Check {
+    fn id(&self) -> &'static str {
+        self.id
+    }
+
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn description(&self) -> &'static str {
+        self.description
+    }
+
+    fn default_severity(&self) -> Severity {
+        self.default_severity
+    }
+
+    fn enabled_by_default(&self) -> bool {
+        self.enabled_by_default
+    }
+
+    fn min_bevy_version(&self) -> Option<(u64, u64)> {
+        self.since
+    }
+
+    fn max_bevy_version(&self) -> Option<(u64, u64)> {
+        self.until
+    }
+
+    fn check_item(&self, ctx: &ItemCtx<'_>) -> Vec<Finding> {
+        if (self.applies)(ctx.ty) {
+            Finding::all(self, ctx.ty, ctx.crate_kind)
+        } else {
+            Vec::new()
+        }
+    }
+}
+
+/// The rules an analysis can run, the built-in checks by default.
+pub struct RuleRegistry {
+    rules: Vec<Box<dyn Rule>>,
+}
+
+impl Default for RuleRegistry {
+    fn default() -> Self {
+        Self {
+            rules: checks::ALL
+                .iter()
+                .map(|check| Box::new(**check) as Box<dyn Rule>)
+                .collect(),
+        }
+    }
+}
+
+impl fmt::Debug for RuleRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(self.rules.iter().map(|rule| rule.id()))
+            .finish()
+    }
+}
+
+impl RuleRegistry {
+    /// Add a rule. A rule with the same id as an existing one replaces it.
+    pub fn register(&mut self, rule: impl Rule + 'static) {
+        self.rules.retain(|existing| existing.id() != rule.id());
+        self.rules.push(Box::new(rule));
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &dyn Rule> {
+        self.rules.iter().map(|rule| rule.as_ref())
+    }
+
+    /// Look up a rule by its identifier.
+    pub fn by_id(&self, id: &str) -> Option<&dyn Rule> {
+        self.iter().find(|rule| rule.id() == id)
+    }
+
+    /// The rules to run: everything enabled by default or requested, minus the disabled ones
+    /// and those that don't apply to `bevy_version` of `bevy_reflect`, if it's known.
+    pub fn enabled(
+        &self,
+        requested: &[String],
+        disabled: &[String],
+        bevy_version: Option<&Version>,
+    ) -> Vec<&dyn Rule> {
+        self.iter()
+            .filter(|rule| rule.enabled_by_default() || requested.iter().any(|id| id == rule.id()))
+            .filter(|rule| !disabled.iter().any(|id| id == rule.id()))
+            .filter(|rule| {
+                let Some(version) = bevy_version else {
+                    return true;
+                };
+                let version = (version.major, version.minor);
+                rule.min_bevy_version().is_none_or(|min| version >= min)
+                    && rule.max_bevy_version().is_none_or(|max| version < max)
+            })
+            .collect()
+    }
+}
+
+/// A rule requiring types that derive `Reflect` and implement all of `derives` to reflect all of
+/// `reflect`, like `#[reflect(InspectorOptions)]` for types deriving `InspectorOptions`. Projects
+/// declare these in their configuration for the traits of the crates they use.
+#[derive(Debug, Clone)]
+pub struct AttributeRule {
+    id: &'static str,
+    description: &'static str,
+    severity: Severity,
+    enabled_by_default: bool,
+    derives: Vec<String>,
+    reflect: Vec<String>,
+}
+
+impl AttributeRule {
+    /// A warning with the identifier `id` that's enabled by default. As rules have static
+    /// identifiers, `id` and the generated description are leaked, which is fine for the few
+    /// rules of a configuration.
+    pub fn new(id: String, mut derives: Vec<String>, reflect: Vec<String>) -> Self {
+        if !derives.iter().any(|name| name == "Reflect") {
+            derives.insert(0, "Reflect".to_string());
+        }
+        let description = format!(
+            "Types implementing {} should have `#[reflect({})]`.",
+            quoted(&derives),
+            reflect.join(", ")
+        );
+        Self {
+            id: id.leak(),
+            description: description.leak(),
+            severity: Severity::Warning,
+            enabled_by_default: true,
+            derives,
+            reflect,
+        }
+    }
+
+    pub fn severity(mut self, severity: Severity) -> Self {
+        self.severity = severity;
+        self
+    }
+
+    /// Make the rule opt-in.
+    pub fn opt_in(mut self) -> Self {
+        self.enabled_by_default = false;
+        self
+    }
+}
+
+impl Rule for AttributeRule {
+    fn id(&self) -> &'static str {
+        self.id
+    }
+
+    fn name(&self) -> &'static str {
+        self.id
+    }
+
+    fn description(&self) -> &'static str {
+        self.description
+    }
+
+    fn default_severity(&self) -> Severity {
+        self.severity
+    }
+
+    fn enabled_by_default(&self) -> bool {
+        self.enabled_by_default
+    }
+
+    fn check_item(&self, ctx: &ItemCtx<'_>) -> Vec<Finding> {
+        let ty = ctx.ty;
+        if !ty.attrs.derives("Reflect") || !self.derives.iter().all(|name| ty.implements(name)) {
+            return Vec::new();
+        }
+        let missing: Vec<&str> = self
+            .reflect
+            .iter()
+            .filter(|name| !ty.attrs.reflects(name))
+            .map(String::as_str)
+            .collect();
+        if missing.is_empty() {
+            return Vec::new();
+        }
+        let message = format!(
+            "`{}` implements {} but is missing `#[reflect({})]`",
+            ty.ident,
+            quoted(&self.derives),
+            missing.join(", ")
+        );
+        vec![Finding::at_type(
+            self.id,
+            self.severity,
+            message,
+            ty,
+            ctx.crate_kind,
+        )]
+    }
+}
+
+// `A`, `B` and `C`
+fn quoted(names: &[String]) -> String {
+    let names: Vec<String> = names.iter().map(|name| format!("`{name}`")).collect();
+    match names.split_last() {
+        Some((last, rest)) if !rest.is_empty() => format!("{} and {last}", rest.join(", ")),
+        _ => names.concat(),
+    }
+}
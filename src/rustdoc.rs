@@ -0,0 +1,248 @@
+use crate::analyzer::AnalysisConfig;
+use crate::expand;
+use crate::modules::Visibility;
+use crate::types::TypeInfo;
+use cargo_metadata::Package;
+use serde_json::{Map, Value};
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fs;
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
+
+/// How deep re-exports are followed, against glob imports that form a cycle.
+const MAX_DEPTH: usize = 32;
+
+/// What rustdoc knows about a struct or enum of a workspace library.
+#[derive(Debug, Clone)]
+pub(crate) struct DocType {
+    pub crate_name: String,
+    pub name: String,
+    /// The file the type is defined in, canonicalized.
+    pub file: PathBuf,
+    /// Lines of the definition, without its attributes.
+    pub lines: RangeInclusive<usize>,
+    /// Path of the module the type is defined in.
+    pub definition: String,
+    /// All paths other crates can name the type by, empty if it can't be named outside its
+    /// crate.
+    pub public_paths: Vec<String>,
+    /// Traits implemented for the type, derived or by hand, without auto traits and blanket
+    /// impls.
+    pub traits: BTreeSet<String>,
+}
+
+/// Document the library of `package` as JSON with `cargo rustdoc`, which is unstable and so
+/// enabled through `RUSTC_BOOTSTRAP`, and read its structs and enums. Relative paths in the
+/// output are resolved against `workspace_root`.
+pub(crate) fn document_lib(
+    package: &Package,
+    crate_name: &str,
+    config: &AnalysisConfig,
+    is_root: bool,
+    target_dir: &Path,
+    workspace_root: &Path,
+) -> Result<Vec<DocType>, String> {
+    let mut command = expand::cargo_command("rustdoc", package, config, is_root, target_dir);
+    command
+        .arg("--")
+        .arg("-Zunstable-options")
+        .arg("--output-format")
+        .arg("json");
+    expand::run(command)?;
+    let path = target_dir.join("doc").join(format!("{crate_name}.json"));
+    let content = fs::read_to_string(&path)
+        .map_err(|err| format!("Failed to read {}: {err}", path.display()))?;
+    let krate: Value = serde_json::from_str(&content).map_err(|err| err.to_string())?;
+    Ok(doc_types(&krate, crate_name, workspace_root))
+}
+
+/// Take the visibility, paths and trait impls of the types in `crates` from what rustdoc found.
+/// Types it didn't document can't be named outside their crate.
+pub(crate) fn apply(types: &mut [TypeInfo], documented: &[DocType], crates: &HashSet<String>) {
+    let mut by_name: HashMap<(&str, &str), Vec<&DocType>> = HashMap::new();
+    for doc in documented {
+        by_name
+            .entry((&doc.crate_name, &doc.name))
+            .or_default()
+            .push(doc);
+    }
+    for ty in types {
+        let Some(crate_name) = ty
+            .crate_name
+            .as_deref()
+            .filter(|name| crates.contains(*name))
+        else {
+            continue;
+        };
+        let file = canonical(Path::new(&ty.location.file));
+        let doc = by_name
+            .get(&(crate_name, ty.ident.as_str()))
+            .into_iter()
+            .flatten()
+            .find(|doc| doc.file == file && doc.lines.contains(&ty.location.span.line));
+        let Some(doc) = doc.filter(|doc| !doc.public_paths.is_empty()) else {
+            ty.visibility = ty.visibility.min(Visibility::Crate);
+            ty.location.reexports.clear();
+            continue;
+        };
+        ty.visibility = Visibility::Public;
+        let mut paths = doc.public_paths.clone();
+        paths.sort_by_key(|path| {
+            let in_prelude = path.split("::").any(|segment| segment == "prelude");
+            let depth = path.matches("::").count();
+            (path != &doc.definition, in_prelude, depth, path.clone())
+        });
+        paths.dedup();
+        ty.location.type_path = paths.remove(0);
+        ty.location.reexports = paths;
+        let derived: BTreeSet<String> = doc
+            .traits
+            .iter()
+            .filter(|name| !ty.attrs.derives(name))
+            .cloned()
+            .collect();
+        ty.manual_impls.extend(derived);
+    }
+}
+
+// The structs and enums of the local crate in rustdoc's JSON output
+fn doc_types(krate: &Value, crate_name: &str, workspace_root: &Path) -> Vec<DocType> {
+    let Some(index) = krate["index"].as_object() else {
+        return Vec::new();
+    };
+    let mut public_paths: HashMap<String, Vec<String>> = HashMap::new();
+    if let Some(root) = krate.get("root").map(id) {
+        walk_module(index, &root, crate_name, 0, &mut public_paths);
+    }
+
+    let mut types = Vec::new();
+    for (key, item) in index {
+        if item["crate_id"].as_u64() != Some(0) {
+            continue;
+        }
+        let Some(inner) = type_inner(item) else {
+            continue;
+        };
+        let (Some(name), Some(span)) = (item["name"].as_str(), item.get("span")) else {
+            continue;
+        };
+        let (Some(filename), Some(begin), Some(end)) = (
+            span["filename"].as_str(),
+            span["begin"][0].as_u64(),
+            span["end"][0].as_u64(),
+        ) else {
+            continue;
+        };
+        let definition = krate["paths"][key]["path"]
+            .as_array()
+            .map(|segments| {
+                segments
+                    .iter()
+                    .filter_map(Value::as_str)
+                    .collect::<Vec<_>>()
+                    .join("::")
+            })
+            .unwrap_or_else(|| format!("{crate_name}::{name}"));
+        let traits = inner["impls"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|impl_id| trait_name(index.get(&id(impl_id))?))
+            .collect();
+        types.push(DocType {
+            crate_name: crate_name.to_string(),
+            name: name.to_string(),
+            file: canonical(&workspace_root.join(filename)),
+            lines: begin as usize..=end as usize,
+            definition,
+            public_paths: public_paths.remove(key).unwrap_or_default(),
+            traits,
+        });
+    }
+    types
+}
+
+// Record the path of every struct and enum that can be named through the module `module_id`,
+// which is reached as `path`, following public modules and re-exports
+fn walk_module(
+    index: &Map<String, Value>,
+    module_id: &str,
+    path: &str,
+    depth: usize,
+    public_paths: &mut HashMap<String, Vec<String>>,
+) {
+    let Some(items) = index
+        .get(module_id)
+        .and_then(|module| module["inner"]["module"]["items"].as_array())
+    else {
+        return;
+    };
+    if depth > MAX_DEPTH {
+        return;
+    }
+    for item_id in items.iter().map(id) {
+        let Some(item) = index.get(&item_id) else {
+            continue;
+        };
+        let name = item["name"].as_str().unwrap_or_default();
+        if item["inner"].get("module").is_some() {
+            let path = format!("{path}::{name}");
+            walk_module(index, &item_id, &path, depth + 1, public_paths);
+        } else if type_inner(item).is_some() {
+            let paths = public_paths.entry(item_id).or_default();
+            paths.push(format!("{path}::{name}"));
+        } else if let Some(import) = item["inner"].get("use").or(item["inner"].get("import")) {
+            // Re-exports of other crates' items point nowhere in the index
+            let Some(target_id) = import.get("id").filter(|id| !id.is_null()).map(id) else {
+                continue;
+            };
+            let Some(target) = index.get(&target_id) else {
+                continue;
+            };
+            let name = import["name"].as_str().unwrap_or(name);
+            if import["is_glob"].as_bool() == Some(true) {
+                walk_module(index, &target_id, path, depth + 1, public_paths);
+            } else if target["inner"].get("module").is_some() {
+                let path = format!("{path}::{name}");
+                walk_module(index, &target_id, &path, depth + 1, public_paths);
+            } else if type_inner(target).is_some() {
+                let paths = public_paths.entry(target_id).or_default();
+                paths.push(format!("{path}::{name}"));
+            }
+        }
+    }
+}
+
+// The details of a struct or enum item
+fn type_inner(item: &Value) -> Option<&Value> {
+    ["struct", "enum"]
+        .into_iter()
+        .find_map(|kind| item["inner"].get(kind))
+}
+
+// The last segment of the trait an impl item implements, unless it's synthetic (auto traits like
+// `Send`) or a blanket impl
+fn trait_name(item: &Value) -> Option<String> {
+    let inner = item["inner"].get("impl")?;
+    if inner["is_synthetic"].as_bool() == Some(true) || !inner["blanket_impl"].is_null() {
+        return None;
+    }
+    // The field is called `name` in older format versions
+    let path = inner["trait"]
+        .get("path")
+        .or(inner["trait"].get("name"))?
+        .as_str()?;
+    Some(path.rsplit("::").next().unwrap_or(path).to_string())
+}
+
+// Ids are numbers in newer format versions and strings in older ones, index keys are strings
+fn id(value: &Value) -> String {
+    match value {
+        Value::String(id) => id.clone(),
+        other => other.to_string(),
+    }
+}
+
+fn canonical(path: &Path) -> PathBuf {
+    fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
@@ -0,0 +1,275 @@
+//! Cross-checking scene files against the reflected types.
+//!
+//! Scenes saved by Bevy (`.scn.ron`) name every resource and component by its type path. Loading
+//! one fails if a type isn't registered, or can't be inserted because it lacks
+//! `#[reflect(Component)]` or `#[reflect(Resource)]`.
+
+use crate::checks::UNLOADABLE_SCENE_TYPE;
+use crate::finding::{CrateKind, Finding, ParseError, Severity, Span};
+use crate::types::TypeInfo;
+use ron::Value;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+/// File name ending of scene files.
+pub const SCENE_EXTENSION: &str = ".scn.ron";
+
+/// Whether a scene entry is inserted as a resource or as a component of an entity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EntryKind {
+    Resource,
+    Component,
+}
+
+impl EntryKind {
+    /// The trait the type has to implement and reflect.
+    pub fn type_data(self) -> &'static str {
+        match self {
+            Self::Resource => "Resource",
+            Self::Component => "Component",
+        }
+    }
+}
+
+/// A resource or component in a scene file.
+#[derive(Debug, Clone)]
+pub struct SceneEntry {
+    pub type_path: String,
+    pub kind: EntryKind,
+    /// Where the type path is, including its quotes.
+    pub span: Span,
+}
+
+/// The `.scn.ron` files below `dir`, skipping hidden directories.
+pub fn scene_files(dir: &Path) -> Vec<PathBuf> {
+    WalkDir::new(dir)
+        .into_iter()
+        .filter_entry(|entry| {
+            entry.depth() == 0 || !entry.file_name().to_string_lossy().starts_with('.')
+        })
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().is_file())
+        .filter(|entry| {
+            entry
+                .file_name()
+                .to_string_lossy()
+                .ends_with(SCENE_EXTENSION)
+        })
+        .map(|entry| entry.into_path())
+        .collect()
+}
+
+/// The resources and components of a scene, in the order they appear in the file.
+pub fn parse_scene(file: &str, content: &str) -> Result<Vec<SceneEntry>, ParseError> {
+    let scene: Value = ron::from_str(content).map_err(|err| {
+        let offset = line_offset(content, err.position.line, err.position.col);
+        ParseError {
+            file: file.to_string(),
+            message: err.code.to_string(),
+            span: span(content, offset, offset),
+        }
+    })?;
+
+    let mut kinds: HashMap<String, EntryKind> = HashMap::new();
+    for (type_path, _) in map_entries(field(&scene, "resources")) {
+        kinds.insert(type_path, EntryKind::Resource);
+    }
+    for (_, entity) in map_entries(field(&scene, "entities")) {
+        for (type_path, _) in map_entries(field(&entity, "components")) {
+            kinds.insert(type_path, EntryKind::Component);
+        }
+    }
+
+    // The parsed value has no positions, so the type paths are looked up in the text. Type paths
+    // only show up as keys in practice.
+    let mut entries = Vec::new();
+    for (type_path, kind) in kinds {
+        let quoted = format!("\"{type_path}\"");
+        for (offset, _) in content.match_indices(&quoted) {
+            entries.push(SceneEntry {
+                type_path: type_path.clone(),
+                kind,
+                span: span(content, offset, offset + quoted.len()),
+            });
+        }
+    }
+    entries.sort_by_key(|entry| entry.span.start_byte);
+    Ok(entries)
+}
+
+/// Check the scene files in `dirs` against `types`, returning the findings and the files that
+/// couldn't be read or parsed.
+pub fn check_scene_dirs(
+    dirs: &[PathBuf],
+    types: &[TypeInfo],
+    severity: Severity,
+) -> (Vec<Finding>, Vec<ParseError>) {
+    let mut findings = Vec::new();
+    let mut errors = Vec::new();
+    for path in dirs.iter().flat_map(|dir| scene_files(dir)) {
+        let file = path.to_string_lossy().into_owned();
+        let parsed = fs::read_to_string(&path)
+            .map_err(|err| ParseError {
+                file: file.clone(),
+                message: err.to_string(),
+                span: span("", 0, 0),
+            })
+            .and_then(|content| parse_scene(&file, &content));
+        match parsed {
+            Ok(entries) => {
+                // Every type is reported once per file, at its first entry
+                let mut reported = BTreeSet::new();
+                findings.extend(
+                    check_scene(&file, &entries, types, severity)
+                        .into_iter()
+                        .filter(|finding| reported.insert(finding.type_path.clone())),
+                );
+            }
+            Err(err) => errors.push(err),
+        }
+    }
+    (findings, errors)
+}
+
+/// A finding for every entry of a scene that would fail to load. Types from crates that weren't
+/// scanned are left alone, as nothing is known about them.
+pub fn check_scene(
+    file: &str,
+    entries: &[SceneEntry],
+    types: &[TypeInfo],
+    severity: Severity,
+) -> Vec<Finding> {
+    let by_path: HashMap<&str, &TypeInfo> = types
+        .iter()
+        .flat_map(|ty| {
+            // Scenes name types by their definition path, but the public ones are accepted too
+            [&ty.location.type_path, &ty.location.definition_path]
+                .into_iter()
+                .chain(&ty.location.reexports)
+                .map(move |path| (path.as_str(), ty))
+        })
+        .collect();
+    let crates: HashSet<&str> = by_path
+        .keys()
+        .filter_map(|path| path.split("::").next())
+        .collect();
+
+    let mut findings = Vec::new();
+    for entry in entries {
+        let Some(problem) = problem(entry, &by_path, &crates) else {
+            continue;
+        };
+        let ty = by_path.get(entry.type_path.as_str());
+        findings.push(Finding {
+            check: UNLOADABLE_SCENE_TYPE.id,
+            severity,
+            message: format!("`{}` {problem}", entry.type_path),
+            type_path: entry.type_path.clone(),
+            reexports: Vec::new(),
+            crate_kind: CrateKind::Workspace,
+            component_storage: ty.and_then(|ty| ty.attrs.component_storage().map(str::to_string)),
+            file: file.to_string(),
+            span: entry.span,
+            variant: None,
+            upstream: None,
+        });
+    }
+    findings
+}
+
+// Why the entry's type can't be loaded, if it can't
+fn problem(
+    entry: &SceneEntry,
+    by_path: &HashMap<&str, &TypeInfo>,
+    crates: &HashSet<&str>,
+) -> Option<String> {
+    let kind = entry.kind.type_data();
+    let Some(ty) = by_path.get(entry.type_path.as_str()) else {
+        let crate_name = entry.type_path.split("::").next()?;
+        return crates.contains(crate_name).then(|| {
+            "is in a scene file but isn't among the checked types of its crate, it may have \
+                been renamed or removed"
+                .to_string()
+        });
+    };
+    if !ty.implements("Reflect") {
+        Some("is in a scene file but doesn't implement `Reflect`".to_string())
+    } else if !ty.registered {
+        Some("is in a scene file but is never registered with `register_type`".to_string())
+    } else if ty.attrs.derives("Reflect") && !ty.attrs.reflects(kind) {
+        Some(format!(
+            "is a {} in a scene file but is missing `#[reflect({kind})]`",
+            kind.to_lowercase()
+        ))
+    } else if ty.lacks_scene_constructor() {
+        Some(
+            "is in a scene file but has `#[reflect(from_reflect = false)]` without \
+                `#[reflect(Default)]` or `#[reflect(FromWorld)]` to construct it"
+                .to_string(),
+        )
+    } else {
+        None
+    }
+}
+
+// The value of a struct field, which parses as a map entry
+fn field(value: &Value, name: &str) -> Option<Value> {
+    match value {
+        Value::Map(map) => map
+            .iter()
+            .find(|(key, _)| matches!(key, Value::String(key) if key == name))
+            .map(|(_, value)| value.clone()),
+        _ => None,
+    }
+}
+
+// The entries of a map, with string keys as strings and other keys formatted
+fn map_entries(value: Option<Value>) -> Vec<(String, Value)> {
+    let Some(Value::Map(map)) = value else {
+        return Vec::new();
+    };
+    map.into_iter()
+        .map(|(key, value)| match key {
+            Value::String(key) => (key, value),
+            key => (format!("{key:?}"), value),
+        })
+        .collect()
+}
+
+// The byte offset of a 1-based line and column
+fn line_offset(content: &str, line: usize, column: usize) -> usize {
+    let line_start: usize = content
+        .split_inclusive('\n')
+        .take(line.saturating_sub(1))
+        .map(str::len)
+        .sum();
+    content[line_start..]
+        .char_indices()
+        .nth(column.saturating_sub(1))
+        .map_or(content.len(), |(offset, _)| line_start + offset)
+}
+
+fn span(content: &str, start: usize, end: usize) -> Span {
+    let (line, column) = line_column(content, start);
+    let (end_line, end_column) = line_column(content, end);
+    Span {
+        line,
+        column,
+        end_line,
+        end_column,
+        start_byte: start,
+        end_byte: end,
+    }
+}
+
+// 1-based line and column of a byte offset, counting characters
+fn line_column(content: &str, offset: usize) -> (usize, usize) {
+    let before = &content[..offset];
+    let line_start = before.rfind('\n').map_or(0, |index| index + 1);
+    (
+        before.matches('\n').count() + 1,
+        before[line_start..].chars().count() + 1,
+    )
+}
@@ -0,0 +1,209 @@
+use crate::finding::Finding;
+use crate::types::TypeInfo;
+use serde::Serialize;
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+
+/// Rough heap size of a type's registration without its fields and type data: the `TypeInfo`,
+/// the registration itself and its entries in the registry's maps.
+const BYTES_PER_TYPE: usize = 320;
+/// Rough size of the info about a field or variant, with its name and custom attributes.
+const BYTES_PER_FIELD: usize = 72;
+/// Rough size of a type data entry, boxed in the registration's map.
+const BYTES_PER_TYPE_DATA: usize = 48;
+/// Rough time it takes to register a type at startup, in nanoseconds.
+const NANOS_PER_TYPE: usize = 1500;
+/// Rough time it takes to create and insert a type data entry, in nanoseconds.
+const NANOS_PER_TYPE_DATA: usize = 200;
+
+/// Reflection coverage of a crate.
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct CrateStats {
+    pub crate_name: String,
+    /// Types that implement `Reflect`.
+    pub reflect_types: usize,
+    /// Types that implement `Component`.
+    pub components: usize,
+    /// Components with `#[reflect(Component)]`.
+    pub reflected_components: usize,
+    /// Percentage of components with `#[reflect(Component)]`, `None` without components.
+    pub coverage: Option<f64>,
+    pub findings: usize,
+}
+
+/// The number of findings in a module.
+#[derive(Debug, Clone, Serialize)]
+pub struct ModuleStats {
+    pub module_path: String,
+    pub findings: usize,
+}
+
+/// Aggregate numbers for tracking reflection coverage over time.
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct Stats {
+    /// Crates with any reflected types, components or findings, by name.
+    pub crates: Vec<CrateStats>,
+    /// Totals over all crates, with `total` as the crate name.
+    pub total: CrateStats,
+    /// The modules with the most findings, most first.
+    pub worst_modules: Vec<ModuleStats>,
+}
+
+impl Stats {
+    /// Count the types and findings per crate, and list the `top` modules with the most findings.
+    pub fn new(types: &[TypeInfo], findings: &[Finding], top: usize) -> Self {
+        let mut crates: BTreeMap<String, CrateStats> = BTreeMap::new();
+        let mut total = CrateStats {
+            crate_name: "total".to_string(),
+            ..CrateStats::default()
+        };
+        for ty in types {
+            let reflect = ty.implements("Reflect");
+            let component = ty.implements("Component");
+            if !reflect && !component {
+                continue;
+            }
+            let reflected_component = component && ty.attrs.reflects("Component");
+            for stats in [crate_entry(&mut crates, &ty.location.type_path), &mut total] {
+                stats.reflect_types += usize::from(reflect);
+                stats.components += usize::from(component);
+                stats.reflected_components += usize::from(reflected_component);
+            }
+        }
+        let mut modules: BTreeMap<&str, usize> = BTreeMap::new();
+        for finding in findings {
+            crate_entry(&mut crates, &finding.type_path).findings += 1;
+            total.findings += 1;
+            let module_path = finding
+                .type_path
+                .rsplit_once("::")
+                .map_or(finding.type_path.as_str(), |(module_path, _)| module_path);
+            *modules.entry(module_path).or_default() += 1;
+        }
+
+        let mut crates: Vec<CrateStats> = crates.into_values().collect();
+        for stats in crates.iter_mut().chain([&mut total]) {
+            stats.coverage = (stats.components > 0)
+                .then(|| stats.reflected_components as f64 * 100.0 / stats.components as f64);
+        }
+        let mut worst_modules: Vec<ModuleStats> = modules
+            .into_iter()
+            .map(|(module_path, findings)| ModuleStats {
+                module_path: module_path.to_string(),
+                findings,
+            })
+            .collect();
+        // Ties are broken by path, as the map is sorted by it and the sort is stable
+        worst_modules.sort_by_key(|module| Reverse(module.findings));
+        worst_modules.truncate(top);
+
+        Self {
+            crates,
+            total,
+            worst_modules,
+        }
+    }
+}
+
+// The stats of the crate a type belongs to
+fn crate_entry<'a>(
+    crates: &'a mut BTreeMap<String, CrateStats>,
+    type_path: &str,
+) -> &'a mut CrateStats {
+    let crate_name = type_path.split("::").next().unwrap_or_default();
+    crates
+        .entry(crate_name.to_string())
+        .or_insert_with(|| CrateStats {
+            crate_name: crate_name.to_string(),
+            ..CrateStats::default()
+        })
+}
+
+/// How much a crate's reflected types add to the type registry.
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct CrateRegistrySize {
+    pub crate_name: String,
+    /// Types that derive `Reflect`.
+    pub types: usize,
+    /// Fields and variants of those types.
+    pub fields: usize,
+    /// Type data entries, from `#[reflect(...)]` and the ones the derive always registers.
+    pub type_data: usize,
+    /// Estimated heap memory of the registrations, in bytes.
+    pub bytes: usize,
+    /// Estimated time it takes to register the types at startup, in microseconds.
+    pub startup_micros: f64,
+    /// Percentage of the total estimated memory.
+    pub share: f64,
+}
+
+/// An estimate of the type registry's size, if every type that derives `Reflect` is registered.
+///
+/// The numbers are rough averages for a 64-bit target, meant to compare crates with each other
+/// rather than to predict the exact footprint. Generic types count once, although each
+/// registered instance has a registration of its own.
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct RegistrySize {
+    /// Crates with reflected types, the biggest first.
+    pub crates: Vec<CrateRegistrySize>,
+    /// Totals over all crates, with `total` as the crate name.
+    pub total: CrateRegistrySize,
+}
+
+impl RegistrySize {
+    /// Add up the registrations of the types that derive `Reflect`, per crate.
+    pub fn new(types: &[TypeInfo]) -> Self {
+        let mut crates: BTreeMap<String, CrateRegistrySize> = BTreeMap::new();
+        let mut total = CrateRegistrySize {
+            crate_name: "total".to_string(),
+            ..CrateRegistrySize::default()
+        };
+        for ty in types.iter().filter(|ty| ty.attrs.derives("Reflect")) {
+            let crate_name = ty.location.type_path.split("::").next().unwrap_or_default();
+            let crate_size =
+                crates
+                    .entry(crate_name.to_string())
+                    .or_insert_with(|| CrateRegistrySize {
+                        crate_name: crate_name.to_string(),
+                        ..CrateRegistrySize::default()
+                    });
+            // The derive registers `ReflectFromPtr`, and `ReflectFromReflect` unless it's
+            // disabled. Options like `opaque` aren't type data.
+            let type_data = ty
+                .attrs
+                .reflect
+                .iter()
+                .filter(|entry| entry.starts_with(char::is_uppercase))
+                .count()
+                + 1
+                + usize::from(!ty.attrs.from_reflect_disabled);
+            for size in [crate_size, &mut total] {
+                size.types += 1;
+                size.fields += ty.fields.len() + ty.variants.len();
+                size.type_data += type_data;
+            }
+        }
+
+        let mut crates: Vec<CrateRegistrySize> = crates.into_values().collect();
+        for size in crates.iter_mut().chain([&mut total]) {
+            size.bytes = size.types * BYTES_PER_TYPE
+                + size.fields * BYTES_PER_FIELD
+                + size.type_data * BYTES_PER_TYPE_DATA;
+            size.startup_micros = (size.types * NANOS_PER_TYPE
+                + size.type_data * NANOS_PER_TYPE_DATA) as f64
+                / 1000.0;
+        }
+        let total_bytes = total.bytes;
+        for size in crates.iter_mut().chain([&mut total]) {
+            size.share = if total_bytes > 0 {
+                size.bytes as f64 * 100.0 / total_bytes as f64
+            } else {
+                0.0
+            };
+        }
+        // Ties are broken by name, as the map is sorted by it and the sort is stable
+        crates.sort_by_key(|size| Reverse(size.bytes));
+
+        Self { crates, total }
+    }
+}
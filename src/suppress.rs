@@ -0,0 +1,88 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use syn::punctuated::Punctuated;
+use syn::{Attribute, Meta, Path, Token};
+
+/// Comment marker that suppresses findings for the type below it, e.g.
+/// `// reflect-check: ignore` or `// reflect-check: ignore(missing-reflect-default)`.
+const COMMENT_MARKER: &str = "reflect-check:";
+
+/// Findings suppressed on a type.
+///
+/// Besides the comment marker, `#[cfg_attr(any(), reflect_check::ignore)]` is recognized. The
+/// `cfg_attr` with a predicate that's never true keeps the compiler from complaining about an
+/// unknown attribute.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct Suppressions {
+    all: bool,
+    rules: BTreeSet<String>,
+}
+
+impl Suppressions {
+    /// Look for suppressions in the attributes of a type and in the comments between the line
+    /// above its first attribute and the line with its name (1-based, exclusive).
+    pub fn parse(attrs: &[Attribute], source: &str, first_line: usize, ident_line: usize) -> Self {
+        let mut result = Self::default();
+
+        for line in source
+            .lines()
+            .skip(first_line.saturating_sub(2))
+            .take(ident_line.saturating_sub(first_line.saturating_sub(1)))
+        {
+            if let Some((_, comment)) = line.split_once("//")
+                && let Some(directive) = comment.trim().strip_prefix(COMMENT_MARKER)
+                && let Some(args) = directive.trim().strip_prefix("ignore")
+            {
+                result.add(args);
+            }
+        }
+
+        for attr in attrs {
+            if let Meta::List(meta_list) = &attr.meta
+                && meta_list.path.is_ident("cfg_attr")
+                && let Ok(nested) =
+                    meta_list.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)
+            {
+                // The first entry is the predicate
+                for meta in nested.iter().skip(1) {
+                    match meta {
+                        Meta::Path(path) if is_ignore_path(path) => result.all = true,
+                        Meta::List(list) if is_ignore_path(&list.path) => {
+                            result.add(&format!("({})", list.tokens));
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        }
+
+        result
+    }
+
+    /// Add the rules from an optional `(rule, ...)` list, no list means everything.
+    fn add(&mut self, args: &str) {
+        let args: String = args.chars().filter(|c| !c.is_whitespace()).collect();
+        if args.is_empty() {
+            self.all = true;
+        } else if let Some(list) = args
+            .strip_prefix('(')
+            .and_then(|args| args.strip_suffix(')'))
+        {
+            self.rules.extend(
+                list.split(',')
+                    .filter(|rule| !rule.is_empty())
+                    .map(str::to_owned),
+            );
+        }
+    }
+
+    /// Whether findings of the given rule are suppressed.
+    pub fn covers(&self, rule: &str) -> bool {
+        self.all || self.rules.contains(rule)
+    }
+}
+
+fn is_ignore_path(path: &Path) -> bool {
+    let segments: Vec<_> = path.segments.iter().map(|s| s.ident.to_string()).collect();
+    segments == ["reflect_check", "ignore"]
+}
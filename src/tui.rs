@@ -0,0 +1,324 @@
+use crate::config::Config;
+use bevy_reflect_check::baseline::Baseline;
+use bevy_reflect_check::{CrateKind, Finding, Severity, checks, fix};
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Style, Stylize};
+use ratatui::text::{Line, Span, Text};
+use ratatui::widgets::{Block, List, ListItem, ListState, Paragraph, Wrap};
+use ratatui::{DefaultTerminal, Frame};
+use std::collections::BTreeSet;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+/// Lines of source shown above and below the line a finding points at.
+const CONTEXT_LINES: usize = 4;
+
+const KEYS: &str = "↑/↓ move  i ignore finding  t ignore type  f fix  q quit";
+
+/// Where the decisions made while triaging are written to.
+pub struct Triage {
+    /// The baseline that ignored findings are added to, along with its current content.
+    pub baseline: Option<(PathBuf, Baseline)>,
+    /// The configuration file whose `ignore-types` ignored types are added to.
+    pub config: PathBuf,
+}
+
+/// Browse `findings` grouped by module, with the source of the selected one, and ignore or fix
+/// them one at a time until the user quits.
+///
+/// `analyze` is called after a fix to pick up the changed source. Findings ignored in the
+/// meantime are left out of its results.
+pub fn run(
+    triage: Triage,
+    findings: Vec<Finding>,
+    analyze: impl Fn() -> Vec<Finding>,
+) -> io::Result<()> {
+    let mut app = App {
+        triage,
+        ignored_types: BTreeSet::new(),
+        findings: Vec::new(),
+        rows: Vec::new(),
+        list: ListState::default(),
+        status: String::new(),
+    };
+    app.set_findings(findings);
+    // Restores the terminal on panics as well
+    let mut terminal = ratatui::init();
+    let result = app.run(&mut terminal, &analyze);
+    ratatui::restore();
+    result
+}
+
+// A line of the list on the left
+enum Row {
+    /// The module the findings below it are in.
+    Module(String),
+    /// An index into the findings.
+    Finding(usize),
+}
+
+struct App {
+    triage: Triage,
+    /// Types ignored during this session, which the analysis doesn't know about yet.
+    ignored_types: BTreeSet<String>,
+    /// Sorted by type path, so that findings of a module are next to each other.
+    findings: Vec<Finding>,
+    rows: Vec<Row>,
+    list: ListState,
+    /// The outcome of the last action.
+    status: String,
+}
+
+impl App {
+    fn run(
+        &mut self,
+        terminal: &mut DefaultTerminal,
+        analyze: &impl Fn() -> Vec<Finding>,
+    ) -> io::Result<()> {
+        loop {
+            terminal.draw(|frame| self.draw(frame))?;
+            let Event::Key(key) = event::read()? else {
+                continue;
+            };
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            match key.code {
+                KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+                KeyCode::Down | KeyCode::Char('j') => self.select_next(1),
+                KeyCode::Up | KeyCode::Char('k') => self.select_next(-1),
+                KeyCode::Char('i') => self.ignore_finding(),
+                KeyCode::Char('t') => self.ignore_type(),
+                KeyCode::Char('f') => self.fix(analyze),
+                _ => {}
+            }
+        }
+    }
+
+    // Replace the findings, keeping the selection at about the same place in the list
+    fn set_findings(&mut self, mut findings: Vec<Finding>) {
+        findings.retain(|finding| {
+            !self.ignored_types.contains(&finding.type_path)
+                && !self
+                    .triage
+                    .baseline
+                    .as_ref()
+                    .is_some_and(|(_, baseline)| baseline.contains(finding))
+        });
+        findings.sort_by(|a, b| {
+            (&a.type_path, &a.variant, a.check).cmp(&(&b.type_path, &b.variant, b.check))
+        });
+        self.rows.clear();
+        let mut current_module = None;
+        for (index, finding) in findings.iter().enumerate() {
+            let module = module_of(&finding.type_path);
+            if current_module != Some(module) {
+                self.rows.push(Row::Module(module.to_string()));
+                current_module = Some(module);
+            }
+            self.rows.push(Row::Finding(index));
+        }
+        self.findings = findings;
+
+        let selected = self.list.selected().unwrap_or_default();
+        let row = (selected.min(self.rows.len().saturating_sub(1))..self.rows.len())
+            .chain((0..selected.min(self.rows.len())).rev())
+            .find(|row| matches!(self.rows[*row], Row::Finding(_)));
+        self.list.select(row);
+    }
+
+    // Move the selection by `step` findings, skipping the module rows
+    fn select_next(&mut self, step: isize) {
+        let Some(mut row) = self.list.selected() else {
+            return;
+        };
+        loop {
+            let Some(next) = row
+                .checked_add_signed(step)
+                .filter(|row| *row < self.rows.len())
+            else {
+                return;
+            };
+            row = next;
+            if let Row::Finding(_) = self.rows[row] {
+                self.list.select(Some(row));
+                return;
+            }
+        }
+    }
+
+    fn selected(&self) -> Option<&Finding> {
+        match self.rows.get(self.list.selected()?)? {
+            Row::Finding(index) => self.findings.get(*index),
+            Row::Module(_) => None,
+        }
+    }
+
+    // Add the selected finding to the baseline and save it right away
+    fn ignore_finding(&mut self) {
+        let Some(finding) = self.selected().cloned() else {
+            return;
+        };
+        let Some((path, baseline)) = &mut self.triage.baseline else {
+            self.status = "Pass --baseline <file> or set `baseline` in the configuration to \
+                ignore findings"
+                .to_string();
+            return;
+        };
+        baseline.insert(&finding);
+        self.status = match baseline.save(path) {
+            Ok(()) => format!(
+                "Ignored `{}` for {} in {}",
+                finding.check,
+                finding.subject(),
+                path.display()
+            ),
+            Err(err) => format!("Failed to write {}: {err}", path.display()),
+        };
+        let findings = std::mem::take(&mut self.findings);
+        self.set_findings(findings);
+    }
+
+    // Add the selected finding's type to `ignore-types` in the configuration
+    fn ignore_type(&mut self) {
+        let Some(type_path) = self.selected().map(|finding| finding.type_path.clone()) else {
+            return;
+        };
+        self.status = match Config::ignore_type(&self.triage.config, &type_path) {
+            Ok(()) => format!("Ignored `{type_path}` in {}", self.triage.config.display()),
+            Err(err) => err.to_string(),
+        };
+        self.ignored_types.insert(type_path);
+        let findings = std::mem::take(&mut self.findings);
+        self.set_findings(findings);
+    }
+
+    // Apply the `--fix` edit for the selected finding and analyze again, as the edit moves the
+    // code after it
+    fn fix(&mut self, analyze: &impl Fn() -> Vec<Finding>) {
+        let Some(finding) = self.selected().cloned() else {
+            return;
+        };
+        if finding.crate_kind == CrateKind::Dependency {
+            self.status = "Only findings in the workspace can be fixed".to_string();
+            return;
+        }
+        let fixes = fix::plan(std::slice::from_ref(&finding));
+        let Some(file_fix) = fixes.iter().find(|file_fix| file_fix.covers(&finding)) else {
+            self.status = format!("`{}` can't be fixed automatically", finding.check);
+            return;
+        };
+        if let Err(err) = file_fix.apply() {
+            self.status = format!("Failed to write {}: {err}", file_fix.path);
+            return;
+        }
+        self.status = format!("Fixed {} in {}", finding.subject(), file_fix.path);
+        self.set_findings(analyze());
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [main, status] =
+            Layout::vertical([Constraint::Min(1), Constraint::Length(1)]).areas(frame.area());
+        let [list_area, detail_area] =
+            Layout::horizontal([Constraint::Percentage(40), Constraint::Percentage(60)])
+                .areas(main);
+
+        let items: Vec<ListItem> = self
+            .rows
+            .iter()
+            .map(|row| match row {
+                Row::Module(module) => ListItem::new(Line::from(module.clone()).bold()),
+                Row::Finding(index) => {
+                    let finding = &self.findings[*index];
+                    let name = finding
+                        .subject()
+                        .strip_prefix(module_of(&finding.type_path))
+                        .unwrap_or_default()
+                        .trim_start_matches("::")
+                        .to_string();
+                    ListItem::new(Line::from(vec![
+                        Span::raw("  "),
+                        severity_span(finding.severity),
+                        Span::raw(format!(" {name} ")),
+                        Span::raw(finding.check).dark_gray(),
+                    ]))
+                }
+            })
+            .collect();
+        let list = List::new(items)
+            .block(Block::bordered().title(format!(" Findings ({}) ", self.findings.len())))
+            .highlight_style(Style::new().reversed());
+        frame.render_stateful_widget(list, list_area, &mut self.list);
+
+        let detail = Paragraph::new(self.detail())
+            .block(Block::bordered().title(" Details "))
+            .wrap(Wrap { trim: false });
+        frame.render_widget(detail, detail_area);
+
+        let status_line = if self.status.is_empty() {
+            KEYS
+        } else {
+            self.status.as_str()
+        };
+        frame.render_widget(Paragraph::new(status_line).dark_gray(), status);
+    }
+
+    // The message, hint and source code of the selected finding
+    fn detail(&self) -> Text<'static> {
+        let Some(finding) = self.selected() else {
+            return Text::from("No findings left");
+        };
+        let mut lines = vec![
+            Line::from(vec![
+                severity_span(finding.severity),
+                Span::raw(format!("[{}]", finding.check)),
+            ]),
+            Line::from(finding.message.clone()),
+            Line::default(),
+            Line::from(format!(
+                "{}:{}:{}",
+                finding.file, finding.span.line, finding.span.column
+            ))
+            .dark_gray(),
+        ];
+        if let Ok(source) = fs::read_to_string(&finding.file) {
+            let first = finding.span.line.saturating_sub(CONTEXT_LINES).max(1);
+            for (number, line) in source
+                .lines()
+                .enumerate()
+                .map(|(index, line)| (index + 1, line))
+                .skip(first - 1)
+                .take(2 * CONTEXT_LINES + 1)
+            {
+                let text = Line::from(format!("{number:>5} | {line}"));
+                lines.push(if number == finding.span.line {
+                    text.yellow()
+                } else {
+                    text
+                });
+            }
+        }
+        if let Some(check) = checks::by_id(finding.check) {
+            lines.push(Line::default());
+            lines.push(Line::from(format!("help: {}", check.hint)).green());
+        }
+        Text::from(lines)
+    }
+}
+
+// The module a type is in, everything before the last `::`
+fn module_of(type_path: &str) -> &str {
+    type_path
+        .rsplit_once("::")
+        .map_or(type_path, |(module, _)| module)
+}
+
+fn severity_span(severity: Severity) -> Span<'static> {
+    match severity {
+        Severity::Error => Span::styled("error ", Style::new().fg(Color::Red)),
+        Severity::Warning => Span::styled("warning ", Style::new().fg(Color::Yellow)),
+        Severity::Info => Span::styled("info ", Style::new().fg(Color::Blue)),
+    }
+}
@@ -0,0 +1,639 @@
+use crate::attrs::{KNOWN_TYPE_DATA, ReflectAttrs};
+use crate::exports::{Binding, Exports};
+use crate::finding::{ParseError, Span, TypeLocation};
+use crate::modules::{ModDecl, Placement, Visibility};
+use crate::suppress::Suppressions;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use syn::{ItemImpl, Type};
+
+/// Everything the checks need to know about a struct or enum.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TypeInfo {
+    pub ident: String,
+    /// Package the type was found in, `None` for the current project.
+    pub crate_name: Option<String>,
+    pub location: TypeLocation,
+    /// How far the type can be seen. Until [`Collected::finish`], this only accounts for the
+    /// modules within its own file.
+    pub visibility: Visibility,
+    pub attrs: ReflectAttrs,
+    /// Names of the type's type and const parameters.
+    pub generics: Vec<String>,
+    /// Traits implemented by hand (`impl Trait for Type`) somewhere in the same crate, and
+    /// `TypePath` if it's implemented through `impl_type_path!`.
+    pub manual_impls: BTreeSet<String>,
+    /// Whether a type of the same name is passed to `register_type` anywhere in the scanned code.
+    pub registered: bool,
+    /// Type data added by the registration methods a type of the same name is passed to, like
+    /// `Asset` by `register_asset_reflect` or `State` by `register_type_state`.
+    #[serde(default)]
+    pub registered_type_data: BTreeSet<String>,
+    /// Whether the type has component hooks, from `#[component(on_add = ...)]` and the like or
+    /// registered in code, or observers watch it.
+    #[serde(default)]
+    pub observed: bool,
+    /// Whether the type's crate registers types in `impl Plugin` blocks, but none of the plugins
+    /// in the scanned code registers this one, directly or as a field of a registered type.
+    pub missing_plugin_registration: bool,
+    /// Instances of the type with concrete generic arguments, like `Foo<f32>`, that are used in
+    /// the scanned code but neither registered nor part of a registered type.
+    pub unregistered_instances: BTreeSet<String>,
+    /// Fields that aren't configured out.
+    pub fields: Vec<FieldInfo>,
+    /// Variants of an enum that aren't configured out, empty for structs.
+    pub variants: Vec<VariantInfo>,
+    /// Type parameters without the bounds that reflecting them takes, along with the missing
+    /// traits.
+    pub missing_bounds: BTreeMap<String, Vec<String>>,
+    /// Fields whose type is defined in the scanned code without implementing `Reflect`, along
+    /// with the name of that type. Only set for types that derive `Reflect` without being opaque.
+    pub non_reflect_fields: BTreeMap<String, String>,
+    /// `#[reflect(ignore)]`d fields that `FromReflect` has no fallback for, and whether their type
+    /// is known not to implement `Default`. Only set for reflected components and resources.
+    pub ignored_without_default: BTreeMap<String, bool>,
+    /// Fields of a `Bundle` whose type is a component from the scanned code that isn't fully
+    /// reflected, along with that type's name and whether it implements `Reflect` at all.
+    pub unreflected_bundle_fields: BTreeMap<String, (String, bool)>,
+    /// Components from `#[require(...)]` defined in the scanned code that aren't fully
+    /// reflected, along with whether they implement `Reflect` at all. Only set for reflected
+    /// components.
+    #[serde(default)]
+    pub unreflected_required: BTreeMap<String, bool>,
+    /// Entries in `#[reflect(...)]` that don't name any known type data, along with the closest
+    /// known name if there's one that's similar enough.
+    pub unknown_type_data: BTreeMap<String, Option<String>>,
+    /// Findings the source code asks us not to report.
+    pub suppressions: Suppressions,
+}
+
+/// A variant of an enum.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VariantInfo {
+    pub name: String,
+    /// Where the variant's name is.
+    pub span: Span,
+}
+
+/// A field of a struct or enum variant that takes part in reflection.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FieldInfo {
+    /// `name` or `0`, prefixed with the variant as in `Variant::name` for enums.
+    pub name: String,
+    /// Names of the types mentioned in the field's type, e.g. `Vec` and `Transform` for
+    /// `Vec<Transform>`.
+    pub type_names: BTreeSet<String>,
+    /// Name of the field's type without generic arguments, `Vec` for `Vec<Transform>`.
+    pub outer_type: Option<String>,
+    /// Marked with `#[reflect(ignore)]`.
+    pub ignored: bool,
+    /// Has a fallback for `FromReflect` via `#[reflect(default)]` or `#[reflect(default = "...")]`.
+    pub reflect_default: bool,
+    /// Marked with `#[bundle(ignore)]`, so not inserted as a component.
+    pub bundle_ignored: bool,
+    /// Generic types with arguments in the field's type, which get registered along with it.
+    pub instances: BTreeSet<Instance>,
+    /// Lifetimes other than `'static` in the field's type, like `'a` in `&'a str`.
+    #[serde(default)]
+    pub lifetimes: BTreeSet<String>,
+}
+
+/// A generic type with arguments, like `Foo<u32>`.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct Instance {
+    /// Name of the generic type, `Foo` for `Foo<u32>`.
+    pub name: String,
+    /// The type with its arguments, using the last path segment of each type, or `None` if the
+    /// arguments refer to generic parameters and so stand for any instance.
+    pub concrete: Option<String>,
+}
+
+impl TypeInfo {
+    /// Whether the type derives or manually implements the trait `name`.
+    pub fn implements(&self, name: &str) -> bool {
+        self.attrs.derives(name) || self.manual_impls.contains(name)
+    }
+
+    /// Whether the type derives `Reflect` with `#[reflect(from_reflect = false)]` and has neither
+    /// `#[reflect(Default)]` nor `#[reflect(FromWorld)]`, which scenes could fall back to when
+    /// inserting it as a component or resource.
+    pub fn lacks_scene_constructor(&self) -> bool {
+        self.attrs.derives("Reflect")
+            && self.attrs.from_reflect_disabled
+            && !self.attrs.reflects("Default")
+            && !self.attrs.reflects("FromWorld")
+    }
+
+    /// Reflected fields that hold an `Entity`, directly or inside another type like
+    /// `Vec<Entity>`.
+    pub fn entity_fields(&self) -> Vec<&str> {
+        self.fields
+            .iter()
+            .filter(|field| !field.ignored && field.type_names.contains("Entity"))
+            .map(|field| field.name.as_str())
+            .collect()
+    }
+
+    /// Whether `name` is in the type's `#[reflect(...)]` or added by a registration method it's
+    /// passed to, like `register_asset_reflect`.
+    pub fn has_type_data(&self, name: &str) -> bool {
+        self.attrs.reflects(name) || self.registered_type_data.contains(name)
+    }
+
+    /// Whether the type derives or implements `States` or `SubStates`. `ComputedStates` are
+    /// derived from other states and not meant to be set through reflection.
+    pub fn is_state(&self) -> bool {
+        self.implements("States") || self.implements("SubStates")
+    }
+
+    /// Whether the type derives `Reflect` and derives or implements `name`, but doesn't have
+    /// `#[reflect(name)]`. Type data of a hand-written `Reflect` impl is registered in code
+    /// rather than through attributes, so those types are left alone.
+    pub fn missing_reflect_for(&self, name: &str) -> bool {
+        self.attrs.derives("Reflect") && self.implements(name) && !self.attrs.reflects(name)
+    }
+}
+
+/// Manual trait implementations, keyed by crate and the implementing type's name.
+///
+/// `impl` blocks can live anywhere in a crate, so we can't reliably resolve which of
+/// several same-named types they refer to. Matching on the name is good enough in practice.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(from = "Vec<TraitImpl>", into = "Vec<TraitImpl>")]
+pub(crate) struct TraitImpls(HashMap<(Option<String>, String), BTreeSet<String>>);
+
+/// Serialized form of a [`TraitImpls`] entry, as JSON maps can't have tuple keys.
+#[derive(Debug, Serialize, Deserialize)]
+struct TraitImpl {
+    crate_name: Option<String>,
+    type_name: String,
+    traits: BTreeSet<String>,
+}
+
+impl From<Vec<TraitImpl>> for TraitImpls {
+    fn from(entries: Vec<TraitImpl>) -> Self {
+        Self(
+            entries
+                .into_iter()
+                .map(|entry| ((entry.crate_name, entry.type_name), entry.traits))
+                .collect(),
+        )
+    }
+}
+
+impl From<TraitImpls> for Vec<TraitImpl> {
+    fn from(impls: TraitImpls) -> Self {
+        impls
+            .0
+            .into_iter()
+            .map(|((crate_name, type_name), traits)| TraitImpl {
+                crate_name,
+                type_name,
+                traits,
+            })
+            .collect()
+    }
+}
+
+impl TraitImpls {
+    pub fn record(&mut self, crate_name: Option<&str>, item: &ItemImpl) {
+        let Some((_, trait_path, _)) = &item.trait_ else {
+            return;
+        };
+        let Type::Path(self_ty) = item.self_ty.as_ref() else {
+            return;
+        };
+        if let (Some(trait_segment), Some(type_segment)) =
+            (trait_path.segments.last(), self_ty.path.segments.last())
+        {
+            self.0
+                .entry((
+                    crate_name.map(str::to_owned),
+                    type_segment.ident.to_string(),
+                ))
+                .or_default()
+                .insert(trait_segment.ident.to_string());
+        }
+    }
+
+    pub fn merge(&mut self, other: Self) {
+        for (key, traits) in other.0 {
+            self.0.entry(key).or_default().extend(traits);
+        }
+    }
+
+    pub fn for_type(&self, crate_name: Option<&str>, ident: &str) -> BTreeSet<String> {
+        self.0
+            .get(&(crate_name.map(str::to_owned), ident.to_owned()))
+            .cloned()
+            .unwrap_or_default()
+    }
+}
+
+/// Types, trait impls, module declarations and names in scope collected from one or more files.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub(crate) struct Collected {
+    /// Types with paths relative to their file.
+    pub types: Vec<TypeInfo>,
+    pub impls: TraitImpls,
+    /// `mod foo;` declarations per file.
+    pub mods: BTreeMap<String, Vec<ModDecl>>,
+    /// Names declared or imported per file, relative to the file's module.
+    pub bindings: BTreeMap<String, Vec<Binding>>,
+    /// Names of the types passed to `register_type` or `register_type_data`.
+    pub registered: BTreeSet<String>,
+    /// The same registrations by instance, for telling apart `Foo<u32>` and `Foo<f32>`.
+    pub registered_instances: BTreeSet<Instance>,
+    /// Type data added along with registering a type, by the name of the type, from
+    /// registration methods like `register_asset_reflect`.
+    #[serde(default)]
+    pub registered_type_data: BTreeMap<String, BTreeSet<String>>,
+    /// Names of the types registered inside `impl Plugin` blocks.
+    pub plugin_registered: BTreeSet<String>,
+    /// Crates that register types inside `impl Plugin` blocks, `None` for the current project.
+    pub plugin_crates: BTreeSet<Option<String>>,
+    /// Concrete instances of generic types mentioned anywhere, e.g. `Foo<f32>` in
+    /// `Query<&Foo<f32>>`.
+    pub used_instances: BTreeSet<Instance>,
+    /// Names usable in `#[reflect(...)]`: `Foo` for every `ReflectFoo` type or `#[reflect_trait]`
+    /// trait `Foo`.
+    pub type_data: BTreeSet<String>,
+    /// Names of types that implement `Reflect` through macros like `impl_reflect!`.
+    pub macro_reflected: BTreeSet<String>,
+    /// Names of types that implement `TypePath` through `impl_type_path!`.
+    pub macro_type_path: BTreeSet<String>,
+    /// Names of the components that observers watch, as in `Trigger<OnAdd, Player>`, or whose
+    /// hooks are registered with `register_component_hooks`.
+    #[serde(default)]
+    pub observed: BTreeSet<String>,
+    /// Files that couldn't be parsed.
+    pub parse_errors: Vec<ParseError>,
+}
+
+impl Collected {
+    /// Combine the results of two collections, e.g. from different files or threads.
+    pub fn merge(mut self, other: Self) -> Self {
+        self.types.extend(other.types);
+        self.impls.merge(other.impls);
+        self.mods.extend(other.mods);
+        self.bindings.extend(other.bindings);
+        self.registered.extend(other.registered);
+        self.registered_instances.extend(other.registered_instances);
+        self.plugin_registered.extend(other.plugin_registered);
+        for (name, type_data) in other.registered_type_data {
+            self.registered_type_data
+                .entry(name)
+                .or_default()
+                .extend(type_data);
+        }
+        self.plugin_crates.extend(other.plugin_crates);
+        self.used_instances.extend(other.used_instances);
+        self.type_data.extend(other.type_data);
+        self.macro_reflected.extend(other.macro_reflected);
+        self.macro_type_path.extend(other.macro_type_path);
+        self.observed.extend(other.observed);
+        self.parse_errors.extend(other.parse_errors);
+        self
+    }
+
+    /// Recover the derives of the types in `crates`, whose sources had their macros expanded.
+    /// Expansion replaces derives with the impls they generate, so every implemented trait counts
+    /// as derived, but `Reflect` only along with the `GetTypeRegistration` impl of the derive.
+    pub fn derives_from_impls(&mut self, crates: &BTreeSet<Option<String>>) {
+        for ty in &mut self.types {
+            if !crates.contains(&ty.crate_name) {
+                continue;
+            }
+            let mut traits = self.impls.for_type(ty.crate_name.as_deref(), &ty.ident);
+            if !traits.contains("GetTypeRegistration") {
+                traits.remove("Reflect");
+            }
+            ty.attrs.derives.extend(traits);
+        }
+    }
+
+    /// Put the types into their modules using `placement`, which maps a file to its place in the
+    /// module tree (or `None` for files that aren't compiled). Types that are re-exported get
+    /// their public path, preferring the one they're defined at. Then attach the manual trait
+    /// impls, registrations, fields without `Reflect` and unknown type data to their types and
+    /// return the types that are visible at least as far as `min_visibility`.
+    pub fn finish(
+        self,
+        placement: impl Fn(&str) -> Option<Placement>,
+        min_visibility: Visibility,
+    ) -> Vec<TypeInfo> {
+        let Self {
+            types,
+            impls,
+            bindings,
+            registered,
+            registered_instances,
+            plugin_registered,
+            registered_type_data,
+            plugin_crates,
+            used_instances,
+            type_data,
+            macro_reflected,
+            macro_type_path,
+            observed,
+            ..
+        } = self;
+        let mut placements: HashMap<String, Option<Placement>> = HashMap::new();
+        let mut place = |file: &str| {
+            placements
+                .entry(file.to_string())
+                .or_insert_with(|| placement(file))
+                .clone()
+        };
+
+        let mut exports = Exports::default();
+        for (file, bindings) in &bindings {
+            if let Some(file_placement) = place(file) {
+                exports.add(&file_placement.module_path, bindings);
+            }
+        }
+        let public_paths = exports.public_paths();
+        let registered = registered_with_fields(registered, &types);
+        let plugin_registered = registered_with_fields(plugin_registered, &types);
+        let unregistered_instances =
+            unregistered_instances(registered_instances, &used_instances, &types, &registered);
+        let not_reflected = not_implementing(&types, &impls, "Reflect", &macro_reflected);
+        let no_default = not_implementing(&types, &impls, "Default", &BTreeSet::new());
+        let unreflected_components = unreflected_components(&types, &impls, &macro_reflected);
+
+        types
+            .into_iter()
+            .filter_map(|mut ty| {
+                let file_placement = place(&ty.location.file)?;
+                ty.location.type_path =
+                    format!("{}::{}", file_placement.module_path, ty.location.type_path);
+                ty.location.definition_path = ty.location.type_path.clone();
+                ty.visibility = ty.visibility.within(file_placement.visibility);
+                if let Some(paths) = public_paths.get(&ty.location.type_path) {
+                    ty.visibility = Visibility::Public;
+                    let mut paths = paths.clone();
+                    // Preludes are shortcuts, the module a type is grouped in is more telling
+                    paths.sort_by_key(|path| {
+                        let depth = path.matches("::").count();
+                        let in_prelude = path.split("::").any(|segment| segment == "prelude");
+                        (
+                            path != &ty.location.type_path,
+                            in_prelude,
+                            depth,
+                            path.clone(),
+                        )
+                    });
+                    paths.dedup();
+                    ty.location.type_path = paths.remove(0);
+                    ty.location.reexports = paths;
+                }
+                if ty.visibility < min_visibility {
+                    return None;
+                }
+                ty.manual_impls = impls.for_type(ty.crate_name.as_deref(), &ty.ident);
+                if macro_type_path.contains(&ty.ident) {
+                    ty.manual_impls.insert("TypePath".to_string());
+                }
+                ty.registered = registered.contains(&ty.ident);
+                ty.registered_type_data = registered_type_data
+                    .get(&ty.ident)
+                    .cloned()
+                    .unwrap_or_default();
+                ty.observed = observed.contains(&ty.ident)
+                    || ty
+                        .attrs
+                        .component
+                        .keys()
+                        .any(|option| option.starts_with("on_"));
+                ty.missing_plugin_registration = plugin_crates.contains(&ty.crate_name)
+                    && !plugin_registered.contains(&ty.ident);
+                if let Some(instances) = unregistered_instances.get(&ty.ident)
+                    && !ty.generics.is_empty()
+                {
+                    ty.unregistered_instances = instances.clone();
+                }
+                // `#[reflect(opaque)]` types are reflected as a whole, not field by field
+                if ty.attrs.derives("Reflect") && !ty.attrs.reflects("opaque") {
+                    ty.non_reflect_fields = ty
+                        .fields
+                        .iter()
+                        .filter(|field| !field.ignored)
+                        .filter_map(|field| {
+                            let name = field
+                                .type_names
+                                .iter()
+                                .find(|name| not_reflected.contains(*name))?;
+                            Some((field.name.clone(), name.clone()))
+                        })
+                        .collect();
+                }
+                // Scenes build components and resources through `FromReflect`, which takes
+                // ignored fields from `#[reflect(default)]`, or all fields from `#[reflect(Default)]`
+                if ty.attrs.derives("Reflect")
+                    && (ty.implements("Component") || ty.implements("Resource"))
+                    && !ty.attrs.reflects("opaque")
+                    && !ty.attrs.reflects("from_reflect")
+                    && !ty.attrs.reflects("Default")
+                {
+                    ty.ignored_without_default = ty
+                        .fields
+                        .iter()
+                        .filter(|field| field.ignored && !field.reflect_default)
+                        .map(|field| {
+                            let lacks_default = field
+                                .outer_type
+                                .as_ref()
+                                .is_some_and(|name| no_default.contains(name));
+                            (field.name.clone(), lacks_default)
+                        })
+                        .collect();
+                }
+                // A bundle is only as serializable as each of the components it inserts
+                if ty.attrs.derives("Bundle") {
+                    ty.unreflected_bundle_fields = ty
+                        .fields
+                        .iter()
+                        .filter(|field| !field.bundle_ignored)
+                        .filter_map(|field| {
+                            let name = field.outer_type.as_ref()?;
+                            let reflect = *unreflected_components.get(name)?;
+                            Some((field.name.clone(), (name.clone(), reflect)))
+                        })
+                        .collect();
+                }
+                // Spawning a component inserts its required components, which scenes then can't
+                // save or load
+                if ty.attrs.derives("Reflect") && ty.attrs.reflects("Component") {
+                    ty.unreflected_required = ty
+                        .attrs
+                        .required
+                        .iter()
+                        .filter_map(|name| {
+                            let reflect = *unreflected_components.get(name)?;
+                            Some((name.clone(), reflect))
+                        })
+                        .collect();
+                }
+                ty.unknown_type_data = unknown_type_data(&ty.attrs.reflect, &type_data);
+                Some(ty)
+            })
+            .collect()
+    }
+}
+
+// Registering a reflected type registers the types of its fields as well, recursively
+fn registered_with_fields(
+    mut registered: BTreeSet<String>,
+    types: &[TypeInfo],
+) -> BTreeSet<String> {
+    let mut fields: HashMap<&str, Vec<&FieldInfo>> = HashMap::new();
+    for ty in types.iter().filter(|ty| ty.attrs.derives("Reflect")) {
+        let reflected = ty.fields.iter().filter(|field| !field.ignored);
+        fields.entry(&ty.ident).or_default().extend(reflected);
+    }
+
+    let mut pending: Vec<String> = registered.iter().cloned().collect();
+    while let Some(name) = pending.pop() {
+        for field in fields.get(name.as_str()).into_iter().flatten() {
+            for type_name in &field.type_names {
+                if registered.insert(type_name.clone()) {
+                    pending.push(type_name.clone());
+                }
+            }
+        }
+    }
+    registered
+}
+
+// The used concrete instances of each generic type that aren't registered, directly or as part of
+// a registered type. Registering a type with generic arguments covers all of its instances.
+fn unregistered_instances(
+    mut registered_instances: BTreeSet<Instance>,
+    used_instances: &BTreeSet<Instance>,
+    types: &[TypeInfo],
+    registered: &BTreeSet<String>,
+) -> HashMap<String, BTreeSet<String>> {
+    for ty in types
+        .iter()
+        .filter(|ty| ty.attrs.derives("Reflect") && registered.contains(&ty.ident))
+    {
+        for field in ty.fields.iter().filter(|field| !field.ignored) {
+            registered_instances.extend(field.instances.iter().cloned());
+        }
+    }
+    let any_instance: HashSet<&str> = registered_instances
+        .iter()
+        .filter(|instance| instance.concrete.is_none())
+        .map(|instance| instance.name.as_str())
+        .collect();
+
+    let mut unregistered: HashMap<String, BTreeSet<String>> = HashMap::new();
+    for instance in used_instances.difference(&registered_instances) {
+        if let Some(concrete) = &instance.concrete
+            && !any_instance.contains(instance.name.as_str())
+        {
+            unregistered
+                .entry(instance.name.clone())
+                .or_default()
+                .insert(concrete.clone());
+        }
+    }
+    unregistered
+}
+
+// Names of the scanned types that don't implement `trait_name`, not counting those implemented
+// elsewhere. Types are matched by name, so a name only counts if none of the types carrying it
+// implement the trait.
+fn not_implementing(
+    types: &[TypeInfo],
+    impls: &TraitImpls,
+    trait_name: &str,
+    implemented_elsewhere: &BTreeSet<String>,
+) -> HashSet<String> {
+    let implementing: HashSet<&str> = types
+        .iter()
+        .filter(|ty| {
+            ty.attrs.derives(trait_name)
+                || impls
+                    .for_type(ty.crate_name.as_deref(), &ty.ident)
+                    .contains(trait_name)
+        })
+        .map(|ty| ty.ident.as_str())
+        .collect();
+    types
+        .iter()
+        .map(|ty| ty.ident.as_str())
+        .filter(|name| !implementing.contains(name) && !implemented_elsewhere.contains(*name))
+        .map(str::to_owned)
+        .collect()
+}
+
+// Components that either don't implement `Reflect` or derive it without `#[reflect(Component)]`,
+// with whether they implement `Reflect`. Names that are also used by a fully reflected component
+// are left out, as types are matched by name.
+fn unreflected_components(
+    types: &[TypeInfo],
+    impls: &TraitImpls,
+    macro_reflected: &BTreeSet<String>,
+) -> HashMap<String, bool> {
+    let mut unreflected = HashMap::new();
+    let mut reflected = HashSet::new();
+    for ty in types {
+        let manual_impls = impls.for_type(ty.crate_name.as_deref(), &ty.ident);
+        if !ty.attrs.derives("Component") && !manual_impls.contains("Component") {
+            continue;
+        }
+        let reflect = ty.attrs.derives("Reflect")
+            || manual_impls.contains("Reflect")
+            || macro_reflected.contains(&ty.ident);
+        // Hand-written `Reflect` impls register their type data in code
+        if reflect && (ty.attrs.reflects("Component") || !ty.attrs.derives("Reflect")) {
+            reflected.insert(ty.ident.as_str());
+        } else {
+            unreflected.insert(ty.ident.clone(), reflect);
+        }
+    }
+    unreflected.retain(|name, _| !reflected.contains(name.as_str()));
+    unreflected
+}
+
+// The entries of `reflect` that are neither built in nor defined in the scanned code, each with
+// a suggestion that's at most two edits away
+fn unknown_type_data(
+    reflect: &BTreeSet<String>,
+    type_data: &BTreeSet<String>,
+) -> BTreeMap<String, Option<String>> {
+    let known = || {
+        KNOWN_TYPE_DATA
+            .iter()
+            .copied()
+            .chain(type_data.iter().map(String::as_str))
+    };
+    reflect
+        .iter()
+        .filter(|name| !known().any(|known| known == name.as_str()))
+        .map(|name| {
+            let suggestion = known()
+                .map(|known| (edit_distance(name, known), known))
+                .filter(|(distance, _)| *distance <= 2)
+                .min()
+                .map(|(_, known)| known.to_string());
+            (name.clone(), suggestion)
+        })
+        .collect()
+}
+
+// Levenshtein distance between two names
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, a_char) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != *b_char);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
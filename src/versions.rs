@@ -0,0 +1,118 @@
+use crate::analyzer::relative_to_current_dir;
+use crate::checks::MIXED_BEVY_VERSIONS;
+use crate::finding::{CrateKind, Finding, Severity, Span};
+use cargo_metadata::{Metadata, Package, PackageId};
+use std::collections::BTreeMap;
+use std::fs;
+
+/// Crates whose types have to be the same for all of a Bevy app: a second version has its own
+/// `Reflect` and `Component` traits and its own `App`, so types of one aren't registered with the
+/// other.
+const SHARED_CRATES: &[&str] = &["bevy_reflect", "bevy_ecs"];
+
+/// A finding for each of [`SHARED_CRATES`] that cargo resolved to more than one version, naming
+/// the packages that depend on each. It points at the crate's first entry in `Cargo.lock`, or at
+/// the workspace manifest if there's no lock file.
+pub fn mixed_versions(metadata: &Metadata, severity: Severity) -> Vec<Finding> {
+    let mut by_name: BTreeMap<&str, Vec<&Package>> = BTreeMap::new();
+    for package in &metadata.packages {
+        if SHARED_CRATES.contains(&package.name.as_str()) {
+            by_name.entry(&package.name).or_default().push(package);
+        }
+    }
+
+    let lock_file = metadata.workspace_root.join("Cargo.lock");
+    let lock = fs::read_to_string(&lock_file).ok();
+    by_name
+        .into_iter()
+        .filter(|(_, packages)| packages.len() > 1)
+        .map(|(name, mut packages)| {
+            packages.sort_by(|a, b| a.version.cmp(&b.version));
+            let versions: Vec<String> = packages
+                .iter()
+                .map(|package| {
+                    let dependents = dependents(metadata, &package.id);
+                    if dependents.is_empty() {
+                        package.version.to_string()
+                    } else {
+                        format!("{} (from {})", package.version, dependents.join(", "))
+                    }
+                })
+                .collect();
+            let (file, span) = match lock.as_deref().and_then(|lock| lock_entry(lock, name)) {
+                Some(span) => (lock_file.clone(), span),
+                None => (
+                    metadata.workspace_root.join("Cargo.toml"),
+                    Span {
+                        line: 1,
+                        column: 1,
+                        end_line: 1,
+                        end_column: 1,
+                        start_byte: 0,
+                        end_byte: 0,
+                    },
+                ),
+            };
+            Finding {
+                check: MIXED_BEVY_VERSIONS.id,
+                severity,
+                message: format!(
+                    "`{name}` {}: {}",
+                    MIXED_BEVY_VERSIONS.problem,
+                    versions.join(", ")
+                ),
+                type_path: name.to_string(),
+                reexports: Vec::new(),
+                crate_kind: CrateKind::Workspace,
+                component_storage: None,
+                file: relative_to_current_dir(file.as_std_path())
+                    .to_string_lossy()
+                    .into_owned(),
+                span,
+                variant: None,
+                upstream: None,
+            }
+        })
+        .collect()
+}
+
+// The packages that depend on `id`, as `name version`
+fn dependents(metadata: &Metadata, id: &PackageId) -> Vec<String> {
+    let Some(resolve) = &metadata.resolve else {
+        return Vec::new();
+    };
+    let mut dependents: Vec<String> = resolve
+        .nodes
+        .iter()
+        .filter(|node| node.dependencies.contains(id))
+        .filter_map(|node| {
+            let package = metadata
+                .packages
+                .iter()
+                .find(|package| package.id == node.id)?;
+            Some(format!("{} {}", package.name, package.version))
+        })
+        .collect();
+    dependents.sort();
+    dependents
+}
+
+// The `name = "..."` line of the first `[[package]]` entry for `name`
+fn lock_entry(lock: &str, name: &str) -> Option<Span> {
+    let needle = format!("name = \"{name}\"");
+    let mut start_byte = 0;
+    for (index, line) in lock.lines().enumerate() {
+        if line == needle {
+            return Some(Span {
+                line: index + 1,
+                column: 1,
+                end_line: index + 1,
+                end_column: line.len() + 1,
+                start_byte,
+                end_byte: start_byte + line.len(),
+            });
+        }
+        start_byte += line.len() + 1;
+    }
+    None
+}
@@ -0,0 +1,49 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, SystemTime};
+use walkdir::WalkDir;
+
+/// How often the sources are checked for changes.
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Call `run` once, then again whenever a `.rs` file below `dirs` is added, removed or modified.
+/// Never returns; the user stops it with Ctrl+C.
+///
+/// Changes are found by polling modification times, which works the same on every platform.
+/// Re-running is cheap because the analysis cache only re-parses files whose content changed.
+pub fn watch(dirs: &[PathBuf], mut run: impl FnMut()) -> ! {
+    let mut last = snapshot(dirs);
+    run();
+    loop {
+        thread::sleep(POLL_INTERVAL);
+        let current = snapshot(dirs);
+        if current != last {
+            last = current;
+            run();
+        }
+    }
+}
+
+// Modification times of all source files, skipping build output and hidden directories
+fn snapshot(dirs: &[PathBuf]) -> BTreeMap<PathBuf, SystemTime> {
+    dirs.iter()
+        .flat_map(|dir| {
+            WalkDir::new(dir)
+                .into_iter()
+                .filter_entry(|entry| entry.depth() == 0 || !is_skipped(entry.path()))
+                .filter_map(Result::ok)
+        })
+        .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "rs"))
+        .filter_map(|entry| {
+            let modified = entry.metadata().ok()?.modified().ok()?;
+            Some((entry.into_path(), modified))
+        })
+        .collect()
+}
+
+fn is_skipped(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| name == "target" || name.starts_with('.'))
+}
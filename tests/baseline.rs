@@ -0,0 +1,51 @@
+use bevy_reflect_check::baseline::Baseline;
+use bevy_reflect_check::diff::{ReportDiff, ReportedFinding};
+use bevy_reflect_check::{AnalysisConfig, Analyzer, Finding};
+
+fn findings(dir: &str) -> Vec<Finding> {
+    let config = AnalysisConfig {
+        paths: vec![format!("tests/fixtures/baseline/{dir}")],
+        crate_name: Some("game".to_string()),
+        use_cache: false,
+        ..AnalysisConfig::default()
+    };
+    Analyzer::without_metadata(config)
+        .analyze()
+        .findings
+        .into_iter()
+        .filter(|finding| finding.check == "field-not-reflected")
+        .collect()
+}
+
+fn reported(findings: &[Finding]) -> Vec<ReportedFinding> {
+    serde_json::from_value(serde_json::to_value(findings).unwrap()).unwrap()
+}
+
+fn variants(findings: &[ReportedFinding]) -> Vec<Option<&str>> {
+    findings
+        .iter()
+        .map(|finding| finding.variant.as_deref())
+        .collect()
+}
+
+// A variant added to a baselined enum is a new finding, not one the baseline already covers
+#[test]
+fn new_variant_of_baselined_enum_is_reported() {
+    let before = findings("before");
+    let after = findings("after");
+    assert_eq!(before.len(), 1);
+    assert_eq!(after.len(), 2);
+
+    let baseline = Baseline::from_findings(&before);
+    let new: Vec<Option<&str>> = after
+        .iter()
+        .filter(|finding| !baseline.contains(finding))
+        .map(|finding| finding.variant.as_deref())
+        .collect();
+    assert_eq!(new, [Some("Attack")]);
+
+    let diff = ReportDiff::new(reported(&before), reported(&after));
+    assert_eq!(variants(&diff.added), [Some("Attack")]);
+    assert_eq!(variants(&diff.unchanged), [Some("Wait")]);
+    assert!(diff.removed.is_empty());
+}
@@ -0,0 +1,34 @@
+use bevy_reflect_check::cfg::Target;
+
+#[test]
+fn vendor_only_from_triples_that_have_one() {
+    let vendor = |triple| Target::from_triple(triple).vendor;
+    assert_eq!(vendor("x86_64-pc-windows-msvc"), "pc");
+    assert_eq!(vendor("aarch64-apple-darwin"), "apple");
+    assert_eq!(vendor("x86_64-unknown-linux-gnu"), "unknown");
+    assert_eq!(vendor("aarch64-linux-android"), "unknown");
+    assert_eq!(vendor("thumbv7em-none-eabihf"), "unknown");
+
+    let android = Target::from_triple("aarch64-linux-android");
+    assert_eq!(android.os, "android");
+}
+
+#[test]
+fn atomic_widths_follow_the_architecture() {
+    let widths = |triple| Target::from_triple(triple).atomic_widths;
+    assert_eq!(
+        widths("x86_64-unknown-linux-gnu"),
+        ["8", "16", "32", "64", "ptr"]
+    );
+    assert_eq!(
+        widths("wasm32-unknown-unknown"),
+        ["8", "16", "32", "64", "ptr"]
+    );
+    assert_eq!(widths("thumbv7em-none-eabihf"), ["8", "16", "32", "ptr"]);
+    assert!(widths("thumbv6m-none-eabi").is_empty());
+    assert!(widths("riscv32imc-unknown-none-elf").is_empty());
+    assert_eq!(
+        widths("riscv32imac-unknown-none-elf"),
+        ["8", "16", "32", "ptr"]
+    );
+}
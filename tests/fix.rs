@@ -0,0 +1,75 @@
+use bevy_reflect_check::fix::{self, FileFix};
+use bevy_reflect_check::{AnalysisConfig, Analyzer, Finding};
+use std::fs;
+
+const BEFORE: &str = "tests/fixtures/fix/before";
+const AFTER: &str = "tests/fixtures/fix/after";
+
+fn findings() -> Vec<Finding> {
+    let config = AnalysisConfig {
+        paths: vec![BEFORE.to_string()],
+        crate_name: Some("fixes".to_string()),
+        all_features: true,
+        use_cache: false,
+        ..AnalysisConfig::default()
+    };
+    Analyzer::without_metadata(config).analyze().findings
+}
+
+// The fixture in `before` with the fixes of all its findings applied, compared to the one in
+// `after`
+fn assert_fixed(name: &str) {
+    let fixes = fix::plan(&findings());
+    let file_fix = fixes
+        .iter()
+        .find(|file_fix| file_fix.path.ends_with(name))
+        .unwrap_or_else(|| panic!("no fix for {name}"));
+    let expected = fs::read_to_string(format!("{AFTER}/{name}")).unwrap();
+    assert_eq!(file_fix.fixed, expected);
+}
+
+#[test]
+fn extends_reflect_with_and_without_trailing_comma() {
+    assert_fixed("trailing_comma.rs");
+}
+
+#[test]
+fn adds_reflect_behind_the_cfg_attr_of_the_derive() {
+    assert_fixed("cfg_attr.rs");
+}
+
+#[test]
+fn finds_types_in_nested_inline_modules() {
+    assert_fixed("nested.rs");
+}
+
+#[test]
+fn inserts_at_byte_offsets_after_non_ascii_text() {
+    assert_fixed("non_ascii.rs");
+}
+
+#[test]
+fn adds_only_missing_state_entries() {
+    assert_fixed("state.rs");
+}
+
+// The language server's quick fixes plan a single finding at a time, which only touches its type
+#[test]
+fn fixes_a_single_finding() {
+    let findings = findings();
+    let finding = findings
+        .iter()
+        .find(|finding| finding.type_path.ends_with("::Health"))
+        .unwrap();
+    let fixes: Vec<FileFix> = fix::plan(std::slice::from_ref(finding));
+    assert_eq!(fixes.len(), 1);
+    assert!(fixes[0].covers(finding));
+
+    let original = fs::read_to_string(format!("{BEFORE}/trailing_comma.rs")).unwrap();
+    let expected = original.replacen(
+        "#[reflect(Component)]\npub struct Health",
+        "#[reflect(Component, Default)]\npub struct Health",
+        1,
+    );
+    assert_eq!(fixes[0].fixed, expected);
+}
@@ -0,0 +1,9 @@
+use bevy::prelude::*;
+
+pub struct Opaque;
+
+#[derive(Reflect)]
+pub enum Action {
+    Wait(Opaque),
+    Attack { target: Opaque },
+}
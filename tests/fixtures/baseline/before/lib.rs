@@ -0,0 +1,8 @@
+use bevy::prelude::*;
+
+pub struct Opaque;
+
+#[derive(Reflect)]
+pub enum Action {
+    Wait(Opaque),
+}
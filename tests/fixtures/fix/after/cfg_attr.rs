@@ -0,0 +1,6 @@
+use bevy::prelude::*;
+
+#[derive(Component)]
+#[cfg_attr(feature = "reflect", derive(Reflect))]
+#[cfg_attr(feature = "reflect", reflect(Component))]
+pub struct Velocity(Vec3);
@@ -0,0 +1,9 @@
+pub mod outer {
+    pub mod inner {
+        use bevy::prelude::*;
+
+        #[derive(Resource, Reflect)]
+        #[reflect(Resource)]
+        pub struct Score(u32);
+    }
+}
@@ -0,0 +1,13 @@
+use bevy::prelude::*;
+
+/// Größe des Spielers in Metern – „ungefähr“ 🦀
+pub const NAME: &str = "Spieler ✓ 名前";
+
+/// Läuft über die Straße 🚶
+#[derive(Component, Reflect, Default)]
+#[reflect(Component, Default)]
+pub struct Gehweg(f32);
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct Überholen;
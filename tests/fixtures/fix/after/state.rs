@@ -0,0 +1,8 @@
+use bevy::prelude::*;
+
+#[derive(States, Reflect, Default, Clone, PartialEq, Eq, Hash, Debug)]
+#[reflect(State, Default, FreelyMutableState)]
+pub enum GameState {
+    #[default]
+    Menu,
+}
@@ -0,0 +1,13 @@
+use bevy::prelude::*;
+
+#[derive(Component, Reflect, Default)]
+#[reflect(Component, Default)]
+pub struct Speed(f32);
+
+#[derive(Component, Reflect, Default)]
+#[reflect(Component, Default)]
+pub struct Health(u32);
+
+#[derive(Component, Reflect, Default)]
+#[reflect(Component, Default)]
+pub struct Armor;
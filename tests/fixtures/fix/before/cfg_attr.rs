@@ -0,0 +1,5 @@
+use bevy::prelude::*;
+
+#[derive(Component)]
+#[cfg_attr(feature = "reflect", derive(Reflect))]
+pub struct Velocity(Vec3);
@@ -0,0 +1,8 @@
+pub mod outer {
+    pub mod inner {
+        use bevy::prelude::*;
+
+        #[derive(Resource, Reflect)]
+        pub struct Score(u32);
+    }
+}
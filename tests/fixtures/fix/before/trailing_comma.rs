@@ -0,0 +1,13 @@
+use bevy::prelude::*;
+
+#[derive(Component, Reflect, Default)]
+#[reflect(Component,)]
+pub struct Speed(f32);
+
+#[derive(Component, Reflect, Default)]
+#[reflect(Component)]
+pub struct Health(u32);
+
+#[derive(Component, Reflect, Default)]
+#[reflect()]
+pub struct Armor;
@@ -0,0 +1,12 @@
+use bevy::prelude::*;
+
+#[derive(Component, Reflect)]
+#[cfg_attr(feature = "serde" derive(Serialize))]
+pub struct MissingComma;
+
+#[derive(Component)]
+#[cfg_attr(all(), derive(Reflect = "odd"))]
+pub struct OddDerive;
+
+#[derive(Component, Reflect)]
+pub struct Fine;
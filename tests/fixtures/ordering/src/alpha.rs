@@ -0,0 +1,7 @@
+use bevy::prelude::*;
+
+#[derive(Component, Reflect)]
+pub struct Omega;
+
+#[derive(Component, Reflect, Default)]
+pub struct Beta;
@@ -0,0 +1,7 @@
+use bevy::prelude::*;
+
+#[derive(Resource, Reflect)]
+pub struct Zeta;
+
+#[derive(Component, Reflect, Default)]
+pub struct Eta;
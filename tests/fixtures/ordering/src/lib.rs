@@ -0,0 +1,7 @@
+use bevy::prelude::*;
+
+pub mod beta;
+pub mod alpha;
+
+#[derive(Component, Reflect, Default)]
+pub struct Root;
@@ -0,0 +1 @@
+pub use bevy_skim::Health;
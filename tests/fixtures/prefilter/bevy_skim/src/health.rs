@@ -0,0 +1,2 @@
+#[derive(Component, Reflect)]
+pub struct Health(pub f32);
@@ -0,0 +1,15 @@
+//! Nothing in here is worth parsing, and the body below doesn't even parse. Only the module
+//! declarations, the re-export and the impl header are picked out.
+
+pub mod health;
+#[cfg(feature = "never")]
+mod gone;
+
+pub use health::Health;
+
+impl Default for Health {
+    fn default() -> Self {
+        let broken = ;
+        Health(broken)
+    }
+}
@@ -0,0 +1,7 @@
+use bevy::prelude::*;
+
+#[derive(Component, Reflect, Default)]
+#[reflect(Component, Default)]
+pub struct Player {
+    pub lives: u32,
+}
@@ -0,0 +1,13 @@
+use bevy::prelude::*;
+
+mod components;
+
+pub use components::*;
+
+pub struct GamePlugin;
+
+impl Plugin for GamePlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Player>();
+    }
+}
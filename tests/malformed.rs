@@ -0,0 +1,30 @@
+use bevy_reflect_check::{AnalysisConfig, Analyzer};
+
+// Attributes that don't parse the way they're expected to are skipped instead of ending the run.
+// A derive list that fails partway still counts the derives before the error, like a top-level
+// `derive` does.
+#[test]
+fn malformed_cfg_attr_is_skipped() {
+    let config = AnalysisConfig {
+        paths: vec!["tests/fixtures/malformed".to_string()],
+        crate_name: Some("malformed".to_string()),
+        use_cache: false,
+        ..AnalysisConfig::default()
+    };
+    let analysis = Analyzer::without_metadata(config).analyze();
+    let mut types: Vec<&str> = analysis
+        .findings
+        .iter()
+        .filter(|finding| finding.check == "missing-reflect-component")
+        .map(|finding| finding.type_path.as_str())
+        .collect();
+    types.sort();
+    assert_eq!(
+        types,
+        [
+            "malformed::Fine",
+            "malformed::MissingComma",
+            "malformed::OddDerive"
+        ]
+    );
+}
@@ -0,0 +1,33 @@
+use bevy_reflect_check::{AnalysisConfig, Analyzer};
+
+// Findings come out sorted by crate, file, position and type, whatever order the files are
+// parsed and the checks run in
+#[test]
+fn findings_are_sorted() {
+    let config = AnalysisConfig {
+        paths: vec!["tests/fixtures/ordering/src".to_string()],
+        crate_name: Some("ordering".to_string()),
+        use_cache: false,
+        ..AnalysisConfig::default()
+    };
+    let analysis = Analyzer::without_metadata(config).analyze();
+    let findings: Vec<(&str, &str)> = analysis
+        .findings
+        .iter()
+        .map(|finding| (finding.type_path.as_str(), finding.check))
+        .collect();
+
+    assert_eq!(
+        findings,
+        [
+            ("ordering::alpha::Omega", "missing-reflect-component"),
+            ("ordering::alpha::Beta", "missing-reflect-component"),
+            ("ordering::alpha::Beta", "missing-reflect-default"),
+            ("ordering::beta::Zeta", "missing-reflect-resource"),
+            ("ordering::beta::Eta", "missing-reflect-component"),
+            ("ordering::beta::Eta", "missing-reflect-default"),
+            ("ordering::Root", "missing-reflect-component"),
+            ("ordering::Root", "missing-reflect-default"),
+        ]
+    );
+}
@@ -0,0 +1,37 @@
+use bevy_reflect_check::{AnalysisConfig, Analyzer};
+use cargo_metadata::MetadataCommand;
+
+// `bevy_skim/src/lib.rs` mentions nothing the checks look at, so it's skipped without being
+// parsed, which its syntax error shows. Its module declarations, re-export and impl still count.
+#[test]
+fn irrelevant_dependency_file_is_outlined() {
+    let metadata = MetadataCommand::new()
+        .manifest_path("tests/fixtures/prefilter/app/Cargo.toml")
+        .other_options(vec!["--offline".to_string()])
+        .exec()
+        .unwrap();
+    let config = AnalysisConfig {
+        use_cache: false,
+        ..AnalysisConfig::default()
+    };
+    let analysis = Analyzer::new(config, metadata).analyze();
+    assert!(
+        analysis.parse_errors.is_empty(),
+        "{:?}",
+        analysis.parse_errors
+    );
+
+    let types: Vec<_> = analysis
+        .types
+        .iter()
+        .filter(|ty| ty.crate_name.as_deref() == Some("bevy_skim"))
+        .collect();
+    assert_eq!(types.len(), 1, "the disabled module is left out");
+    let health = types[0];
+    assert_eq!(health.location.type_path, "bevy_skim::health::Health");
+    assert_eq!(
+        health.location.reexports,
+        ["app::Health", "bevy_skim::Health"]
+    );
+    assert!(health.manual_impls.contains("Default"));
+}
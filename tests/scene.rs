@@ -0,0 +1,32 @@
+use bevy_reflect_check::scene::{check_scene, parse_scene};
+use bevy_reflect_check::{AnalysisConfig, Analyzer, Severity};
+use std::fs;
+
+// Bevy writes scenes with the path a type is defined at, which for a type re-exported from a
+// private module isn't the public path the findings use
+#[test]
+fn reexported_types_are_found_by_definition_path() {
+    let config = AnalysisConfig {
+        paths: vec!["tests/fixtures/scene/src".to_string()],
+        crate_name: Some("game".to_string()),
+        use_cache: false,
+        ..AnalysisConfig::default()
+    };
+    let analysis = Analyzer::without_metadata(config).analyze();
+    let player = analysis
+        .types
+        .iter()
+        .find(|ty| ty.ident == "Player")
+        .unwrap();
+    assert_eq!(player.location.type_path, "game::Player");
+    assert_eq!(player.location.definition_path, "game::components::Player");
+
+    let file = "tests/fixtures/scene/assets/level.scn.ron";
+    let entries = parse_scene(file, &fs::read_to_string(file).unwrap()).unwrap();
+    let findings = check_scene(file, &entries, &analysis.types, Severity::Error);
+    let types: Vec<&str> = findings
+        .iter()
+        .map(|finding| finding.type_path.as_str())
+        .collect();
+    assert_eq!(types, ["game::components::Enemy"]);
+}